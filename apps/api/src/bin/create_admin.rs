@@ -0,0 +1,35 @@
+//! Create an operator account for the admin API.
+//!
+//! Usage: `ADMIN_PASSWORD=... cargo run -p api --bin create_admin -- <username>`
+//! The password is read from stdin when ADMIN_PASSWORD is unset.
+
+use api::config::Config;
+use application::admin::CreateAdminUseCase;
+use std::io::BufRead;
+
+#[actix_web::main]
+async fn main() -> anyhow::Result<()> {
+    let username = std::env::args()
+        .nth(1)
+        .ok_or_else(|| anyhow::anyhow!("usage: create_admin <username>"))?;
+
+    let password = match std::env::var("ADMIN_PASSWORD") {
+        Ok(password) => password,
+        Err(_) => {
+            eprintln!("Password for {}:", username);
+            let mut line = String::new();
+            std::io::stdin().lock().read_line(&mut line)?;
+            line.trim_end_matches(['\r', '\n']).to_string()
+        }
+    };
+
+    let config = Config::from_env()?;
+    let db = sea_orm::Database::connect(config.database_url()).await?;
+
+    let admin = CreateAdminUseCase::execute(&db, &username, &password)
+        .await
+        .map_err(|e| anyhow::anyhow!(e.to_string()))?;
+    println!("Created admin {} ({})", admin.username, admin.admin_id);
+
+    Ok(())
+}
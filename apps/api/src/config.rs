@@ -1,3 +1,7 @@
+use application::auth::phone::{PhoneHasher, PhonePolicy};
+use application::transparency::LogSigner;
+use infrastructure::storage::BlobStoreConfig;
+
 #[derive(Clone)]
 pub struct Config {
     // Database URLs
@@ -11,6 +15,44 @@ pub struct Config {
     pub jwt_secret: String,
     pub jwt_expiration: i64,
     pub refresh_token_expiration: i64,
+    /// Signs admin API tokens. The admin API is disabled when unset.
+    pub admin_jwt_secret: Option<String>,
+
+    // PIN Configuration
+    /// Cooling-off period before a forgot-PIN request clears the registration lock
+    pub pin_reset_cooldown_seconds: i64,
+
+    // Account deletion
+    /// Time a deleted account can still be restored by logging in again
+    pub account_deletion_grace_seconds: i64,
+    /// Signs data export download links; kept apart from JWT_SECRET so a leaked
+    /// link key cannot mint access tokens and rotating one leaves the other alone
+    pub data_export_signing_secret: String,
+    /// How often the purge job looks for accounts past their grace period
+    pub account_purge_interval_seconds: u64,
+
+    // Signal keys
+    /// Age after which a device's signed prekey is due for rotation
+    pub signed_prekey_rotation_seconds: i64,
+    /// How long a replaced signed prekey is kept after rotation
+    pub signed_prekey_grace_seconds: i64,
+    /// Signs key transparency tree heads. KEY_TRANSPARENCY_SIGNING_KEY is
+    /// required unless KEY_TRANSPARENCY_ALLOW_EPHEMERAL_KEY=true, which
+    /// generates a throwaway key for development.
+    pub key_transparency_signer: LogSigner,
+
+    // Phone number policy (per-country allow/deny lists and OTP limits)
+    pub phone_policy: PhonePolicy,
+
+    // Contact discovery
+    /// Keys `users.phone_number_hash` with HMAC when CONTACT_DISCOVERY_HMAC_KEY is set.
+    /// Existing users are re-hashed at startup after the key changes.
+    pub phone_hasher: PhoneHasher,
+
+    // Media storage
+    pub blob_store: BlobStoreConfig,
+    /// Externally reachable base URL, used to build media URLs stored in profiles
+    pub public_base_url: String,
     
     // Server Configuration
     pub server_host: String,
@@ -21,6 +63,29 @@ impl Config {
     pub fn from_env() -> anyhow::Result<Self> {
         dotenvy::dotenv().ok();
 
+        let jwt_secret = std::env::var("JWT_SECRET")?;
+        let admin_jwt_secret = std::env::var("ADMIN_JWT_SECRET").ok().filter(|s| !s.is_empty());
+        // A shared secret would let user tokens be forged into admin tokens and back
+        if admin_jwt_secret.as_deref() == Some(jwt_secret.as_str()) {
+            anyhow::bail!("ADMIN_JWT_SECRET must differ from JWT_SECRET");
+        }
+        let data_export_signing_secret = std::env::var("DATA_EXPORT_SIGNING_SECRET")
+            .map_err(|_| anyhow::anyhow!("DATA_EXPORT_SIGNING_SECRET must be set"))?;
+        if data_export_signing_secret == jwt_secret {
+            anyhow::bail!("DATA_EXPORT_SIGNING_SECRET must differ from JWT_SECRET");
+        }
+        // A key regenerated on restart leaves clients unable to check earlier tree heads
+        let key_transparency_signing_key = std::env::var("KEY_TRANSPARENCY_SIGNING_KEY")
+            .ok()
+            .filter(|k| !k.is_empty());
+        let allow_ephemeral_key =
+            std::env::var("KEY_TRANSPARENCY_ALLOW_EPHEMERAL_KEY").is_ok_and(|v| v == "true");
+        if key_transparency_signing_key.is_none() && !allow_ephemeral_key {
+            anyhow::bail!(
+                "KEY_TRANSPARENCY_SIGNING_KEY must be set (KEY_TRANSPARENCY_ALLOW_EPHEMERAL_KEY=true allows a throwaway key in development)"
+            );
+        }
+
         Ok(Self {
             // Support both DATABASE_URL (legacy) and POSTGRES_URL
             postgres_url: std::env::var("POSTGRES_URL")
@@ -30,13 +95,54 @@ impl Config {
                 .unwrap_or_else(|_| "redis://localhost:6379".to_string()),
             scylladb_url: std::env::var("SCYLLADB_URL").ok(),
             
-            jwt_secret: std::env::var("JWT_SECRET")?,
+            jwt_secret,
             jwt_expiration: std::env::var("JWT_EXPIRATION")
                 .unwrap_or_else(|_| "3600".to_string())
                 .parse()?,
             refresh_token_expiration: std::env::var("REFRESH_TOKEN_EXPIRATION")
                 .unwrap_or_else(|_| "604800".to_string())
                 .parse()?,
+            admin_jwt_secret,
+            pin_reset_cooldown_seconds: std::env::var("PIN_RESET_COOLDOWN_SECONDS")
+                .unwrap_or_else(|_| "604800".to_string()) // 7 days
+                .parse()?,
+            account_deletion_grace_seconds: std::env::var("ACCOUNT_DELETION_GRACE_SECONDS")
+                .unwrap_or_else(|_| "2592000".to_string()) // 30 days
+                .parse()?,
+            data_export_signing_secret,
+            account_purge_interval_seconds: std::env::var("ACCOUNT_PURGE_INTERVAL_SECONDS")
+                .unwrap_or_else(|_| "3600".to_string())
+                .parse()?,
+            signed_prekey_rotation_seconds: std::env::var("SIGNED_PREKEY_ROTATION_SECONDS")
+                .unwrap_or_else(|_| "604800".to_string()) // 7 days
+                .parse()?,
+            signed_prekey_grace_seconds: std::env::var("SIGNED_PREKEY_GRACE_SECONDS")
+                .unwrap_or_else(|_| "2592000".to_string()) // 30 days
+                .parse()?,
+            key_transparency_signer: LogSigner::new(key_transparency_signing_key.as_deref())
+                .map_err(anyhow::Error::msg)?,
+            phone_policy: PhonePolicy::parse(
+                &std::env::var("PHONE_ALLOWED_COUNTRIES").unwrap_or_default(),
+                &std::env::var("PHONE_DENIED_COUNTRIES").unwrap_or_default(),
+                &std::env::var("PHONE_OTP_LIMITS").unwrap_or_default(),
+            )
+            .map_err(anyhow::Error::msg)?,
+            phone_hasher: PhoneHasher::new(
+                std::env::var("CONTACT_DISCOVERY_HMAC_KEY").ok().as_deref(),
+            ),
+            blob_store: match std::env::var("BLOB_STORE").as_deref() {
+                Ok("s3") => BlobStoreConfig::S3 {
+                    bucket: std::env::var("S3_BUCKET")?,
+                    region: std::env::var("S3_REGION").ok(),
+                    endpoint: std::env::var("S3_ENDPOINT").ok(),
+                },
+                _ => BlobStoreConfig::Local {
+                    root: std::env::var("BLOB_STORE_PATH")
+                        .unwrap_or_else(|_| "./data/blobs".to_string()),
+                },
+            },
+            public_base_url: std::env::var("PUBLIC_BASE_URL")
+                .unwrap_or_else(|_| "http://localhost:8000".to_string()),
             server_host: std::env::var("SERVER_HOST")
                 .unwrap_or_else(|_| "0.0.0.0".to_string()),
             server_port: std::env::var("SERVER_PORT")
@@ -0,0 +1,218 @@
+use crate::config::Config;
+use crate::handlers::auth::{extract_auth_claims, unauthorized};
+use crate::handlers::error_handler::app_error_to_response;
+use crate::handlers::users::{notify_contacts, notify_profile_updated};
+use crate::websocket::connection::ConnectionManager;
+use crate::websocket::messages::WsMessage;
+use actix_web::http::header;
+use actix_web::{delete, get, post, web, HttpRequest, HttpResponse, Responder};
+use application::account::dtos::DownloadDataExportQuery;
+use application::account::{
+    DeleteAccountUseCase, DownloadDataExportUseCase, GetDataExportUseCase,
+    RequestDataExportUseCase,
+};
+use application::auth::dtos::{ChangeNumberRequest, ConfirmChangeNumberRequest, RequestOtpResponse};
+use application::auth::use_cases::AuthConfig;
+use application::auth::{ChangeNumberUseCase, RequestChangeNumberUseCase};
+use infrastructure::storage::BlobStore;
+use redis::aio::MultiplexedConnection;
+use sea_orm::DatabaseConnection;
+use tracing::info;
+use uuid::Uuid;
+
+// ============ Account Deletion ============
+
+#[delete("")]
+pub async fn delete_account(
+    http_req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    config: web::Data<Config>,
+    manager: web::Data<ConnectionManager>,
+) -> impl Responder {
+    let (user_id, device_id) = match extract_auth_claims(&http_req) {
+        Some(claims) => claims,
+        None => return unauthorized(),
+    };
+
+    match DeleteAccountUseCase::execute(
+        db.get_ref(),
+        user_id,
+        device_id,
+        config.account_deletion_grace_seconds,
+    )
+    .await
+    {
+        Ok(response) => {
+            // Contacts drop the profile, other devices learn they were signed out
+            notify_profile_updated(db.get_ref(), &manager, user_id, device_id).await;
+            HttpResponse::Ok().json(response)
+        }
+        Err(e) => app_error_to_response(e),
+    }
+}
+
+// ============ Data Export ============
+
+#[post("/export")]
+pub async fn request_data_export(
+    http_req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    redis_conn: web::Data<MultiplexedConnection>,
+    blob_store: web::Data<BlobStore>,
+) -> impl Responder {
+    let (user_id, _) = match extract_auth_claims(&http_req) {
+        Some(claims) => claims,
+        None => return unauthorized(),
+    };
+
+    let mut conn = redis_conn.get_ref().clone();
+
+    match RequestDataExportUseCase::execute(db.get_ref(), &mut conn, blob_store.get_ref(), user_id)
+        .await
+    {
+        Ok(response) => HttpResponse::Accepted().json(response),
+        Err(e) => app_error_to_response(e),
+    }
+}
+
+#[get("/export/{export_id}")]
+pub async fn get_data_export(
+    http_req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    config: web::Data<Config>,
+    path: web::Path<Uuid>,
+) -> impl Responder {
+    let (user_id, _) = match extract_auth_claims(&http_req) {
+        Some(claims) => claims,
+        None => return unauthorized(),
+    };
+
+    match GetDataExportUseCase::execute(
+        db.get_ref(),
+        &config.data_export_signing_secret,
+        &config.public_base_url,
+        user_id,
+        path.into_inner(),
+    )
+    .await
+    {
+        Ok(response) => HttpResponse::Ok().json(response),
+        Err(e) => app_error_to_response(e),
+    }
+}
+
+/// Authorised by the signed query string rather than a bearer token
+#[get("/export/{export_id}/download")]
+pub async fn download_data_export(
+    db: web::Data<DatabaseConnection>,
+    blob_store: web::Data<BlobStore>,
+    config: web::Data<Config>,
+    path: web::Path<Uuid>,
+    query: web::Query<DownloadDataExportQuery>,
+) -> impl Responder {
+    let export_id = path.into_inner();
+
+    match DownloadDataExportUseCase::execute(
+        db.get_ref(),
+        blob_store.get_ref(),
+        &config.data_export_signing_secret,
+        export_id,
+        query.into_inner(),
+    )
+    .await
+    {
+        Ok(archive) => HttpResponse::Ok()
+            .content_type("application/zip")
+            .insert_header((
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"vyry-export-{}.zip\"", export_id),
+            ))
+            .insert_header((header::CACHE_CONTROL, "no-store"))
+            .body(archive),
+        Err(e) => app_error_to_response(e),
+    }
+}
+
+// ============ Change Number ============
+
+#[post("/change-number")]
+pub async fn request_change_number(
+    http_req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    redis_conn: web::Data<MultiplexedConnection>,
+    config: web::Data<Config>,
+    req: web::Json<ChangeNumberRequest>,
+) -> impl Responder {
+    let (user_id, device_id) = match extract_auth_claims(&http_req) {
+        Some(claims) => claims,
+        None => return unauthorized(),
+    };
+
+    let mut conn = redis_conn.get_ref().clone();
+
+    match RequestChangeNumberUseCase::execute(
+        db.get_ref(),
+        &mut conn,
+        &config.phone_policy,
+        user_id,
+        device_id,
+        req.into_inner(),
+    )
+    .await
+    {
+        Ok(otp) => {
+            info!("Change number OTP generated for testing: {}", otp);
+            HttpResponse::Ok().json(RequestOtpResponse {
+                message: "OTP sent successfully".to_string(),
+                expires_in_seconds: application::auth::otp::OTP_EXPIRY_SECONDS,
+            })
+        }
+        Err(e) => app_error_to_response(e),
+    }
+}
+
+#[post("/change-number/confirm")]
+pub async fn confirm_change_number(
+    http_req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    redis_conn: web::Data<MultiplexedConnection>,
+    config: web::Data<Config>,
+    manager: web::Data<ConnectionManager>,
+    req: web::Json<ConfirmChangeNumberRequest>,
+) -> impl Responder {
+    let (user_id, device_id) = match extract_auth_claims(&http_req) {
+        Some(claims) => claims,
+        None => return unauthorized(),
+    };
+
+    let mut conn = redis_conn.get_ref().clone();
+
+    let auth_config = AuthConfig {
+        jwt_secret: config.jwt_secret.clone(),
+        jwt_expiration: config.jwt_expiration,
+        refresh_token_expiration: config.refresh_token_expiration,
+        phone_hasher: config.phone_hasher.clone(),
+    };
+
+    match ChangeNumberUseCase::execute(
+        db.get_ref(),
+        &mut conn,
+        &auth_config,
+        &config.phone_policy,
+        user_id,
+        device_id,
+        req.into_inner(),
+    )
+    .await
+    {
+        Ok(response) => {
+            let msg = WsMessage::NumberChanged {
+                user_id,
+                identity_key_changed: false,
+            };
+            notify_contacts(db.get_ref(), &manager, user_id, device_id, &msg).await;
+            HttpResponse::Ok().json(response)
+        }
+        Err(e) => app_error_to_response(e),
+    }
+}
@@ -0,0 +1,278 @@
+use crate::config::Config;
+use crate::handlers::auth::unauthorized;
+use crate::handlers::error_handler::app_error_to_response;
+use crate::middleware::admin_auth::AdminIdentity;
+use crate::websocket::connection::ConnectionManager;
+use crate::websocket::messages::WsMessage;
+use actix_web::{delete, get, post, web, HttpMessage, HttpRequest, HttpResponse, Responder};
+use application::admin::dtos::{AdminLoginRequest, ListAuditLogsQuery, LookupUserQuery};
+use application::admin::{
+    AdminClearRateLimitsUseCase, AdminGetUserUseCase, AdminKeyMetricsUseCase,
+    AdminListAuditLogsUseCase, AdminListDevicesUseCase, AdminLoginUseCase, AdminLookupUserUseCase,
+    AdminUnlinkDeviceUseCase,
+};
+use application::reports::dtos::{ListReportsQuery, ResolveReportRequest};
+use application::reports::{GetReportUseCase, ListReportsUseCase, ResolveReportUseCase};
+use core::entities::moderation_actions::ModerationActionType;
+use redis::aio::MultiplexedConnection;
+use sea_orm::DatabaseConnection;
+use uuid::Uuid;
+
+/// Admin ID set by `AdminAuthMiddleware`
+fn extract_admin_id(req: &HttpRequest) -> Option<Uuid> {
+    req.extensions().get::<AdminIdentity>().map(|identity| identity.admin_id)
+}
+
+// ============ Admin Auth ============
+
+#[post("/login")]
+pub async fn admin_login(
+    db: web::Data<DatabaseConnection>,
+    redis_conn: web::Data<MultiplexedConnection>,
+    config: web::Data<Config>,
+    req: web::Json<AdminLoginRequest>,
+) -> impl Responder {
+    // Without a secret the admin API does not exist
+    let Some(secret) = config.admin_jwt_secret.as_deref() else {
+        return HttpResponse::NotFound().finish();
+    };
+
+    let mut conn = redis_conn.get_ref().clone();
+    match AdminLoginUseCase::execute(db.get_ref(), &mut conn, secret, req.into_inner()).await {
+        Ok(response) => HttpResponse::Ok().json(response),
+        Err(e) => app_error_to_response(e),
+    }
+}
+
+// ============ Moderation Queue ============
+
+#[get("/reports")]
+pub async fn list_reports(
+    http_req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    query: web::Query<ListReportsQuery>,
+) -> impl Responder {
+    if extract_admin_id(&http_req).is_none() {
+        return unauthorized();
+    }
+
+    match ListReportsUseCase::execute(db.get_ref(), query.into_inner()).await {
+        Ok(response) => HttpResponse::Ok().json(response),
+        Err(e) => app_error_to_response(e),
+    }
+}
+
+#[get("/reports/{report_id}")]
+pub async fn get_report(
+    http_req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<Uuid>,
+) -> impl Responder {
+    let admin_id = match extract_admin_id(&http_req) {
+        Some(admin_id) => admin_id,
+        None => return unauthorized(),
+    };
+
+    match GetReportUseCase::execute(db.get_ref(), admin_id, path.into_inner()).await {
+        Ok(response) => HttpResponse::Ok().json(response),
+        Err(e) => app_error_to_response(e),
+    }
+}
+
+#[post("/reports/{report_id}/resolve")]
+pub async fn resolve_report(
+    http_req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    manager: web::Data<ConnectionManager>,
+    path: web::Path<Uuid>,
+    req: web::Json<ResolveReportRequest>,
+) -> impl Responder {
+    let admin_id = match extract_admin_id(&http_req) {
+        Some(admin_id) => admin_id,
+        None => return unauthorized(),
+    };
+
+    match ResolveReportUseCase::execute(db.get_ref(), admin_id, path.into_inner(), req.into_inner())
+        .await
+    {
+        Ok(response) => {
+            if let Some(action) = &response.action {
+                let msg = WsMessage::AccountModerated {
+                    action: action.action,
+                    reason: action.reason.clone(),
+                    expires_at: action.expires_at.map(|t| t.timestamp()),
+                };
+                manager.send_to_user(&action.user_id, None, &msg).await;
+
+                // Revoked devices must not keep an open socket
+                if action.action != ModerationActionType::Warn {
+                    manager.disconnect_user(&action.user_id).await;
+                }
+            }
+            HttpResponse::Ok().json(response)
+        }
+        Err(e) => app_error_to_response(e),
+    }
+}
+
+// ============ Users and Devices ============
+
+#[get("/users")]
+pub async fn lookup_user(
+    http_req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    config: web::Data<Config>,
+    query: web::Query<LookupUserQuery>,
+) -> impl Responder {
+    let admin_id = match extract_admin_id(&http_req) {
+        Some(admin_id) => admin_id,
+        None => return unauthorized(),
+    };
+
+    match AdminLookupUserUseCase::execute(
+        db.get_ref(),
+        &config.phone_hasher,
+        admin_id,
+        query.into_inner(),
+    )
+    .await
+    {
+        Ok(response) => HttpResponse::Ok().json(response),
+        Err(e) => app_error_to_response(e),
+    }
+}
+
+#[get("/users/{user_id}")]
+pub async fn get_user(
+    http_req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<Uuid>,
+) -> impl Responder {
+    let admin_id = match extract_admin_id(&http_req) {
+        Some(admin_id) => admin_id,
+        None => return unauthorized(),
+    };
+
+    match AdminGetUserUseCase::execute(db.get_ref(), admin_id, path.into_inner()).await {
+        Ok(response) => HttpResponse::Ok().json(response),
+        Err(e) => app_error_to_response(e),
+    }
+}
+
+#[get("/users/{user_id}/devices")]
+pub async fn list_user_devices(
+    http_req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    config: web::Data<Config>,
+    path: web::Path<Uuid>,
+) -> impl Responder {
+    let admin_id = match extract_admin_id(&http_req) {
+        Some(admin_id) => admin_id,
+        None => return unauthorized(),
+    };
+
+    match AdminListDevicesUseCase::execute(
+        db.get_ref(),
+        admin_id,
+        path.into_inner(),
+        config.signed_prekey_rotation_seconds,
+    )
+    .await
+    {
+        Ok(response) => HttpResponse::Ok().json(response),
+        Err(e) => app_error_to_response(e),
+    }
+}
+
+#[delete("/users/{user_id}/devices/{device_id}")]
+pub async fn unlink_user_device(
+    http_req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    manager: web::Data<ConnectionManager>,
+    path: web::Path<(Uuid, i64)>,
+) -> impl Responder {
+    let admin_id = match extract_admin_id(&http_req) {
+        Some(admin_id) => admin_id,
+        None => return unauthorized(),
+    };
+
+    let (user_id, device_id) = path.into_inner();
+    match AdminUnlinkDeviceUseCase::execute(db.get_ref(), admin_id, user_id, device_id).await {
+        Ok(response) => {
+            manager.disconnect_device(&user_id, device_id).await;
+            HttpResponse::Ok().json(response)
+        }
+        Err(e) => app_error_to_response(e),
+    }
+}
+
+#[delete("/users/{user_id}/rate-limits")]
+pub async fn clear_user_rate_limits(
+    http_req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    redis_conn: web::Data<MultiplexedConnection>,
+    path: web::Path<Uuid>,
+) -> impl Responder {
+    let admin_id = match extract_admin_id(&http_req) {
+        Some(admin_id) => admin_id,
+        None => return unauthorized(),
+    };
+
+    let mut conn = redis_conn.get_ref().clone();
+    match AdminClearRateLimitsUseCase::execute(db.get_ref(), &mut conn, admin_id, path.into_inner())
+        .await
+    {
+        Ok(response) => HttpResponse::Ok().json(response),
+        Err(e) => app_error_to_response(e),
+    }
+}
+
+#[get("/users/{user_id}/audit-logs")]
+pub async fn list_user_audit_logs(
+    http_req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<Uuid>,
+    query: web::Query<ListAuditLogsQuery>,
+) -> impl Responder {
+    let admin_id = match extract_admin_id(&http_req) {
+        Some(admin_id) => admin_id,
+        None => return unauthorized(),
+    };
+
+    match AdminListAuditLogsUseCase::execute(
+        db.get_ref(),
+        admin_id,
+        path.into_inner(),
+        query.into_inner(),
+    )
+    .await
+    {
+        Ok(response) => HttpResponse::Ok().json(response),
+        Err(e) => app_error_to_response(e),
+    }
+}
+
+// ============ Metrics ============
+
+#[get("/metrics/keys")]
+pub async fn get_key_metrics(
+    http_req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    config: web::Data<Config>,
+) -> impl Responder {
+    let admin_id = match extract_admin_id(&http_req) {
+        Some(admin_id) => admin_id,
+        None => return unauthorized(),
+    };
+
+    match AdminKeyMetricsUseCase::execute(
+        db.get_ref(),
+        admin_id,
+        config.signed_prekey_rotation_seconds,
+    )
+    .await
+    {
+        Ok(response) => HttpResponse::Ok().json(response),
+        Err(e) => app_error_to_response(e),
+    }
+}
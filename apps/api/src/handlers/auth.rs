@@ -1,5 +1,8 @@
 use crate::config::Config;
 use crate::handlers::error_handler::app_error_to_response;
+use crate::handlers::users::{notify_contacts, notify_profile_updated};
+use crate::websocket::connection::ConnectionManager;
+use crate::websocket::messages::{PinEventType, WsMessage};
 use actix_web::{delete, get, post, web, HttpMessage, HttpRequest, HttpResponse, Responder};
 use application::auth::{
     dtos::*,
@@ -11,14 +14,13 @@ use tracing::{error, info};
 use uuid::Uuid;
 
 /// Extract user_id and device_id from JWT claims in request extensions
-fn extract_auth_claims(req: &HttpRequest) -> Option<(Uuid, i64)> {
+pub(crate) fn extract_auth_claims(req: &HttpRequest) -> Option<(Uuid, i64)> {
     req.extensions()
         .get::<Claims>()
-        .map(|claims| {
+        .and_then(|claims| {
             let user_id = claims.sub.parse::<Uuid>().ok()?;
             Some((user_id, claims.device_id))
         })
-        .flatten()
 }
 
 // ============ OTP Endpoints ============
@@ -26,16 +28,17 @@ fn extract_auth_claims(req: &HttpRequest) -> Option<(Uuid, i64)> {
 #[post("/request-otp")]
 pub async fn request_otp(
     redis_conn: web::Data<MultiplexedConnection>,
+    config: web::Data<Config>,
     req: web::Json<RequestOtpRequest>,
 ) -> impl Responder {
     let mut conn = redis_conn.get_ref().clone();
 
-    match RequestOtpUseCase::execute(&mut conn, req.into_inner()).await {
+    match RequestOtpUseCase::execute(&mut conn, &config.phone_policy, req.into_inner()).await {
         Ok(otp) => {
             info!("OTP generated for testing: {}", otp);
             HttpResponse::Ok().json(RequestOtpResponse {
                 message: "OTP sent successfully".to_string(),
-                expires_in_seconds: 180,
+                expires_in_seconds: application::auth::otp::OTP_EXPIRY_SECONDS,
             })
         }
         Err(e) => {
@@ -49,6 +52,7 @@ pub async fn request_otp(
 pub async fn verify_otp(
     db: web::Data<DatabaseConnection>,
     redis_conn: web::Data<MultiplexedConnection>,
+    manager: web::Data<ConnectionManager>,
     config: web::Data<Config>,
     req: web::Json<VerifyOtpRequest>,
 ) -> impl Responder {
@@ -58,11 +62,28 @@ pub async fn verify_otp(
         jwt_secret: config.jwt_secret.clone(),
         jwt_expiration: config.jwt_expiration,
         refresh_token_expiration: config.refresh_token_expiration,
+        phone_hasher: config.phone_hasher.clone(),
     };
 
-    match VerifyOtpUseCase::execute(db.get_ref(), &mut conn, &auth_config, req.into_inner()).await {
+    match VerifyOtpUseCase::execute(
+        db.get_ref(),
+        &mut conn,
+        &auth_config,
+        &config.phone_policy,
+        req.into_inner(),
+    )
+    .await
+    {
         Ok(response) => {
             info!("OTP verified successfully for user: {}", response.user_id);
+            if response.identity_key_changed {
+                let msg = WsMessage::IdentityChanged {
+                    user_id: response.user_id,
+                    device_id: response.device_id,
+                };
+                notify_contacts(db.get_ref(), &manager, response.user_id, response.device_id, &msg)
+                    .await;
+            }
             HttpResponse::Ok().json(response)
         }
         Err(e) => {
@@ -104,9 +125,10 @@ pub async fn get_profile(
 pub async fn setup_profile(
     http_req: HttpRequest,
     db: web::Data<DatabaseConnection>,
+    manager: web::Data<ConnectionManager>,
     req: web::Json<SetupProfileRequest>,
 ) -> impl Responder {
-    let (user_id, _) = match extract_auth_claims(&http_req) {
+    let (user_id, device_id) = match extract_auth_claims(&http_req) {
         Some(claims) => claims,
         None => {
             return HttpResponse::Unauthorized().json(AuthErrorResponse {
@@ -118,7 +140,10 @@ pub async fn setup_profile(
     };
 
     match SetupProfileUseCase::execute(db.get_ref(), user_id, req.into_inner()).await {
-        Ok(response) => HttpResponse::Ok().json(response),
+        Ok(response) => {
+            notify_profile_updated(db.get_ref(), &manager, user_id, device_id).await;
+            HttpResponse::Ok().json(response)
+        }
         Err(e) => HttpResponse::BadRequest().json(AuthErrorResponse {
             error: e.to_string(),
             error_code: "INVALID_REQUEST".to_string(),
@@ -248,6 +273,151 @@ pub async fn skip_pin_setup(
     }
 }
 
+#[post("/change-pin")]
+pub async fn change_pin(
+    http_req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    redis_conn: web::Data<MultiplexedConnection>,
+    manager: web::Data<ConnectionManager>,
+    req: web::Json<ChangePinRequest>,
+) -> impl Responder {
+    let (user_id, device_id) = match extract_auth_claims(&http_req) {
+        Some(claims) => claims,
+        None => return unauthorized(),
+    };
+
+    let mut conn = redis_conn.get_ref().clone();
+
+    match ChangePinUseCase::execute(db.get_ref(), &mut conn, user_id, device_id, req.into_inner()).await {
+        Ok(response) => {
+            notify_pin_event(&manager, user_id, device_id, PinEventType::Changed, None).await;
+            HttpResponse::Ok().json(response)
+        }
+        Err(e) => app_error_to_response(e),
+    }
+}
+
+#[post("/remove-pin")]
+pub async fn remove_pin(
+    http_req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    redis_conn: web::Data<MultiplexedConnection>,
+    manager: web::Data<ConnectionManager>,
+    req: web::Json<RemovePinRequest>,
+) -> impl Responder {
+    let (user_id, device_id) = match extract_auth_claims(&http_req) {
+        Some(claims) => claims,
+        None => return unauthorized(),
+    };
+
+    let mut conn = redis_conn.get_ref().clone();
+
+    match RemovePinUseCase::execute(db.get_ref(), &mut conn, user_id, device_id, req.into_inner()).await {
+        Ok(response) => {
+            notify_pin_event(&manager, user_id, device_id, PinEventType::Removed, None).await;
+            HttpResponse::Ok().json(response)
+        }
+        Err(e) => app_error_to_response(e),
+    }
+}
+
+#[post("/forgot-pin")]
+pub async fn forgot_pin(
+    http_req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    redis_conn: web::Data<MultiplexedConnection>,
+    config: web::Data<Config>,
+    manager: web::Data<ConnectionManager>,
+) -> impl Responder {
+    let (user_id, device_id) = match extract_auth_claims(&http_req) {
+        Some(claims) => claims,
+        None => return unauthorized(),
+    };
+
+    let mut conn = redis_conn.get_ref().clone();
+
+    match RequestPinResetUseCase::execute(
+        db.get_ref(),
+        &mut conn,
+        user_id,
+        device_id,
+        config.pin_reset_cooldown_seconds,
+    )
+    .await
+    {
+        Ok(response) => {
+            let reset_available_at = response.reset_available_at.map(|t| t.timestamp());
+            notify_pin_event(&manager, user_id, device_id, PinEventType::ResetRequested, reset_available_at).await;
+            HttpResponse::Ok().json(response)
+        }
+        Err(e) => app_error_to_response(e),
+    }
+}
+
+#[post("/forgot-pin/cancel")]
+pub async fn cancel_forgot_pin(
+    http_req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    manager: web::Data<ConnectionManager>,
+) -> impl Responder {
+    let (user_id, device_id) = match extract_auth_claims(&http_req) {
+        Some(claims) => claims,
+        None => return unauthorized(),
+    };
+
+    match CancelPinResetUseCase::execute(db.get_ref(), user_id, device_id).await {
+        Ok(response) => {
+            notify_pin_event(&manager, user_id, device_id, PinEventType::ResetCancelled, None).await;
+            HttpResponse::Ok().json(response)
+        }
+        Err(e) => app_error_to_response(e),
+    }
+}
+
+#[post("/forgot-pin/complete")]
+pub async fn complete_forgot_pin(
+    http_req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    manager: web::Data<ConnectionManager>,
+) -> impl Responder {
+    let (user_id, device_id) = match extract_auth_claims(&http_req) {
+        Some(claims) => claims,
+        None => return unauthorized(),
+    };
+
+    match CompletePinResetUseCase::execute(db.get_ref(), user_id, device_id).await {
+        Ok(response) => {
+            notify_pin_event(&manager, user_id, device_id, PinEventType::ResetCompleted, None).await;
+            HttpResponse::Ok().json(response)
+        }
+        Err(e) => app_error_to_response(e),
+    }
+}
+
+pub(crate) fn unauthorized() -> HttpResponse {
+    HttpResponse::Unauthorized().json(AuthErrorResponse {
+        error: "Unauthorized".to_string(),
+        error_code: "UNAUTHORIZED".to_string(),
+        retry_after_seconds: None,
+    })
+}
+
+/// Tell the user's other devices that PIN state changed
+async fn notify_pin_event(
+    manager: &ConnectionManager,
+    user_id: Uuid,
+    device_id: i64,
+    event: PinEventType,
+    reset_available_at: Option<i64>,
+) {
+    let msg = WsMessage::PinEvent {
+        event,
+        device_id,
+        reset_available_at,
+    };
+    manager.send_to_user(&user_id, Some(device_id), &msg).await;
+}
+
 // ============ Token Refresh ============
 
 #[post("/refresh-token")]
@@ -260,6 +430,7 @@ pub async fn refresh_token(
         jwt_secret: config.jwt_secret.clone(),
         jwt_expiration: config.jwt_expiration,
         refresh_token_expiration: config.refresh_token_expiration,
+        phone_hasher: config.phone_hasher.clone(),
     };
 
     match RefreshTokenUseCase::execute(db.get_ref(), &auth_config, req.into_inner()).await {
@@ -330,9 +501,10 @@ pub async fn complete_linking(
 pub async fn approve_linking(
     http_req: HttpRequest,
     db: web::Data<DatabaseConnection>,
+    manager: web::Data<ConnectionManager>,
     req: web::Json<ApproveLinkingRequest>,
 ) -> impl Responder {
-    let (_, device_id) = match extract_auth_claims(&http_req) {
+    let (user_id, device_id) = match extract_auth_claims(&http_req) {
         Some(claims) => claims,
         None => {
             return HttpResponse::Unauthorized().json(AuthErrorResponse {
@@ -344,7 +516,17 @@ pub async fn approve_linking(
     };
 
     match ApproveLinkingUseCase::execute(db.get_ref(), device_id, req.into_inner()).await {
-        Ok(response) => HttpResponse::Ok().json(response),
+        Ok(response) => {
+            // A linked device brings its own identity key, so peers' safety numbers change
+            if let Some(new_device_id) = response.new_device_id {
+                let msg = WsMessage::IdentityChanged {
+                    user_id,
+                    device_id: new_device_id,
+                };
+                notify_contacts(db.get_ref(), &manager, user_id, device_id, &msg).await;
+            }
+            HttpResponse::Ok().json(response)
+        }
         Err(e) => HttpResponse::BadRequest().json(AuthErrorResponse {
             error: e.to_string(),
             error_code: "INVALID_REQUEST".to_string(),
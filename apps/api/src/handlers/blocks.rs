@@ -0,0 +1,69 @@
+use crate::handlers::auth::{extract_auth_claims, unauthorized};
+use crate::handlers::error_handler::app_error_to_response;
+use crate::websocket::connection::ConnectionManager;
+use crate::websocket::messages::WsMessage;
+use actix_web::{delete, get, put, web, HttpRequest, HttpResponse, Responder};
+use application::blocks::{BlockUserUseCase, ListBlockedUsersUseCase, UnblockUserUseCase};
+use sea_orm::DatabaseConnection;
+use uuid::Uuid;
+
+// ============ Blocks ============
+
+#[get("")]
+pub async fn list_blocked_users(
+    http_req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+) -> impl Responder {
+    let (user_id, _) = match extract_auth_claims(&http_req) {
+        Some(claims) => claims,
+        None => return unauthorized(),
+    };
+
+    match ListBlockedUsersUseCase::execute(db.get_ref(), user_id).await {
+        Ok(response) => HttpResponse::Ok().json(response),
+        Err(e) => app_error_to_response(e),
+    }
+}
+
+#[put("/{user_id}")]
+pub async fn block_user(
+    http_req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    manager: web::Data<ConnectionManager>,
+    path: web::Path<Uuid>,
+) -> impl Responder {
+    let (user_id, device_id) = match extract_auth_claims(&http_req) {
+        Some(claims) => claims,
+        None => return unauthorized(),
+    };
+
+    match BlockUserUseCase::execute(db.get_ref(), user_id, path.into_inner()).await {
+        Ok(response) => {
+            // This device learns it from the response
+            if response.profile_key_rotation_required {
+                manager
+                    .send_to_user(&user_id, Some(device_id), &WsMessage::ProfileKeyRotationRequired)
+                    .await;
+            }
+            HttpResponse::Ok().json(response)
+        }
+        Err(e) => app_error_to_response(e),
+    }
+}
+
+#[delete("/{user_id}")]
+pub async fn unblock_user(
+    http_req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<Uuid>,
+) -> impl Responder {
+    let (user_id, _) = match extract_auth_claims(&http_req) {
+        Some(claims) => claims,
+        None => return unauthorized(),
+    };
+
+    match UnblockUserUseCase::execute(db.get_ref(), user_id, path.into_inner()).await {
+        Ok(response) => HttpResponse::Ok().json(response),
+        Err(e) => app_error_to_response(e),
+    }
+}
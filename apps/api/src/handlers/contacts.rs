@@ -0,0 +1,38 @@
+use crate::config::Config;
+use crate::handlers::auth::{extract_auth_claims, unauthorized};
+use crate::handlers::error_handler::app_error_to_response;
+use actix_web::{post, web, HttpRequest, HttpResponse, Responder};
+use application::contacts::{dtos::*, DiscoverContactsUseCase};
+use redis::aio::MultiplexedConnection;
+use sea_orm::DatabaseConnection;
+
+// ============ Contact Discovery ============
+
+#[post("/discover")]
+pub async fn discover_contacts(
+    http_req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    redis_conn: web::Data<MultiplexedConnection>,
+    config: web::Data<Config>,
+    req: web::Json<DiscoverContactsRequest>,
+) -> impl Responder {
+    let (user_id, _) = match extract_auth_claims(&http_req) {
+        Some(claims) => claims,
+        None => return unauthorized(),
+    };
+
+    let mut conn = redis_conn.get_ref().clone();
+
+    match DiscoverContactsUseCase::execute(
+        db.get_ref(),
+        &mut conn,
+        &config.phone_hasher,
+        user_id,
+        req.into_inner(),
+    )
+    .await
+    {
+        Ok(response) => HttpResponse::Ok().json(response),
+        Err(e) => app_error_to_response(e),
+    }
+}
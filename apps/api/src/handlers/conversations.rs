@@ -0,0 +1,98 @@
+use crate::handlers::auth::{extract_auth_claims, unauthorized};
+use crate::handlers::error_handler::app_error_to_response;
+use crate::websocket::connection::ConnectionManager;
+use crate::websocket::messages::WsMessage;
+use actix_web::{get, post, web, HttpRequest, HttpResponse, Responder};
+use application::chat::{
+    AcceptMessageRequestUseCase, DeclineMessageRequestUseCase, ListMessageRequestsUseCase,
+};
+use sea_orm::DatabaseConnection;
+use uuid::Uuid;
+
+// ============ Message Requests ============
+
+#[get("/requests")]
+pub async fn list_message_requests(
+    http_req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+) -> impl Responder {
+    let (user_id, _) = match extract_auth_claims(&http_req) {
+        Some(claims) => claims,
+        None => return unauthorized(),
+    };
+
+    match ListMessageRequestsUseCase::execute(db.get_ref(), user_id).await {
+        Ok(response) => HttpResponse::Ok().json(response),
+        Err(e) => app_error_to_response(e),
+    }
+}
+
+#[post("/{conv_id}/accept")]
+pub async fn accept_message_request(
+    http_req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    manager: web::Data<ConnectionManager>,
+    path: web::Path<Uuid>,
+) -> impl Responder {
+    let (user_id, _) = match extract_auth_claims(&http_req) {
+        Some(claims) => claims,
+        None => return unauthorized(),
+    };
+
+    match AcceptMessageRequestUseCase::execute(db.get_ref(), user_id, path.into_inner()).await {
+        Ok(response) => {
+            if let Some(requester_id) = response.requester_id {
+                let msg = WsMessage::MessageRequestAccepted {
+                    conversation_id: response.conversation_id,
+                    user_id,
+                };
+                manager.send_to_user(&requester_id, None, &msg).await;
+            }
+            HttpResponse::Ok().json(response)
+        }
+        Err(e) => app_error_to_response(e),
+    }
+}
+
+#[post("/{conv_id}/decline")]
+pub async fn decline_message_request(
+    http_req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<Uuid>,
+) -> impl Responder {
+    let (user_id, _) = match extract_auth_claims(&http_req) {
+        Some(claims) => claims,
+        None => return unauthorized(),
+    };
+
+    // The requester is not told
+    match DeclineMessageRequestUseCase::execute(db.get_ref(), user_id, path.into_inner(), false).await {
+        Ok(response) => HttpResponse::Ok().json(response),
+        Err(e) => app_error_to_response(e),
+    }
+}
+
+#[post("/{conv_id}/decline-and-block")]
+pub async fn decline_and_block_message_request(
+    http_req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    manager: web::Data<ConnectionManager>,
+    path: web::Path<Uuid>,
+) -> impl Responder {
+    let (user_id, device_id) = match extract_auth_claims(&http_req) {
+        Some(claims) => claims,
+        None => return unauthorized(),
+    };
+
+    match DeclineMessageRequestUseCase::execute(db.get_ref(), user_id, path.into_inner(), true).await {
+        Ok(response) => {
+            if response.block.as_ref().is_some_and(|b| b.profile_key_rotation_required) {
+                manager
+                    .send_to_user(&user_id, Some(device_id), &WsMessage::ProfileKeyRotationRequired)
+                    .await;
+            }
+            HttpResponse::Ok().json(response)
+        }
+        Err(e) => app_error_to_response(e),
+    }
+}
@@ -20,6 +20,7 @@ pub fn app_error_to_response(err: AppError) -> HttpResponse {
         401 => HttpResponse::Unauthorized().json(error_response),
         403 => HttpResponse::Forbidden().json(error_response),
         404 => HttpResponse::NotFound().json(error_response),
+        409 => HttpResponse::Conflict().json(error_response),
         429 => HttpResponse::TooManyRequests().json(error_response),
         _ => HttpResponse::InternalServerError().json(error_response),
     }
@@ -31,7 +32,7 @@ macro_rules! handle_app_result {
     ($result:expr) => {
         match $result {
             Ok(value) => actix_web::HttpResponse::Ok().json(value),
-            Err(e) => crate::handlers::error_handler::app_error_to_response(e),
+            Err(e) => $crate::handlers::error_handler::app_error_to_response(e),
         }
     };
 }
@@ -1,23 +1,230 @@
-use actix_web::{get, web, HttpResponse, Responder};
-use application::keys::use_cases::GetPreKeyBundleUseCase;
+use crate::config::Config;
+use crate::handlers::auth::{extract_auth_claims, unauthorized};
+use crate::handlers::error_handler::app_error_to_response;
+use crate::websocket::connection::ConnectionManager;
+use crate::websocket::messages::WsMessage;
+use actix_web::{get, put, web, HttpRequest, HttpResponse, Responder};
+use application::keys::dtos::{ListIdentityChangesQuery, SignedPreKeyUpload, UploadPreKeysRequest};
+use application::keys::use_cases::{
+    check_prekey_fetch_limit, GetIdentityKeysUseCase, GetPreKeyBundleUseCase,
+    GetPreKeyBundlesUseCase, GetPreKeyCountUseCase, GetSignedPreKeyStatusUseCase,
+    ListIdentityChangesUseCase, RotateSignedPreKeyUseCase, UploadPreKeysUseCase,
+};
+use redis::aio::MultiplexedConnection;
 use sea_orm::DatabaseConnection;
 use uuid::Uuid;
 
+/// Registered before `get_prekey_bundle`, whose `{device_id}` would also match `*`
+#[get(r"/api/v1/keys/{user_id}/devices/{all:\*}")]
+pub async fn get_prekey_bundles(
+    http_req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    redis_conn: web::Data<MultiplexedConnection>,
+    manager: web::Data<ConnectionManager>,
+    config: web::Data<Config>,
+    path: web::Path<(Uuid, String)>,
+) -> impl Responder {
+    let (requester_id, requester_device_id) = match extract_auth_claims(&http_req) {
+        Some(claims) => claims,
+        None => return unauthorized(),
+    };
+    let (user_id, _) = path.into_inner();
+
+    let mut conn = redis_conn.get_ref().clone();
+    match GetPreKeyBundlesUseCase::execute(
+        db.get_ref(),
+        &mut conn,
+        &config.key_transparency_signer,
+        requester_id,
+        requester_device_id,
+        user_id,
+    )
+    .await
+    {
+        Ok(response) => {
+            for bundle in response.devices.iter().filter(|b| b.one_time_prekey.is_some()) {
+                notify_if_prekeys_low(db.get_ref(), &manager, user_id, bundle.device_id).await;
+            }
+            HttpResponse::Ok().json(response)
+        }
+        Err(e) => app_error_to_response(e),
+    }
+}
+
 #[get("/api/v1/keys/{user_id}/devices/{device_id}")]
 pub async fn get_prekey_bundle(
+    http_req: HttpRequest,
     db: web::Data<DatabaseConnection>,
+    redis_conn: web::Data<MultiplexedConnection>,
+    manager: web::Data<ConnectionManager>,
+    config: web::Data<Config>,
     path: web::Path<(Uuid, i64)>,
 ) -> impl Responder {
+    let (requester_id, _) = match extract_auth_claims(&http_req) {
+        Some(claims) => claims,
+        None => return unauthorized(),
+    };
     let (user_id, device_id) = path.into_inner();
 
-    match GetPreKeyBundleUseCase::execute(db.get_ref(), user_id, device_id).await {
-        Ok(response) => HttpResponse::Ok().json(response),
-        Err(e) => {
-            if e == "Device not found" {
-                HttpResponse::NotFound().json(serde_json::json!({ "error": e }))
-            } else {
-                HttpResponse::InternalServerError().json(serde_json::json!({ "error": e }))
+    let mut conn = redis_conn.get_ref().clone();
+    if let Err(e) = check_prekey_fetch_limit(&mut conn, requester_id).await {
+        return app_error_to_response(e);
+    }
+
+    match GetPreKeyBundleUseCase::execute(
+        db.get_ref(),
+        &config.key_transparency_signer,
+        user_id,
+        device_id,
+    )
+    .await
+    {
+        Ok(response) => {
+            if response.one_time_prekey.is_some() {
+                notify_if_prekeys_low(db.get_ref(), &manager, user_id, device_id).await;
             }
+            HttpResponse::Ok().json(response)
+        }
+        Err(e) => app_error_to_response(e),
+    }
+}
+
+/// Current identity keys of the user's devices, for safety number verification
+#[get("/api/v1/keys/{user_id}/identity")]
+pub async fn get_identity_keys(
+    http_req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<Uuid>,
+) -> impl Responder {
+    if extract_auth_claims(&http_req).is_none() {
+        return unauthorized();
+    }
+
+    match GetIdentityKeysUseCase::execute(db.get_ref(), path.into_inner()).await {
+        Ok(response) => HttpResponse::Ok().json(response),
+        Err(e) => app_error_to_response(e),
+    }
+}
+
+/// Key changes of contacts and the caller's other devices since `after`, for
+/// devices that missed `IdentityChanged` while offline
+#[get("/api/v1/keys/identity/changes")]
+pub async fn list_identity_changes(
+    http_req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    query: web::Query<ListIdentityChangesQuery>,
+) -> impl Responder {
+    let (user_id, device_id) = match extract_auth_claims(&http_req) {
+        Some(claims) => claims,
+        None => return unauthorized(),
+    };
+
+    match ListIdentityChangesUseCase::execute(db.get_ref(), user_id, device_id, query.into_inner()).await {
+        Ok(response) => HttpResponse::Ok().json(response),
+        Err(e) => app_error_to_response(e),
+    }
+}
+
+#[put("/api/v1/keys")]
+pub async fn upload_prekeys(
+    http_req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    req: web::Json<UploadPreKeysRequest>,
+) -> impl Responder {
+    let (user_id, device_id) = match extract_auth_claims(&http_req) {
+        Some(claims) => claims,
+        None => return unauthorized(),
+    };
+
+    match UploadPreKeysUseCase::execute(db.get_ref(), user_id, device_id, req.into_inner()).await {
+        Ok(response) => HttpResponse::Ok().json(response),
+        Err(e) => app_error_to_response(e),
+    }
+}
+
+#[get("/api/v1/keys/count")]
+pub async fn get_prekey_count(
+    http_req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+) -> impl Responder {
+    let (_, device_id) = match extract_auth_claims(&http_req) {
+        Some(claims) => claims,
+        None => return unauthorized(),
+    };
+
+    match GetPreKeyCountUseCase::execute(db.get_ref(), device_id).await {
+        Ok(response) => HttpResponse::Ok().json(response),
+        Err(e) => app_error_to_response(e),
+    }
+}
+
+#[put("/api/v1/keys/signed")]
+pub async fn rotate_signed_prekey(
+    http_req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    config: web::Data<Config>,
+    req: web::Json<SignedPreKeyUpload>,
+) -> impl Responder {
+    let (user_id, device_id) = match extract_auth_claims(&http_req) {
+        Some(claims) => claims,
+        None => return unauthorized(),
+    };
+
+    match RotateSignedPreKeyUseCase::execute(
+        db.get_ref(),
+        user_id,
+        device_id,
+        config.signed_prekey_grace_seconds,
+        req.into_inner(),
+    )
+    .await
+    {
+        Ok(response) => HttpResponse::Ok().json(response),
+        Err(e) => app_error_to_response(e),
+    }
+}
+
+#[get("/api/v1/keys/signed")]
+pub async fn get_signed_prekey_status(
+    http_req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    config: web::Data<Config>,
+) -> impl Responder {
+    let (_, device_id) = match extract_auth_claims(&http_req) {
+        Some(claims) => claims,
+        None => return unauthorized(),
+    };
+
+    match GetSignedPreKeyStatusUseCase::execute(
+        db.get_ref(),
+        device_id,
+        config.signed_prekey_rotation_seconds,
+    )
+    .await
+    {
+        Ok(response) => HttpResponse::Ok().json(response),
+        Err(e) => app_error_to_response(e),
+    }
+}
+
+/// Tell a connected device to replenish once its one-time prekeys run low.
+/// Best effort: devices also check `GET /api/v1/keys/count` themselves.
+pub async fn notify_if_prekeys_low(
+    db: &DatabaseConnection,
+    manager: &ConnectionManager,
+    user_id: Uuid,
+    device_id: i64,
+) {
+    match GetPreKeyCountUseCase::execute(db, device_id).await {
+        Ok(status) if status.is_low() => {
+            let msg = WsMessage::PreKeysLow {
+                count: status.count,
+                pq_count: status.pq_count,
+                threshold: status.low_threshold,
+            };
+            manager.send_to_device(&user_id, device_id, &msg).await;
         }
+        Ok(_) => {}
+        Err(e) => tracing::warn!("Failed to count prekeys of device {}: {}", device_id, e),
     }
 }
@@ -0,0 +1,144 @@
+use crate::config::Config;
+use crate::handlers::auth::{extract_auth_claims, unauthorized};
+use crate::handlers::error_handler::app_error_to_response;
+use crate::handlers::users::notify_profile_updated;
+use crate::websocket::connection::ConnectionManager;
+use actix_multipart::Multipart;
+use actix_web::http::header;
+use actix_web::{delete, get, post, web, HttpRequest, HttpResponse, Responder};
+use application::media::processing::ProfileImageKind;
+use application::media::use_cases::MAX_UPLOAD_BYTES;
+use application::media::{GetMediaUseCase, RemoveProfileImageUseCase, UploadProfileImageUseCase};
+use application::AppError;
+use futures::TryStreamExt;
+use infrastructure::storage::BlobStore;
+use sea_orm::DatabaseConnection;
+
+// ============ Profile Images ============
+
+/// Multipart upload with the image in a field named "file"
+#[post("/profile/{kind}")]
+pub async fn upload_profile_image(
+    http_req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    blob_store: web::Data<BlobStore>,
+    config: web::Data<Config>,
+    manager: web::Data<ConnectionManager>,
+    path: web::Path<String>,
+    payload: Multipart,
+) -> impl Responder {
+    let (user_id, device_id) = match extract_auth_claims(&http_req) {
+        Some(claims) => claims,
+        None => return unauthorized(),
+    };
+
+    let kind = match ProfileImageKind::parse(&path.into_inner()) {
+        Ok(kind) => kind,
+        Err(e) => return app_error_to_response(e),
+    };
+
+    let data = match read_file_field(payload).await {
+        Ok(data) => data,
+        Err(e) => return app_error_to_response(e),
+    };
+
+    match UploadProfileImageUseCase::execute(
+        db.get_ref(),
+        blob_store.get_ref(),
+        &config.public_base_url,
+        user_id,
+        kind,
+        data,
+    )
+    .await
+    {
+        Ok(response) => {
+            notify_profile_updated(db.get_ref(), &manager, user_id, device_id).await;
+            HttpResponse::Ok().json(response)
+        }
+        Err(e) => app_error_to_response(e),
+    }
+}
+
+#[delete("/profile/{kind}")]
+pub async fn remove_profile_image(
+    http_req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    blob_store: web::Data<BlobStore>,
+    manager: web::Data<ConnectionManager>,
+    path: web::Path<String>,
+) -> impl Responder {
+    let (user_id, device_id) = match extract_auth_claims(&http_req) {
+        Some(claims) => claims,
+        None => return unauthorized(),
+    };
+
+    let kind = match ProfileImageKind::parse(&path.into_inner()) {
+        Ok(kind) => kind,
+        Err(e) => return app_error_to_response(e),
+    };
+
+    match RemoveProfileImageUseCase::execute(db.get_ref(), blob_store.get_ref(), user_id, kind).await {
+        Ok(response) => {
+            if response.removed {
+                notify_profile_updated(db.get_ref(), &manager, user_id, device_id).await;
+            }
+            HttpResponse::Ok().json(response)
+        }
+        Err(e) => app_error_to_response(e),
+    }
+}
+
+// ============ Serving ============
+
+#[get("/{key:.*}")]
+pub async fn get_media(
+    http_req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    blob_store: web::Data<BlobStore>,
+    path: web::Path<String>,
+) -> impl Responder {
+    let (user_id, _) = match extract_auth_claims(&http_req) {
+        Some(claims) => claims,
+        None => return unauthorized(),
+    };
+
+    match GetMediaUseCase::execute(db.get_ref(), blob_store.get_ref(), user_id, &path.into_inner())
+        .await
+    {
+        // Keys are never reused, so clients may cache forever
+        Ok(data) => HttpResponse::Ok()
+            .content_type("image/jpeg")
+            .insert_header((header::CACHE_CONTROL, "private, max-age=31536000, immutable"))
+            .insert_header(("X-Content-Type-Options", "nosniff"))
+            .body(data),
+        Err(e) => app_error_to_response(e),
+    }
+}
+
+/// Read the "file" field, failing as soon as it grows past the upload limit
+async fn read_file_field(mut payload: Multipart) -> Result<Vec<u8>, AppError> {
+    let invalid = |e: actix_multipart::MultipartError| {
+        AppError::Validation(format!("Invalid multipart body: {}", e))
+    };
+
+    while let Some(mut field) = payload.try_next().await.map_err(invalid)? {
+        if field.name() != Some("file") {
+            continue;
+        }
+
+        let mut data = Vec::new();
+        while let Some(chunk) = field.try_next().await.map_err(invalid)? {
+            if data.len() + chunk.len() > MAX_UPLOAD_BYTES {
+                return Err(AppError::Validation(format!(
+                    "Image too large (max {} MB)",
+                    MAX_UPLOAD_BYTES / 1024 / 1024
+                )));
+            }
+            data.extend_from_slice(&chunk);
+        }
+        return Ok(data);
+    }
+
+    Err(AppError::Validation("Missing multipart field \"file\"".to_string()))
+}
@@ -1,4 +1,15 @@
+pub mod account;
+pub mod admin;
 pub mod auth;
+pub mod blocks;
+pub mod contacts;
+pub mod conversations;
 pub mod error_handler;
 pub mod health;
 pub mod keys;
+pub mod media;
+pub mod profiles;
+pub mod reports;
+pub mod sessions;
+pub mod transparency;
+pub mod users;
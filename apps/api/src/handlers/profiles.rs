@@ -0,0 +1,91 @@
+use crate::handlers::auth::{extract_auth_claims, unauthorized};
+use crate::handlers::error_handler::app_error_to_response;
+use crate::handlers::users::notify_profile_updated;
+use crate::websocket::connection::ConnectionManager;
+use actix_web::{delete, get, put, web, HttpRequest, HttpResponse, Responder};
+use application::profiles::{
+    dtos::*, DeleteEncryptedProfileUseCase, GetEncryptedProfileStatusUseCase,
+    GetEncryptedProfileUseCase, SetEncryptedProfileUseCase,
+};
+use sea_orm::DatabaseConnection;
+use uuid::Uuid;
+
+// ============ Own Encrypted Profile ============
+
+#[put("/me/encrypted-profile")]
+pub async fn set_encrypted_profile(
+    http_req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    manager: web::Data<ConnectionManager>,
+    req: web::Json<SetEncryptedProfileRequest>,
+) -> impl Responder {
+    let (user_id, device_id) = match extract_auth_claims(&http_req) {
+        Some(claims) => claims,
+        None => return unauthorized(),
+    };
+
+    match SetEncryptedProfileUseCase::execute(db.get_ref(), user_id, req.into_inner()).await {
+        Ok(response) => {
+            notify_profile_updated(db.get_ref(), &manager, user_id, device_id).await;
+            HttpResponse::Ok().json(response)
+        }
+        Err(e) => app_error_to_response(e),
+    }
+}
+
+#[get("/me/encrypted-profile")]
+pub async fn get_encrypted_profile_status(
+    http_req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+) -> impl Responder {
+    let (user_id, _) = match extract_auth_claims(&http_req) {
+        Some(claims) => claims,
+        None => return unauthorized(),
+    };
+
+    match GetEncryptedProfileStatusUseCase::execute(db.get_ref(), user_id).await {
+        Ok(response) => HttpResponse::Ok().json(response),
+        Err(e) => app_error_to_response(e),
+    }
+}
+
+#[delete("/me/encrypted-profile")]
+pub async fn delete_encrypted_profile(
+    http_req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    manager: web::Data<ConnectionManager>,
+) -> impl Responder {
+    let (user_id, device_id) = match extract_auth_claims(&http_req) {
+        Some(claims) => claims,
+        None => return unauthorized(),
+    };
+
+    match DeleteEncryptedProfileUseCase::execute(db.get_ref(), user_id).await {
+        Ok(response) => {
+            notify_profile_updated(db.get_ref(), &manager, user_id, device_id).await;
+            HttpResponse::Ok().json(response)
+        }
+        Err(e) => app_error_to_response(e),
+    }
+}
+
+// ============ Peer Encrypted Profile ============
+
+#[get("/{user_id}/encrypted-profile/{version}")]
+pub async fn get_encrypted_profile(
+    http_req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<(Uuid, String)>,
+) -> impl Responder {
+    let (viewer_id, _) = match extract_auth_claims(&http_req) {
+        Some(claims) => claims,
+        None => return unauthorized(),
+    };
+
+    let (user_id, version) = path.into_inner();
+
+    match GetEncryptedProfileUseCase::execute(db.get_ref(), viewer_id, user_id, &version).await {
+        Ok(response) => HttpResponse::Ok().json(response),
+        Err(e) => app_error_to_response(e),
+    }
+}
@@ -0,0 +1,28 @@
+use crate::handlers::auth::{extract_auth_claims, unauthorized};
+use crate::handlers::error_handler::app_error_to_response;
+use actix_web::{post, web, HttpRequest, HttpResponse, Responder};
+use application::reports::dtos::CreateReportRequest;
+use application::reports::CreateReportUseCase;
+use redis::aio::MultiplexedConnection;
+use sea_orm::DatabaseConnection;
+
+// ============ Reports ============
+
+#[post("")]
+pub async fn create_report(
+    http_req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    redis_conn: web::Data<MultiplexedConnection>,
+    req: web::Json<CreateReportRequest>,
+) -> impl Responder {
+    let (user_id, _) = match extract_auth_claims(&http_req) {
+        Some(claims) => claims,
+        None => return unauthorized(),
+    };
+
+    let mut conn = redis_conn.get_ref().clone();
+    match CreateReportUseCase::execute(db.get_ref(), &mut conn, user_id, req.into_inner()).await {
+        Ok(response) => HttpResponse::Created().json(response),
+        Err(e) => app_error_to_response(e),
+    }
+}
@@ -0,0 +1,134 @@
+use crate::handlers::auth::{extract_auth_claims, unauthorized};
+use crate::handlers::error_handler::app_error_to_response;
+use actix_web::{delete, get, post, put, web, HttpRequest, HttpResponse, Responder};
+use application::sessions::dtos::{
+    DeleteSessionQuery, ExportSessionsQuery, ImportSessionsRequest, PutSessionRequest,
+};
+use application::sessions::{
+    DeleteSessionUseCase, ExportSessionsUseCase, GetSessionUseCase, ImportSessionsUseCase,
+    ListSessionsUseCase, PutSessionUseCase,
+};
+use sea_orm::DatabaseConnection;
+
+// ============ Session Records ============
+
+#[get("")]
+pub async fn list_sessions(
+    http_req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+) -> impl Responder {
+    let (user_id, device_id) = match extract_auth_claims(&http_req) {
+        Some(claims) => claims,
+        None => return unauthorized(),
+    };
+
+    match ListSessionsUseCase::execute(db.get_ref(), user_id, device_id).await {
+        Ok(response) => HttpResponse::Ok().json(response),
+        Err(e) => app_error_to_response(e),
+    }
+}
+
+#[get("/{address}")]
+pub async fn get_session(
+    http_req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<String>,
+) -> impl Responder {
+    let (user_id, device_id) = match extract_auth_claims(&http_req) {
+        Some(claims) => claims,
+        None => return unauthorized(),
+    };
+
+    match GetSessionUseCase::execute(db.get_ref(), user_id, device_id, path.into_inner()).await {
+        Ok(response) => HttpResponse::Ok().json(response),
+        Err(e) => app_error_to_response(e),
+    }
+}
+
+#[put("/{address}")]
+pub async fn put_session(
+    http_req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<String>,
+    req: web::Json<PutSessionRequest>,
+) -> impl Responder {
+    let (user_id, device_id) = match extract_auth_claims(&http_req) {
+        Some(claims) => claims,
+        None => return unauthorized(),
+    };
+
+    match PutSessionUseCase::execute(
+        db.get_ref(),
+        user_id,
+        device_id,
+        path.into_inner(),
+        req.into_inner(),
+    )
+    .await
+    {
+        Ok(response) => HttpResponse::Ok().json(response),
+        Err(e) => app_error_to_response(e),
+    }
+}
+
+#[delete("/{address}")]
+pub async fn delete_session(
+    http_req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<String>,
+    query: web::Query<DeleteSessionQuery>,
+) -> impl Responder {
+    let (user_id, device_id) = match extract_auth_claims(&http_req) {
+        Some(claims) => claims,
+        None => return unauthorized(),
+    };
+
+    match DeleteSessionUseCase::execute(
+        db.get_ref(),
+        user_id,
+        device_id,
+        path.into_inner(),
+        query.into_inner(),
+    )
+    .await
+    {
+        Ok(response) => HttpResponse::Ok().json(response),
+        Err(e) => app_error_to_response(e),
+    }
+}
+
+// ============ Export / Import ============
+
+#[get("/export")]
+pub async fn export_sessions(
+    http_req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    query: web::Query<ExportSessionsQuery>,
+) -> impl Responder {
+    let (user_id, device_id) = match extract_auth_claims(&http_req) {
+        Some(claims) => claims,
+        None => return unauthorized(),
+    };
+
+    match ExportSessionsUseCase::execute(db.get_ref(), user_id, device_id, query.into_inner()).await {
+        Ok(response) => HttpResponse::Ok().json(response),
+        Err(e) => app_error_to_response(e),
+    }
+}
+
+#[post("/import")]
+pub async fn import_sessions(
+    http_req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    req: web::Json<ImportSessionsRequest>,
+) -> impl Responder {
+    let (user_id, device_id) = match extract_auth_claims(&http_req) {
+        Some(claims) => claims,
+        None => return unauthorized(),
+    };
+
+    match ImportSessionsUseCase::execute(db.get_ref(), user_id, device_id, req.into_inner()).await {
+        Ok(response) => HttpResponse::Ok().json(response),
+        Err(e) => app_error_to_response(e),
+    }
+}
@@ -0,0 +1,41 @@
+use crate::config::Config;
+use crate::handlers::auth::{extract_auth_claims, unauthorized};
+use crate::handlers::error_handler::app_error_to_response;
+use actix_web::{get, web, HttpRequest, HttpResponse, Responder};
+use application::transparency::dtos::ConsistencyProofQuery;
+use application::transparency::{GetConsistencyProofUseCase, GetTreeHeadUseCase};
+use sea_orm::DatabaseConnection;
+
+// ============ Key Transparency ============
+
+#[get("/tree-head")]
+pub async fn get_tree_head(
+    http_req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    config: web::Data<Config>,
+) -> impl Responder {
+    if extract_auth_claims(&http_req).is_none() {
+        return unauthorized();
+    }
+
+    match GetTreeHeadUseCase::execute(db.get_ref(), &config.key_transparency_signer).await {
+        Ok(response) => HttpResponse::Ok().json(response),
+        Err(e) => app_error_to_response(e),
+    }
+}
+
+#[get("/consistency")]
+pub async fn get_consistency_proof(
+    http_req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    query: web::Query<ConsistencyProofQuery>,
+) -> impl Responder {
+    if extract_auth_claims(&http_req).is_none() {
+        return unauthorized();
+    }
+
+    match GetConsistencyProofUseCase::execute(db.get_ref(), query.into_inner()).await {
+        Ok(response) => HttpResponse::Ok().json(response),
+        Err(e) => app_error_to_response(e),
+    }
+}
@@ -0,0 +1,167 @@
+use crate::handlers::auth::{extract_auth_claims, unauthorized};
+use crate::handlers::error_handler::app_error_to_response;
+use crate::websocket::connection::ConnectionManager;
+use crate::websocket::messages::WsMessage;
+use actix_web::http::header;
+use actix_web::{get, put, web, HttpRequest, HttpResponse, Responder};
+use application::users::{
+    dtos::*, use_cases::list_contact_ids, GetPublicProfileUseCase, GetUserByUsernameUseCase,
+    SearchUsernamesUseCase, UpdateDiscoverabilityUseCase, UpdateProfileVisibilityUseCase,
+};
+use redis::aio::MultiplexedConnection;
+use sea_orm::DatabaseConnection;
+use tracing::warn;
+use uuid::Uuid;
+
+// ============ Username Lookup ============
+
+#[get("/by-username/{username}")]
+pub async fn get_user_by_username(
+    http_req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    redis_conn: web::Data<MultiplexedConnection>,
+    path: web::Path<String>,
+) -> impl Responder {
+    let (viewer_id, _) = match extract_auth_claims(&http_req) {
+        Some(claims) => claims,
+        None => return unauthorized(),
+    };
+
+    let mut conn = redis_conn.get_ref().clone();
+
+    match GetUserByUsernameUseCase::execute(db.get_ref(), &mut conn, viewer_id, &path.into_inner()).await {
+        Ok(response) => HttpResponse::Ok().json(response),
+        Err(e) => app_error_to_response(e),
+    }
+}
+
+#[get("/search")]
+pub async fn search_usernames(
+    http_req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    redis_conn: web::Data<MultiplexedConnection>,
+    query: web::Query<SearchUsernamesQuery>,
+) -> impl Responder {
+    let (viewer_id, _) = match extract_auth_claims(&http_req) {
+        Some(claims) => claims,
+        None => return unauthorized(),
+    };
+
+    let mut conn = redis_conn.get_ref().clone();
+
+    match SearchUsernamesUseCase::execute(db.get_ref(), &mut conn, viewer_id, query.into_inner()).await {
+        Ok(response) => HttpResponse::Ok().json(response),
+        Err(e) => app_error_to_response(e),
+    }
+}
+
+// ============ Discoverability ============
+
+#[put("/me/discoverability")]
+pub async fn update_discoverability(
+    http_req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    req: web::Json<UpdateDiscoverabilityRequest>,
+) -> impl Responder {
+    let (user_id, _) = match extract_auth_claims(&http_req) {
+        Some(claims) => claims,
+        None => return unauthorized(),
+    };
+
+    match UpdateDiscoverabilityUseCase::execute(db.get_ref(), user_id, req.into_inner()).await {
+        Ok(response) => HttpResponse::Ok().json(response),
+        Err(e) => app_error_to_response(e),
+    }
+}
+
+// ============ Public Profile ============
+
+#[get("/{user_id}/profile")]
+pub async fn get_public_profile(
+    http_req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<Uuid>,
+) -> impl Responder {
+    let (viewer_id, _) = match extract_auth_claims(&http_req) {
+        Some(claims) => claims,
+        None => return unauthorized(),
+    };
+
+    match GetPublicProfileUseCase::execute(db.get_ref(), viewer_id, path.into_inner()).await {
+        Ok(response) => {
+            let etag = response.etag();
+            let not_modified = http_req
+                .headers()
+                .get(header::IF_NONE_MATCH)
+                .and_then(|v| v.to_str().ok())
+                .is_some_and(|v| v.split(',').any(|tag| tag.trim() == etag || tag.trim() == "*"));
+
+            let mut builder = if not_modified {
+                HttpResponse::NotModified()
+            } else {
+                HttpResponse::Ok()
+            };
+            builder
+                .insert_header((header::ETAG, etag))
+                .insert_header((header::CACHE_CONTROL, "private, no-cache"));
+
+            if not_modified {
+                builder.finish()
+            } else {
+                builder.json(response)
+            }
+        }
+        Err(e) => app_error_to_response(e),
+    }
+}
+
+#[put("/me/profile-visibility")]
+pub async fn update_profile_visibility(
+    http_req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    manager: web::Data<ConnectionManager>,
+    req: web::Json<UpdateProfileVisibilityRequest>,
+) -> impl Responder {
+    let (user_id, device_id) = match extract_auth_claims(&http_req) {
+        Some(claims) => claims,
+        None => return unauthorized(),
+    };
+
+    match UpdateProfileVisibilityUseCase::execute(db.get_ref(), user_id, req.into_inner()).await {
+        Ok(response) => {
+            notify_profile_updated(db.get_ref(), &manager, user_id, device_id).await;
+            HttpResponse::Ok().json(response)
+        }
+        Err(e) => app_error_to_response(e),
+    }
+}
+
+/// Tell the user's contacts and other devices that the profile changed
+pub(crate) async fn notify_profile_updated(
+    db: &DatabaseConnection,
+    manager: &ConnectionManager,
+    user_id: Uuid,
+    device_id: i64,
+) {
+    notify_contacts(db, manager, user_id, device_id, &WsMessage::ProfileUpdated { user_id }).await;
+}
+
+/// Send `msg` to the user's other devices and to every contact
+pub(crate) async fn notify_contacts(
+    db: &DatabaseConnection,
+    manager: &ConnectionManager,
+    user_id: Uuid,
+    device_id: i64,
+    msg: &WsMessage,
+) {
+    manager.send_to_user(&user_id, Some(device_id), msg).await;
+
+    match list_contact_ids(db, user_id).await {
+        Ok(contacts) => {
+            for contact_id in contacts {
+                manager.send_to_user(&contact_id, None, msg).await;
+            }
+        }
+        Err(e) => warn!("Failed to load contacts of {}: {}", user_id, e),
+    }
+}
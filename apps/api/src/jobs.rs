@@ -0,0 +1,66 @@
+use application::account::{ExpireDataExportsUseCase, PurgeDeletedAccountsUseCase};
+use application::auth::phone::PhoneHasher;
+use application::contacts::RehashPhoneNumbersUseCase;
+use application::keys::use_cases::ExpirePreviousSignedPreKeysUseCase;
+use infrastructure::storage::BlobStore;
+use sea_orm::DatabaseConnection;
+use std::time::Duration;
+
+const DATA_EXPORT_CLEANUP_INTERVAL: Duration = Duration::from_secs(900);
+const SIGNED_PREKEY_CLEANUP_INTERVAL: Duration = Duration::from_secs(3600);
+
+/// Periodically purge accounts whose deletion grace period has ended
+pub fn spawn_account_purge(
+    db: DatabaseConnection,
+    blob_store: BlobStore,
+    grace_period_seconds: i64,
+    interval_seconds: u64,
+) {
+    actix_web::rt::spawn(async move {
+        let mut interval = actix_web::rt::time::interval(Duration::from_secs(interval_seconds));
+        loop {
+            interval.tick().await;
+            if let Err(e) =
+                PurgeDeletedAccountsUseCase::execute(&db, &blob_store, grace_period_seconds).await
+            {
+                tracing::error!("Account purge failed: {}", e);
+            }
+        }
+    });
+}
+
+/// Periodically delete data export archives whose download links have expired
+pub fn spawn_data_export_cleanup(db: DatabaseConnection, blob_store: BlobStore) {
+    actix_web::rt::spawn(async move {
+        let mut interval = actix_web::rt::time::interval(DATA_EXPORT_CLEANUP_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(e) = ExpireDataExportsUseCase::execute(&db, &blob_store).await {
+                tracing::error!("Data export cleanup failed: {}", e);
+            }
+        }
+    });
+}
+
+/// Periodically forget replaced signed prekeys whose grace period has ended
+pub fn spawn_signed_prekey_cleanup(db: DatabaseConnection) {
+    actix_web::rt::spawn(async move {
+        let mut interval = actix_web::rt::time::interval(SIGNED_PREKEY_CLEANUP_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(e) = ExpirePreviousSignedPreKeysUseCase::execute(&db).await {
+                tracing::error!("Signed prekey cleanup failed: {}", e);
+            }
+        }
+    });
+}
+
+/// Re-derive stored phone number hashes once at startup, so a newly set or
+/// rotated contact discovery key applies to existing users straight away
+pub fn spawn_phone_hash_backfill(db: DatabaseConnection, phone_hasher: PhoneHasher) {
+    actix_web::rt::spawn(async move {
+        if let Err(e) = RehashPhoneNumbersUseCase::execute(&db, &phone_hasher).await {
+            tracing::error!("Phone number hash backfill failed: {}", e);
+        }
+    });
+}
@@ -5,11 +5,16 @@ use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilte
 
 pub mod config;
 pub mod handlers;
+mod jobs;
 mod middleware;
 mod websocket;
  
 use config::Config;
-use handlers::{auth, health, keys};
+use handlers::{
+    account, admin, auth, blocks, contacts, conversations, health, keys, media, profiles, reports,
+    sessions, transparency, users,
+};
+use middleware::admin_auth::AdminAuthMiddleware;
 use middleware::auth::AuthMiddleware;
 use middleware::rate_limit::PerIpRateLimitMiddleware;
 use websocket::{connection::ConnectionManager, handler::websocket_handler};
@@ -50,6 +55,11 @@ async fn main() -> anyhow::Result<()> {
     let config = Config::from_env()?;
     let config_data = web::Data::new(config.clone());
     tracing::info!("Starting vyry API server...");
+    if config.key_transparency_signer.is_ephemeral() {
+        tracing::warn!(
+            "KEY_TRANSPARENCY_ALLOW_EPHEMERAL_KEY is set; tree heads are signed with a key that changes on restart. Do not use this in production"
+        );
+    }
 
     // Initialize database connections
     let db_connections = infrastructure::database::DatabaseConnections::new(
@@ -62,6 +72,17 @@ async fn main() -> anyhow::Result<()> {
     let redis_conn = db_connections.redis.clone();
 
     let connection_manager = web::Data::new(ConnectionManager::new());
+    let blob_store = web::Data::new(infrastructure::storage::BlobStore::new(&config.blob_store)?);
+
+    jobs::spawn_account_purge(
+        db.clone(),
+        blob_store.get_ref().clone(),
+        config.account_deletion_grace_seconds,
+        config.account_purge_interval_seconds,
+    );
+    jobs::spawn_data_export_cleanup(db.clone(), blob_store.get_ref().clone());
+    jobs::spawn_signed_prekey_cleanup(db.clone());
+    jobs::spawn_phone_hash_backfill(db.clone(), config.phone_hasher.clone());
 
     let server_addr = format!("{}:{}", config.server_host, config.server_port);
     tracing::info!("Server listening on {}", server_addr);
@@ -88,6 +109,7 @@ async fn main() -> anyhow::Result<()> {
             .app_data(web::Data::new(redis_conn.clone()))
             .app_data(config_data.clone())
             .app_data(connection_manager.clone())
+            .app_data(blob_store.clone())
             // Health (no rate limit)
             .service(health::health_check)
             // Auth endpoints with stricter rate limiting
@@ -102,6 +124,11 @@ async fn main() -> anyhow::Result<()> {
                     .service(auth::verify_pin)
                     .service(auth::pin_status)
                     .service(auth::skip_pin_setup)
+                    .service(auth::change_pin)
+                    .service(auth::remove_pin)
+                    .service(auth::forgot_pin)
+                    .service(auth::cancel_forgot_pin)
+                    .service(auth::complete_forgot_pin)
                     .service(auth::refresh_token)
             )
             // Device endpoints
@@ -113,8 +140,108 @@ async fn main() -> anyhow::Result<()> {
                     .service(auth::list_devices)
                     .service(auth::unlink_device)
             )
+            // Account
+            .service(
+                web::scope("/api/v1/account")
+                    .service(account::delete_account)
+                    .service(account::request_data_export)
+                    .service(account::get_data_export)
+                    .service(account::download_data_export)
+                    .service(account::request_change_number)
+                    .service(account::confirm_change_number)
+            )
+            // Blocks
+            .service(
+                web::scope("/api/v1/blocks")
+                    .service(blocks::list_blocked_users)
+                    .service(blocks::block_user)
+                    .service(blocks::unblock_user)
+            )
+            // Session records; export is registered before "/{address}"
+            .service(
+                web::scope("/api/v1/sessions")
+                    // An import batch of full-size records, base64 encoded
+                    .app_data(web::JsonConfig::default().limit(8 * 1024 * 1024))
+                    .service(sessions::list_sessions)
+                    .service(sessions::export_sessions)
+                    .service(sessions::import_sessions)
+                    .service(sessions::get_session)
+                    .service(sessions::put_session)
+                    .service(sessions::delete_session)
+            )
+            // Key transparency
+            .service(
+                web::scope("/api/v1/transparency")
+                    .service(transparency::get_tree_head)
+                    .service(transparency::get_consistency_proof)
+            )
+            // Message requests
+            .service(
+                web::scope("/api/v1/conversations")
+                    .service(conversations::list_message_requests)
+                    .service(conversations::accept_message_request)
+                    .service(conversations::decline_message_request)
+                    .service(conversations::decline_and_block_message_request)
+            )
+            // Abuse reports
+            .service(
+                web::scope("/api/v1/reports")
+                    .service(reports::create_report)
+            )
+            // Contact discovery
+            .service(
+                web::scope("/api/v1/contacts")
+                    .service(contacts::discover_contacts)
+            )
+            // User lookup
+            .service(
+                web::scope("/api/v1/users")
+                    .service(users::get_user_by_username)
+                    .service(users::search_usernames)
+                    .service(users::update_discoverability)
+                    .service(users::update_profile_visibility)
+                    .service(users::get_public_profile)
+                    .service(profiles::set_encrypted_profile)
+                    .service(profiles::get_encrypted_profile_status)
+                    .service(profiles::delete_encrypted_profile)
+                    .service(profiles::get_encrypted_profile)
+            )
+            // Media
+            .service(
+                web::scope("/api/v1/media")
+                    .service(media::upload_profile_image)
+                    .service(media::remove_profile_image)
+                    .service(media::get_media)
+            )
             // Keys
+            .service(keys::upload_prekeys)
+            .service(keys::get_prekey_count)
+            .service(keys::rotate_signed_prekey)
+            .service(keys::get_signed_prekey_status)
+            .service(keys::get_prekey_bundles)
             .service(keys::get_prekey_bundle)
+            .service(keys::get_identity_keys)
+            .service(keys::list_identity_changes)
+            // Admin API: login is rate limited per IP, everything else needs an admin token
+            .service(
+                web::scope("/admin/v1/auth")
+                    .wrap(PerIpRateLimitMiddleware::new(10))
+                    .service(admin::admin_login)
+            )
+            .service(
+                web::scope("/admin/v1")
+                    .wrap(AdminAuthMiddleware)
+                    .service(admin::list_reports)
+                    .service(admin::get_report)
+                    .service(admin::resolve_report)
+                    .service(admin::lookup_user)
+                    .service(admin::get_user)
+                    .service(admin::list_user_devices)
+                    .service(admin::unlink_user_device)
+                    .service(admin::clear_user_rate_limits)
+                    .service(admin::list_user_audit_logs)
+                    .service(admin::get_key_metrics)
+            )
             // WebSocket
             .service(websocket_handler)
     })
@@ -0,0 +1,99 @@
+use actix_web::{
+    dev::{Service, ServiceRequest, ServiceResponse, Transform},
+    error::{ErrorInternalServerError, ErrorNotFound, ErrorUnauthorized},
+    http::header,
+    web, Error, HttpMessage,
+};
+use futures::future::LocalBoxFuture;
+use sea_orm::DatabaseConnection;
+use std::future::{ready, Ready};
+use std::rc::Rc;
+use std::task::{Context, Poll};
+use uuid::Uuid;
+
+use crate::config::Config;
+use application::admin::AuthenticateAdminUseCase;
+
+/// The authenticated operator, inserted into request extensions
+#[derive(Debug, Clone, Copy)]
+pub struct AdminIdentity {
+    pub admin_id: Uuid,
+}
+
+/// Requires a valid admin token on every request of the wrapped scope.
+/// Unlike `AuthMiddleware`, requests without a token are rejected here.
+pub struct AdminAuthMiddleware;
+
+impl<S, B> Transform<S, ServiceRequest> for AdminAuthMiddleware
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = AdminAuthMiddlewareService<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(AdminAuthMiddlewareService {
+            service: Rc::new(service),
+        }))
+    }
+}
+
+pub struct AdminAuthMiddlewareService<S> {
+    service: Rc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for AdminAuthMiddlewareService<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(cx)
+    }
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = self.service.clone();
+
+        Box::pin(async move {
+            let (Some(config), Some(db)) = (
+                req.app_data::<web::Data<Config>>().cloned(),
+                req.app_data::<web::Data<DatabaseConnection>>().cloned(),
+            ) else {
+                return Err(ErrorInternalServerError("Server misconfigured"));
+            };
+
+            // Without a secret the admin API does not exist
+            let Some(secret) = config.admin_jwt_secret.as_deref() else {
+                return Err(ErrorNotFound("Not found"));
+            };
+
+            let token = req
+                .headers()
+                .get(header::AUTHORIZATION)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| {
+                    value
+                        .strip_prefix("Bearer ")
+                        .or_else(|| value.strip_prefix("bearer "))
+                })
+                .ok_or_else(|| ErrorUnauthorized("Admin token required"))?;
+
+            let admin_id = AuthenticateAdminUseCase::execute(db.get_ref(), secret, token)
+                .await
+                .map_err(|_| ErrorUnauthorized("Invalid or expired admin token"))?;
+
+            req.extensions_mut().insert(AdminIdentity { admin_id });
+            service.call(req).await
+        })
+    }
+}
@@ -1,23 +1,26 @@
 use actix_web::{
     dev::{Service, ServiceRequest, ServiceResponse, Transform},
-    error::ErrorUnauthorized,
+    error::{ErrorForbidden, ErrorInternalServerError, ErrorUnauthorized},
     http::header,
     web, Error, HttpMessage,
 };
 use futures::future::LocalBoxFuture;
+use sea_orm::DatabaseConnection;
 use std::future::{ready, Ready};
+use std::rc::Rc;
 use std::task::{Context, Poll};
 
-use jsonwebtoken::{decode, Algorithm, DecodingKey, Validation};
-
 use crate::config::Config;
-use application::auth::dtos::Claims;
+use application::auth::AuthenticateUseCase;
+use application::AppError;
+
+pub const ADMIN_PATH_PREFIX: &str = "/admin/";
 
 pub struct AuthMiddleware;
 
 impl<S, B> Transform<S, ServiceRequest> for AuthMiddleware
 where
-    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
     S::Future: 'static,
     B: 'static,
 {
@@ -28,17 +31,19 @@ where
     type Future = Ready<Result<Self::Transform, Self::InitError>>;
 
     fn new_transform(&self, service: S) -> Self::Future {
-        ready(Ok(AuthMiddlewareService { service }))
+        ready(Ok(AuthMiddlewareService {
+            service: Rc::new(service),
+        }))
     }
 }
 
 pub struct AuthMiddlewareService<S> {
-    service: S,
+    service: Rc<S>,
 }
 
 impl<S, B> Service<ServiceRequest> for AuthMiddlewareService<S>
 where
-    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
     S::Future: 'static,
     B: 'static,
 {
@@ -53,38 +58,47 @@ where
     fn call(&self, req: ServiceRequest) -> Self::Future {
         // Try to extract and validate JWT if Authorization header is present.
         // If header is missing, we let the request pass through.
-        // If header is present but invalid, we return 401.
-        if let Some(auth_header_value) = req.headers().get(header::AUTHORIZATION) {
-            if let Ok(auth_str) = auth_header_value.to_str() {
-                if let Some(token) = auth_str
+        // If header is present but invalid, or its device or account has been
+        // revoked since it was issued, we return 401/403.
+        // Admin routes carry admin tokens and are checked by AdminAuthMiddleware.
+        let service = self.service.clone();
+        if req.path().starts_with(ADMIN_PATH_PREFIX) {
+            return Box::pin(async move { service.call(req).await });
+        }
+
+        let token = req
+            .headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| {
+                value
                     .strip_prefix("Bearer ")
-                    .or_else(|| auth_str.strip_prefix("bearer "))
-                {
-                    if let Some(config) = req.app_data::<web::Data<Config>>() {
-                        let decoding_key = DecodingKey::from_secret(config.jwt_secret.as_bytes());
-                        let mut validation = Validation::new(Algorithm::HS256);
-                        validation.validate_exp = true;
+                    .or_else(|| value.strip_prefix("bearer "))
+            })
+            .map(str::to_owned);
 
-                        match decode::<Claims>(token, &decoding_key, &validation) {
-                            Ok(token_data) => {
-                                // Put Claims into request extensions so handlers can read them.
-                                req.extensions_mut().insert(token_data.claims);
-                            }
-                            Err(_) => {
-                                return Box::pin(async move {
-                                    Err(ErrorUnauthorized("Invalid or expired token"))
-                                });
-                            }
-                        }
-                    }
-                }
+        Box::pin(async move {
+            if let Some(token) = token {
+                let (Some(config), Some(db)) = (
+                    req.app_data::<web::Data<Config>>().cloned(),
+                    req.app_data::<web::Data<DatabaseConnection>>().cloned(),
+                ) else {
+                    return Err(ErrorInternalServerError("Server misconfigured"));
+                };
+
+                let claims = AuthenticateUseCase::execute(db.get_ref(), &config.jwt_secret, &token)
+                    .await
+                    .map_err(|e| match e {
+                        AppError::Authorization(msg) => ErrorForbidden(msg),
+                        AppError::Authentication(msg) => ErrorUnauthorized(msg),
+                        _ => ErrorInternalServerError("Internal server error"),
+                    })?;
+
+                // Put Claims into request extensions so handlers can read them.
+                req.extensions_mut().insert(claims);
             }
-        }
 
-        let fut = self.service.call(req);
-        Box::pin(async move {
-            let res = fut.await?;
-            Ok(res)
+            service.call(req).await
         })
     }
 }
@@ -1,2 +1,3 @@
+pub mod admin_auth;
 pub mod auth;
 pub mod rate_limit;
\ No newline at end of file
@@ -1,3 +1,4 @@
+use super::messages::WsMessage;
 use actix_ws::Session;
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -68,6 +69,62 @@ impl ConnectionManager {
         let connections = self.get_user_connections(user_id).await;
         connections.into_iter().find(|c| c.device_id == device_id)
     }
+
+    /// Send a message to every connected device of a user, optionally skipping one device
+    pub async fn send_to_user(&self, user_id: &Uuid, except_device_id: Option<i64>, msg: &WsMessage) {
+        let json = match serde_json::to_string(msg) {
+            Ok(json) => json,
+            Err(e) => {
+                tracing::error!("Failed to serialize WebSocket message: {}", e);
+                return;
+            }
+        };
+
+        for mut conn in self.get_user_connections(user_id).await {
+            if Some(conn.device_id) == except_device_id {
+                continue;
+            }
+            let _ = conn.session.text(json.clone()).await;
+        }
+    }
+
+    /// Send a message to one device of a user, if it is connected
+    pub async fn send_to_device(&self, user_id: &Uuid, device_id: i64, msg: &WsMessage) {
+        let json = match serde_json::to_string(msg) {
+            Ok(json) => json,
+            Err(e) => {
+                tracing::error!("Failed to serialize WebSocket message: {}", e);
+                return;
+            }
+        };
+
+        for mut conn in self.get_user_connections(user_id).await {
+            if conn.device_id == device_id {
+                let _ = conn.session.text(json.clone()).await;
+            }
+        }
+    }
+
+    /// Close every connection of a user, e.g. after their devices were revoked
+    pub async fn disconnect_user(&self, user_id: &Uuid) {
+        for conn in self.get_user_connections(user_id).await {
+            let conn_id = conn.conn_id;
+            let _ = conn.session.close(None).await;
+            self.remove_connection(&conn_id).await;
+        }
+    }
+
+    /// Close the connections of a single device, e.g. after it was unlinked
+    pub async fn disconnect_device(&self, user_id: &Uuid, device_id: i64) {
+        for conn in self.get_user_connections(user_id).await {
+            if conn.device_id != device_id {
+                continue;
+            }
+            let conn_id = conn.conn_id;
+            let _ = conn.session.close(None).await;
+            self.remove_connection(&conn_id).await;
+        }
+    }
 }
 
 impl Default for ConnectionManager {
@@ -2,9 +2,9 @@ use super::connection::{ConnectionManager, WsConnection};
 use crate::config::Config;
 use actix_web::{get, web, Error, HttpRequest, HttpResponse};
 use actix_ws::Message;
-use application::auth::dtos::Claims;
+use application::auth::AuthenticateUseCase;
+use application::AppError;
 use futures::StreamExt;
-use jsonwebtoken::{decode, DecodingKey, Validation};
 use serde::Deserialize;
 use uuid::Uuid;
 
@@ -25,12 +25,10 @@ pub async fn websocket_handler(
     db: web::Data<DatabaseConnection>,
     query: web::Query<WsQuery>,
 ) -> Result<HttpResponse, Error> {
-    // Validate JWT
-    let decoding_key = DecodingKey::from_secret(config.jwt_secret.as_bytes());
-    let validation = Validation::default();
-    
-    let claims = match decode::<Claims>(&query.token, &decoding_key, &validation) {
-        Ok(token_data) => token_data.claims,
+    // Validate JWT, and that its device and account have not been revoked
+    let claims = match AuthenticateUseCase::execute(db.get_ref(), &config.jwt_secret, &query.token).await {
+        Ok(claims) => claims,
+        Err(AppError::Authorization(msg)) => return Ok(HttpResponse::Forbidden().body(msg)),
         Err(e) => {
             tracing::error!("Invalid WebSocket token: {}", e);
             return Ok(HttpResponse::Unauthorized().finish());
@@ -56,6 +54,9 @@ pub async fn websocket_handler(
     manager.add_connection(ws_conn.clone()).await;
     tracing::info!("User {} Device {} connected (Conn ID: {})", user_id, device_id, conn_id);
 
+    // Catch up devices that were offline when their prekeys ran low
+    crate::handlers::keys::notify_if_prekeys_low(db.get_ref(), &manager, user_id, device_id).await;
+
     let db = db.get_ref().clone();
 
     actix_web::rt::spawn(async move {
@@ -81,9 +82,17 @@ pub async fn websocket_handler(
                                         content: content.clone(),
                                     };
 
-                                    if let Err(e) = SendMessageUseCase::execute(&db, req).await {
-                                        tracing::error!("Failed to persist message: {}", e);
-                                        // TODO: Send error back to client?
+                                    match SendMessageUseCase::execute(&db, req).await {
+                                        Ok(true) => {}
+                                        // Blocked by the recipient: dropped silently, so the sender cannot tell
+                                        Ok(false) => continue,
+                                        // Never forward what could not be stored: a failed block
+                                        // check must not let the message through
+                                        Err(e) => {
+                                            tracing::error!("Failed to persist message: {}", e);
+                                            send_rejection(&mut session, client_message_id, &e).await;
+                                            continue;
+                                        }
                                     }
 
                                     // Forward to specific device
@@ -123,6 +132,9 @@ pub async fn websocket_handler(
                                 }
                                 super::messages::WsMessage::SdpOffer { recipient_id, recipient_device_id, sdp } => {
                                     tracing::info!("Routing SdpOffer to User {} Device {}", recipient_id, recipient_device_id);
+                                    if is_blocked(&db, user_id, recipient_id).await {
+                                        continue;
+                                    }
                                     if let Some(mut target_conn) = manager.get_device_connection(&recipient_id, recipient_device_id).await {
                                         let outbound = super::messages::WsMessage::SdpOffer {
                                             recipient_id: user_id, // From sender
@@ -136,6 +148,9 @@ pub async fn websocket_handler(
                                 }
                                 super::messages::WsMessage::SdpAnswer { recipient_id, recipient_device_id, sdp } => {
                                     tracing::info!("Routing SdpAnswer to User {} Device {}", recipient_id, recipient_device_id);
+                                    if is_blocked(&db, user_id, recipient_id).await {
+                                        continue;
+                                    }
                                     if let Some(mut target_conn) = manager.get_device_connection(&recipient_id, recipient_device_id).await {
                                         let outbound = super::messages::WsMessage::SdpAnswer {
                                             recipient_id: user_id,
@@ -149,6 +164,9 @@ pub async fn websocket_handler(
                                 }
                                 super::messages::WsMessage::IceCandidate { recipient_id, recipient_device_id, candidate } => {
                                     tracing::info!("Routing IceCandidate to User {} Device {}", recipient_id, recipient_device_id);
+                                    if is_blocked(&db, user_id, recipient_id).await {
+                                        continue;
+                                    }
                                     if let Some(mut target_conn) = manager.get_device_connection(&recipient_id, recipient_device_id).await {
                                         let outbound = super::messages::WsMessage::IceCandidate {
                                             recipient_id: user_id,
@@ -162,6 +180,15 @@ pub async fn websocket_handler(
                                 }
                                 super::messages::WsMessage::DeliveryStatus { message_id, conversation_id, sender_id, status } => {
                                     tracing::info!("Received DeliveryStatus for msg {} from User {} Device {}", message_id, user_id, device_id);
+                                    if is_blocked(&db, user_id, sender_id).await {
+                                        continue;
+                                    }
+                                    // Reading a message request must not tell the requester
+                                    if status == super::messages::DeliveryStatusType::Read
+                                        && is_request_pending(&db, conversation_id, user_id, sender_id).await
+                                    {
+                                        continue;
+                                    }
                                     
                                     // 1. Update DB
                                     // Map API status to Application status
@@ -196,6 +223,11 @@ pub async fn websocket_handler(
                                     }
                                 }
                                 super::messages::WsMessage::Typing { conversation_id, recipient_id, is_typing } => {
+                                    if is_blocked(&db, user_id, recipient_id).await
+                                        || is_request_pending(&db, conversation_id, user_id, recipient_id).await
+                                    {
+                                        continue;
+                                    }
                                     // Forward to recipient(s)
                                     // For 1-on-1, find recipient connections
                                     let recipient_conns = manager.get_user_connections(&recipient_id).await;
@@ -252,3 +284,45 @@ pub async fn websocket_handler(
 
     Ok(response)
 }
+
+/// Tell the sender a message was not accepted, so it can retry or surface the
+/// failure. Server-side errors are not detailed.
+async fn send_rejection(session: &mut actix_ws::Session, client_message_id: Uuid, error: &AppError) {
+    let message = if error.status_code() >= 500 {
+        "Message could not be sent. Please try again.".to_string()
+    } else {
+        error.to_string()
+    };
+    let frame = super::messages::WsMessage::Error {
+        code: error.error_code().to_string(),
+        message,
+        client_message_id: Some(client_message_id),
+    };
+    if let Ok(json) = serde_json::to_string(&frame) {
+        let _ = session.text(json).await;
+    }
+}
+
+/// Whether either user blocked the other. Fails closed: on a lookup error the
+/// event is dropped rather than risk reaching someone who blocked the sender.
+async fn is_blocked(db: &DatabaseConnection, user_id: Uuid, peer_id: Uuid) -> bool {
+    match application::blocks::use_cases::is_blocked_either_way(db, user_id, peer_id).await {
+        Ok(blocked) => blocked,
+        Err(e) => {
+            tracing::error!("Failed to check blocks between {} and {}: {}", user_id, peer_id, e);
+            true
+        }
+    }
+}
+
+/// Whether the conversation is still a message request for either user. Fails
+/// closed like `is_blocked`.
+async fn is_request_pending(db: &DatabaseConnection, conv_id: Uuid, user_id: Uuid, peer_id: Uuid) -> bool {
+    match application::chat::requests::has_pending_request(db, conv_id, [user_id, peer_id]).await {
+        Ok(pending) => pending,
+        Err(e) => {
+            tracing::error!("Failed to check message request {}: {}", conv_id, e);
+            true
+        }
+    }
+}
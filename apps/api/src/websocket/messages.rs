@@ -1,4 +1,5 @@
 use application::chat::dtos::SyncMessageDto;
+use core::entities::moderation_actions::ModerationActionType;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -56,10 +57,60 @@ pub enum WsMessage {
         recipient_id: Uuid,
         is_typing: bool,
     },
+    /// PIN / registration lock changed from another device of the same account
+    PinEvent {
+        event: PinEventType,
+        device_id: i64, // Device that performed the change
+        reset_available_at: Option<i64>,
+    },
+    /// A user's profile or its visibility changed; clients should refetch it
+    ProfileUpdated {
+        user_id: Uuid,
+    },
+    /// A contact moved their account to a new phone number. The user id and
+    /// identity key are unchanged, so existing sessions and safety numbers stay valid.
+    NumberChanged {
+        user_id: Uuid,
+        identity_key_changed: bool,
+    },
+    /// A contact re-registered with a new identity key or linked a new device.
+    /// Clients should refetch `GET /api/v1/keys/{user_id}/identity` and warn
+    /// that the safety number changed. Offline devices catch up through
+    /// `GET /api/v1/keys/identity/changes`.
+    IdentityChanged {
+        user_id: Uuid,
+        device_id: i64,
+    },
+    /// The user blocked someone who may hold their profile key; clients should
+    /// generate a new key and re-upload the encrypted profile
+    ProfileKeyRotationRequired,
+    /// The recipient accepted a message request; read receipts and typing
+    /// indicators are delivered from now on
+    MessageRequestAccepted {
+        conversation_id: Uuid,
+        user_id: Uuid,
+    },
+    /// A moderator warned, suspended or banned the account. Suspended and
+    /// banned accounts are disconnected right after this message.
+    AccountModerated {
+        action: ModerationActionType,
+        reason: Option<String>,
+        expires_at: Option<i64>,
+    },
+    /// This device has fewer one-time prekeys (EC or Kyber) left than
+    /// `threshold`; it should upload a new batch with `PUT /api/v1/keys`
+    PreKeysLow {
+        count: u64,
+        pq_count: u64,
+        threshold: u64,
+    },
     /// Error message from server
     Error {
         code: String,
         message: String,
+        /// Set when a `SignalMessage` was rejected; it was neither stored nor forwarded
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        client_message_id: Option<Uuid>,
     },
 }
 
@@ -68,3 +119,12 @@ pub enum DeliveryStatusType {
     Delivered,
     Read,
 }
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum PinEventType {
+    Changed,
+    Removed,
+    ResetRequested,
+    ResetCancelled,
+    ResetCompleted,
+}
@@ -0,0 +1,33 @@
+use chrono::{DateTime, Utc};
+use core::entities::data_exports::ExportStatus;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+// ============ Account Deletion ============
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DeleteAccountResponse {
+    pub deleted_at: DateTime<Utc>,
+    /// Logging in again before this time restores the account
+    pub purge_after: DateTime<Utc>,
+}
+
+// ============ Data Export ============
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DataExportStatusResponse {
+    pub export_id: Uuid,
+    pub status: ExportStatus,
+    pub created_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub size_bytes: Option<i64>,
+    /// Signed link, usable without a token until `expires_at`. Only set when ready.
+    pub download_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DownloadDataExportQuery {
+    pub expires: i64,
+    pub signature: String,
+}
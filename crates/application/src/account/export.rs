@@ -0,0 +1,163 @@
+use crate::users::dtos::{ProfileVisibility, Visibility};
+use crate::{AppError, AppResult};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use chrono::Utc;
+use core::entities::{conv_members, conversations, devices, encrypted_profiles, push_tokens, users};
+use sea_orm::{ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter, QueryOrder};
+use serde_json::{json, Value};
+use std::io::{Cursor, Write};
+use uuid::Uuid;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+/// Everything stored about a user, one JSON document per archive entry
+pub(crate) async fn collect<C: ConnectionTrait>(
+    db: &C,
+    user_id: Uuid,
+) -> AppResult<Vec<(&'static str, Value)>> {
+    let user = users::Entity::find_by_id(user_id)
+        .one(db)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("User {} not found", user_id)))?;
+
+    let encrypted_profile = encrypted_profiles::Entity::find_by_id(user_id).one(db).await?;
+
+    let devices = devices::Entity::find()
+        .filter(devices::Column::UserId.eq(user_id))
+        .order_by_asc(devices::Column::CreatedAt)
+        .all(db)
+        .await?;
+
+    let memberships = conv_members::Entity::find()
+        .filter(conv_members::Column::UserId.eq(user_id))
+        .find_also_related(conversations::Entity)
+        .order_by_asc(conv_members::Column::JoinedAt)
+        .all(db)
+        .await?;
+
+    let push_tokens = push_tokens::Entity::find()
+        .filter(push_tokens::Column::UserId.eq(user_id))
+        .all(db)
+        .await?;
+
+    let account = json!({
+        "user_id": user.user_id,
+        "phone_number": user.phone_number,
+        "created_at": user.created_at,
+        "updated_at": user.updated_at,
+        "last_seen_at": user.last_seen_at,
+        "deleted_at": user.deleted_at,
+        "pin_set_at": user.pin_set_at,
+        "registration_lock": user.registration_lock,
+        "exported_at": Utc::now(),
+    });
+
+    let profile = json!({
+        "username": user.username,
+        "display_name": user.display_name,
+        "bio": user.bio,
+        "profile_picture": user.profile_picture,
+        "background_image": user.background_image,
+        "discoverability": Visibility::from_i16(user.discoverability),
+        "profile_visibility": ProfileVisibility::from(&user),
+        // Ciphertexts are unreadable to the server; only their metadata is exported
+        "encrypted_profile": encrypted_profile.map(|p| json!({
+            "version": p.version,
+            "created_at": p.created_at,
+            "updated_at": p.updated_at,
+        })),
+    });
+
+    let devices: Vec<Value> = devices
+        .into_iter()
+        .map(|d| {
+            json!({
+                "device_id": d.device_id,
+                "device_uuid": d.device_uuid,
+                "device_name": d.device_name,
+                "platform": d.platform,
+                "device_type": d.device_type,
+                "is_active": d.is_active,
+                "registration_id": d.registration_id,
+                "identity_key_public": BASE64.encode(&d.identity_key_public),
+                "created_at": d.created_at,
+                "last_seen_at": d.last_seen_at,
+                "linked_at": d.linked_at,
+            })
+        })
+        .collect();
+
+    let conversations: Vec<Value> = memberships
+        .into_iter()
+        .map(|(member, conversation)| {
+            json!({
+                "conv_id": member.conv_id,
+                "conv_type": conversation.as_ref().map(|c| c.conv_type),
+                "name": conversation.as_ref().and_then(|c| c.name.clone()),
+                "role": member.role,
+                "joined_at": member.joined_at,
+                "left_at": member.left_at,
+            })
+        })
+        .collect();
+
+    let push_tokens: Vec<Value> = push_tokens
+        .into_iter()
+        .map(|t| {
+            json!({
+                "device_id": t.device_id,
+                "platform": t.platform,
+                "token": t.token,
+                "updated_at": t.updated_at,
+            })
+        })
+        .collect();
+
+    Ok(vec![
+        ("account.json", account),
+        ("profile.json", profile),
+        ("devices.json", Value::Array(devices)),
+        ("conversations.json", Value::Array(conversations)),
+        ("push_tokens.json", Value::Array(push_tokens)),
+    ])
+}
+
+/// Pretty-printed JSON files in a deflated zip archive
+pub(crate) fn write_zip(files: &[(&'static str, Value)]) -> AppResult<Vec<u8>> {
+    let zip_error = |e: zip::result::ZipError| AppError::Internal(format!("Failed to build archive: {}", e));
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    for (name, value) in files {
+        zip.start_file(*name, options).map_err(zip_error)?;
+        let body = serde_json::to_vec_pretty(value)
+            .map_err(|e| AppError::Internal(format!("Failed to serialise {}: {}", name, e)))?;
+        zip.write_all(&body)
+            .map_err(|e| AppError::Internal(format!("Failed to build archive: {}", e)))?;
+    }
+
+    Ok(zip.finish().map_err(zip_error)?.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    #[test]
+    fn test_write_zip_round_trip() {
+        let files = vec![
+            ("account.json", json!({ "user_id": Uuid::nil() })),
+            ("devices.json", json!([])),
+        ];
+        let archive = write_zip(&files).unwrap();
+
+        let mut zip = zip::ZipArchive::new(Cursor::new(archive)).unwrap();
+        assert_eq!(zip.len(), 2);
+
+        let mut body = String::new();
+        zip.by_name("account.json").unwrap().read_to_string(&mut body).unwrap();
+        let parsed: Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(parsed["user_id"], json!(Uuid::nil()));
+    }
+}
@@ -0,0 +1,8 @@
+pub mod dtos;
+mod export;
+pub mod use_cases;
+
+pub use use_cases::{
+    DeleteAccountUseCase, DownloadDataExportUseCase, ExpireDataExportsUseCase,
+    GetDataExportUseCase, PurgeDeletedAccountsUseCase, RequestDataExportUseCase,
+};
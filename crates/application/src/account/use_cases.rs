@@ -0,0 +1,664 @@
+use crate::account::dtos::*;
+use crate::account::export;
+use crate::audit::{self, AuditEvent};
+use crate::auth::phone::phone_number_hash;
+use crate::auth::use_cases::DEVICE_TYPE_PRIMARY;
+use crate::media::use_cases::delete_stored_image;
+use crate::rate_limit::check_rate_limit;
+use crate::users::dtos::Visibility;
+use crate::{AppError, AppResult};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD as BASE64_URL, Engine};
+use chrono::{DateTime, Duration, Utc};
+use core::entities::data_exports::{self, ExportStatus};
+use core::entities::{
+    banned_phone_numbers, blocks, conv_members, conversations, device_linking_sessions, devices,
+    encrypted_profiles, identity_key_history, kyber_prekeys, message_deliveries, messages,
+    one_time_prekeys, push_tokens, reports, signal_sessions, users,
+};
+use hmac::{Hmac, Mac};
+use infrastructure::storage::BlobStore;
+use redis::aio::MultiplexedConnection;
+use sea_orm::sea_query::{Expr, OnConflict, Query};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, Condition, ConnectionTrait, DatabaseConnection, EntityTrait, QueryFilter,
+    QueryOrder, QuerySelect, Set, TransactionTrait,
+};
+use sha2::Sha256;
+use tracing::{error, info, instrument, warn};
+use uuid::Uuid;
+
+// ============ Constants ============
+
+/// Accounts purged per run, so one run never holds the job for long
+const PURGE_BATCH_SIZE: u64 = 100;
+const EXPORT_MAX_REQUESTS: u32 = 3;
+const EXPORT_WINDOW_SECONDS: i64 = 86400;
+/// How long a finished archive stays downloadable
+const EXPORT_TTL_HOURS: i64 = 72;
+/// Pending exports older than this are assumed lost, e.g. to a restart
+const EXPORT_STALE_MINUTES: i64 = 60;
+const EXPORT_PATH: &str = "/api/v1/account/export/";
+
+// ============ Delete Account Use Case ============
+
+pub struct DeleteAccountUseCase;
+
+impl DeleteAccountUseCase {
+    /// Deactivate the account. Its data is kept until the grace period ends,
+    /// and verifying the phone number again before then restores it.
+    #[instrument(skip(db), fields(user_id = %user_id))]
+    pub async fn execute(
+        db: &DatabaseConnection,
+        user_id: Uuid,
+        device_id: i64,
+        grace_period_seconds: i64,
+    ) -> AppResult<DeleteAccountResponse> {
+        let device = devices::Entity::find_by_id(device_id)
+            .filter(devices::Column::UserId.eq(user_id))
+            .filter(devices::Column::IsActive.eq(true))
+            .one(db)
+            .await?
+            .ok_or_else(|| AppError::Authorization("Device is not active".to_string()))?;
+
+        if device.device_type != DEVICE_TYPE_PRIMARY {
+            return Err(AppError::Authorization(
+                "Only the primary device can delete the account".to_string(),
+            ));
+        }
+
+        let user = users::Entity::find_by_id(user_id)
+            .filter(users::Column::IsDeleted.eq(false))
+            .one(db)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("User {} not found", user_id)))?;
+
+        let now = Utc::now();
+        let txn = db.begin().await?;
+
+        let mut active_user: users::ActiveModel = user.into();
+        active_user.is_deleted = Set(true);
+        active_user.deleted_at = Set(Some(now.into()));
+        active_user.is_online = Set(false);
+        active_user.updated_at = Set(now.into());
+        active_user.update(&txn).await?;
+
+        // Signs every device out; refresh tokens stop working immediately
+        devices::Entity::update_many()
+            .col_expr(devices::Column::IsActive, Expr::value(false))
+            .filter(devices::Column::UserId.eq(user_id))
+            .exec(&txn)
+            .await?;
+
+        let purge_after = now + Duration::seconds(grace_period_seconds);
+        audit::record(
+            &txn,
+            AuditEvent::by_user(user_id, device_id, audit::ACCOUNT_DELETION_REQUESTED)
+                .with_details(serde_json::json!({ "purge_after": purge_after })),
+        )
+        .await?;
+
+        txn.commit().await?;
+
+        info!("Account deletion requested for user: {}", user_id);
+
+        Ok(DeleteAccountResponse {
+            deleted_at: now,
+            purge_after,
+        })
+    }
+}
+
+// ============ Purge Deleted Accounts Use Case ============
+
+pub struct PurgeDeletedAccountsUseCase;
+
+impl PurgeDeletedAccountsUseCase {
+    /// Purge accounts whose grace period has ended. Returns how many were purged.
+    #[instrument(skip(db, blob_store))]
+    pub async fn execute(
+        db: &DatabaseConnection,
+        blob_store: &BlobStore,
+        grace_period_seconds: i64,
+    ) -> AppResult<usize> {
+        let cutoff = Utc::now() - Duration::seconds(grace_period_seconds);
+
+        let expired = users::Entity::find()
+            .filter(users::Column::IsDeleted.eq(true))
+            .filter(users::Column::PurgedAt.is_null())
+            .filter(users::Column::DeletedAt.lte(cutoff))
+            .order_by_asc(users::Column::DeletedAt)
+            .limit(PURGE_BATCH_SIZE)
+            .all(db)
+            .await?;
+
+        let mut purged = 0;
+        for user in expired {
+            let user_id = user.user_id;
+            // One failing account must not block the rest of the batch
+            match purge_account(db, blob_store, user).await {
+                Ok(()) => purged += 1,
+                Err(e) => error!("Failed to purge account {}: {}", user_id, e),
+            }
+        }
+
+        if purged > 0 {
+            info!("Purged {} deleted accounts", purged);
+        }
+
+        Ok(purged)
+    }
+}
+
+// ============ Request Data Export Use Case ============
+
+pub struct RequestDataExportUseCase;
+
+impl RequestDataExportUseCase {
+    /// Start building an archive of the caller's data in the background.
+    /// An export already in progress is returned instead of starting another.
+    #[instrument(skip(db, redis_conn, blob_store), fields(user_id = %user_id))]
+    pub async fn execute(
+        db: &DatabaseConnection,
+        redis_conn: &mut MultiplexedConnection,
+        blob_store: &BlobStore,
+        user_id: Uuid,
+    ) -> AppResult<DataExportStatusResponse> {
+        let stale_before = Utc::now() - Duration::minutes(EXPORT_STALE_MINUTES);
+        if let Some(pending) = data_exports::Entity::find()
+            .filter(data_exports::Column::UserId.eq(user_id))
+            .filter(data_exports::Column::Status.eq(i16::from(ExportStatus::Pending)))
+            .filter(data_exports::Column::CreatedAt.gt(stale_before))
+            .one(db)
+            .await?
+        {
+            return Ok(status_response(pending, None));
+        }
+
+        check_rate_limit(
+            redis_conn,
+            &format!("data_export:{}", user_id),
+            EXPORT_MAX_REQUESTS,
+            EXPORT_WINDOW_SECONDS,
+            "Too many data export requests. Please try again tomorrow.",
+        )
+        .await?;
+
+        let export = data_exports::ActiveModel {
+            export_id: Set(Uuid::new_v4()),
+            user_id: Set(user_id),
+            status: Set(ExportStatus::Pending.into()),
+            blob_key: Set(None),
+            size_bytes: Set(None),
+            created_at: Set(Utc::now().into()),
+            completed_at: Set(None),
+            expires_at: Set(None),
+        }
+        .insert(db)
+        .await?;
+
+        let export_id = export.export_id;
+        let (db, blob_store) = (db.clone(), blob_store.clone());
+        tokio::spawn(async move {
+            if let Err(e) = build_export(&db, &blob_store, export_id, user_id).await {
+                error!("Data export {} failed: {}", export_id, e);
+                let failed = data_exports::Entity::update_many()
+                    .col_expr(
+                        data_exports::Column::Status,
+                        Expr::value(i16::from(ExportStatus::Failed)),
+                    )
+                    .filter(data_exports::Column::ExportId.eq(export_id))
+                    .exec(&db)
+                    .await;
+                if let Err(e) = failed {
+                    error!("Failed to mark data export {} as failed: {}", export_id, e);
+                }
+            }
+        });
+
+        info!("Data export {} requested", export_id);
+        Ok(status_response(export, None))
+    }
+}
+
+// ============ Get Data Export Use Case ============
+
+pub struct GetDataExportUseCase;
+
+impl GetDataExportUseCase {
+    #[instrument(skip(db, link_secret, base_url), fields(user_id = %user_id))]
+    pub async fn execute(
+        db: &DatabaseConnection,
+        link_secret: &str,
+        base_url: &str,
+        user_id: Uuid,
+        export_id: Uuid,
+    ) -> AppResult<DataExportStatusResponse> {
+        let export = data_exports::Entity::find_by_id(export_id)
+            .filter(data_exports::Column::UserId.eq(user_id))
+            .one(db)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Export not found".to_string()))?;
+
+        Ok(status_response(export, Some((link_secret, base_url))))
+    }
+}
+
+// ============ Download Data Export Use Case ============
+
+pub struct DownloadDataExportUseCase;
+
+impl DownloadDataExportUseCase {
+    /// Serve an archive through its signed link. Needs no access token, so the
+    /// link works in a browser; the signature binds the export and expiry.
+    #[instrument(skip(db, blob_store, link_secret, query))]
+    pub async fn execute(
+        db: &DatabaseConnection,
+        blob_store: &BlobStore,
+        link_secret: &str,
+        export_id: Uuid,
+        query: DownloadDataExportQuery,
+    ) -> AppResult<Vec<u8>> {
+        let expired = || AppError::NotFound("Download link is invalid or has expired".to_string());
+
+        let signature = BASE64_URL.decode(&query.signature).map_err(|_| expired())?;
+        if query.expires < Utc::now().timestamp()
+            || !verify_download_signature(link_secret, export_id, query.expires, &signature)
+        {
+            return Err(expired());
+        }
+
+        let export = data_exports::Entity::find_by_id(export_id)
+            .filter(data_exports::Column::Status.eq(i16::from(ExportStatus::Ready)))
+            .filter(data_exports::Column::ExpiresAt.gt(Utc::now()))
+            .one(db)
+            .await?
+            .ok_or_else(expired)?;
+
+        let key = export.blob_key.ok_or_else(expired)?;
+        let archive = blob_store
+            .get(&key)
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to read export: {}", e)))?
+            .ok_or_else(expired)?;
+
+        info!("Data export {} downloaded", export_id);
+        Ok(archive.to_vec())
+    }
+}
+
+// ============ Expire Data Exports Use Case ============
+
+pub struct ExpireDataExportsUseCase;
+
+impl ExpireDataExportsUseCase {
+    /// Delete archives past their expiry and give up on lost pending exports.
+    /// Returns how many archives were deleted.
+    #[instrument(skip(db, blob_store))]
+    pub async fn execute(db: &DatabaseConnection, blob_store: &BlobStore) -> AppResult<usize> {
+        let now = Utc::now();
+
+        let expired = data_exports::Entity::find()
+            .filter(data_exports::Column::Status.eq(i16::from(ExportStatus::Ready)))
+            .filter(data_exports::Column::ExpiresAt.lte(now))
+            .all(db)
+            .await?;
+
+        let count = expired.len();
+        for export in expired {
+            if let Some(key) = export.blob_key.as_deref() {
+                if let Err(e) = blob_store.delete(key).await {
+                    // Left as ready so the next run retries
+                    warn!("Failed to delete data export {}: {}", key, e);
+                    continue;
+                }
+            }
+
+            let mut active_export: data_exports::ActiveModel = export.into();
+            active_export.status = Set(ExportStatus::Expired.into());
+            active_export.blob_key = Set(None);
+            active_export.update(db).await?;
+        }
+
+        data_exports::Entity::update_many()
+            .col_expr(data_exports::Column::Status, Expr::value(i16::from(ExportStatus::Failed)))
+            .filter(data_exports::Column::Status.eq(i16::from(ExportStatus::Pending)))
+            .filter(data_exports::Column::CreatedAt.lte(now - Duration::minutes(EXPORT_STALE_MINUTES)))
+            .exec(db)
+            .await?;
+
+        if count > 0 {
+            info!("Expired {} data exports", count);
+        }
+
+        Ok(count)
+    }
+}
+
+// ============ Helpers ============
+
+/// Undo a pending deletion, called when the owner logs in during the grace period
+pub(crate) async fn restore_account<C: ConnectionTrait>(
+    db: &C,
+    user: users::Model,
+) -> AppResult<users::Model> {
+    let mut active_user: users::ActiveModel = user.into();
+    active_user.is_deleted = Set(false);
+    active_user.deleted_at = Set(None);
+    active_user.updated_at = Set(Utc::now().into());
+    Ok(active_user.update(db).await?)
+}
+
+/// Collect, archive and store a user's data, then mark the export ready
+async fn build_export(
+    db: &DatabaseConnection,
+    blob_store: &BlobStore,
+    export_id: Uuid,
+    user_id: Uuid,
+) -> AppResult<()> {
+    let files = export::collect(db, user_id).await?;
+    let archive = tokio::task::spawn_blocking(move || export::write_zip(&files))
+        .await
+        .map_err(|e| AppError::Internal(format!("Archive task failed: {}", e)))??;
+
+    let key = format!("exports/{}/{}.zip", user_id, export_id);
+    let size_bytes = archive.len() as i64;
+    blob_store
+        .put(&key, archive)
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to store export: {}", e)))?;
+
+    let now = Utc::now();
+    data_exports::ActiveModel {
+        export_id: Set(export_id),
+        status: Set(ExportStatus::Ready.into()),
+        blob_key: Set(Some(key)),
+        size_bytes: Set(Some(size_bytes)),
+        completed_at: Set(Some(now.into())),
+        expires_at: Set(Some((now + Duration::hours(EXPORT_TTL_HOURS)).into())),
+        ..Default::default()
+    }
+    .update(db)
+    .await?;
+
+    info!("Data export {} ready ({} bytes)", export_id, size_bytes);
+    Ok(())
+}
+
+/// Status as shown to the owner; `link` signs a download URL for ready exports
+fn status_response(
+    export: data_exports::Model,
+    link: Option<(&str, &str)>,
+) -> DataExportStatusResponse {
+    let expires_at: Option<DateTime<Utc>> = export.expires_at.map(Into::into);
+    let download_url = match (export.export_status(), expires_at, link) {
+        (ExportStatus::Ready, Some(expires_at), Some((link_secret, base_url)))
+            if expires_at > Utc::now() =>
+        {
+            Some(download_url(link_secret, base_url, export.export_id, expires_at.timestamp()))
+        }
+        _ => None,
+    };
+
+    DataExportStatusResponse {
+        export_id: export.export_id,
+        status: export.export_status(),
+        created_at: export.created_at.into(),
+        completed_at: export.completed_at.map(Into::into),
+        expires_at,
+        size_bytes: export.size_bytes,
+        download_url,
+    }
+}
+
+fn download_mac(link_secret: &str, export_id: Uuid, expires: i64) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(link_secret.as_bytes())
+        .expect("HMAC accepts keys of any length");
+    mac.update(format!("data-export:{}:{}", export_id, expires).as_bytes());
+    mac
+}
+
+fn download_url(link_secret: &str, base_url: &str, export_id: Uuid, expires: i64) -> String {
+    let signature = download_mac(link_secret, export_id, expires).finalize().into_bytes();
+    format!(
+        "{}{}{}/download?expires={}&signature={}",
+        base_url.trim_end_matches('/'),
+        EXPORT_PATH,
+        export_id,
+        expires,
+        BASE64_URL.encode(signature)
+    )
+}
+
+fn verify_download_signature(link_secret: &str, export_id: Uuid, expires: i64, signature: &[u8]) -> bool {
+    download_mac(link_secret, export_id, expires)
+        .verify_slice(signature)
+        .is_ok()
+}
+
+/// Phone number stored on a purged account. Longer than any E.164 number and
+/// without the leading '+', so it can never collide with a real registration.
+fn tombstone_phone_number(user_id: Uuid) -> String {
+    format!("deleted:{}", user_id)
+}
+
+/// Remove everything tied to the account and anonymise what other users still
+/// reference. The user row itself stays as a tombstone.
+async fn purge_account(
+    db: &DatabaseConnection,
+    blob_store: &BlobStore,
+    user: users::Model,
+) -> AppResult<()> {
+    let user_id = user.user_id;
+    let txn = db.begin().await?;
+
+    let device_ids: Vec<i64> = devices::Entity::find()
+        .select_only()
+        .column(devices::Column::DeviceId)
+        .filter(devices::Column::UserId.eq(user_id))
+        .into_tuple()
+        .all(&txn)
+        .await?;
+
+    // ============ Devices and keys ============
+
+    push_tokens::Entity::delete_many()
+        .filter(push_tokens::Column::UserId.eq(user_id))
+        .exec(&txn)
+        .await?;
+    identity_key_history::Entity::delete_many()
+        .filter(identity_key_history::Column::UserId.eq(user_id))
+        .exec(&txn)
+        .await?;
+
+    if !device_ids.is_empty() {
+        message_deliveries::Entity::delete_many()
+            .filter(message_deliveries::Column::DeviceId.is_in(device_ids.clone()))
+            .exec(&txn)
+            .await?;
+        signal_sessions::Entity::delete_many()
+            .filter(signal_sessions::Column::DeviceId.is_in(device_ids.clone()))
+            .exec(&txn)
+            .await?;
+        one_time_prekeys::Entity::delete_many()
+            .filter(one_time_prekeys::Column::DeviceId.is_in(device_ids.clone()))
+            .exec(&txn)
+            .await?;
+        kyber_prekeys::Entity::delete_many()
+            .filter(kyber_prekeys::Column::DeviceId.is_in(device_ids.clone()))
+            .exec(&txn)
+            .await?;
+        device_linking_sessions::Entity::delete_many()
+            .filter(device_linking_sessions::Column::PrimaryDeviceId.is_in(device_ids))
+            .exec(&txn)
+            .await?;
+    }
+
+    devices::Entity::delete_many()
+        .filter(devices::Column::UserId.eq(user_id))
+        .exec(&txn)
+        .await?;
+
+    // ============ Messages and memberships ============
+
+    // Sent messages stay so conversations keep their shape, but lose their content
+    message_deliveries::Entity::update_many()
+        .col_expr(message_deliveries::Column::Content, Expr::value(Option::<Vec<u8>>::None))
+        .filter(
+            message_deliveries::Column::MessageId.in_subquery(
+                Query::select()
+                    .column(messages::Column::MessageId)
+                    .from(messages::Entity)
+                    .and_where(messages::Column::SenderUserId.eq(user_id))
+                    .to_owned(),
+            ),
+        )
+        .exec(&txn)
+        .await?;
+
+    messages::Entity::update_many()
+        .col_expr(messages::Column::Content, Expr::value(""))
+        .col_expr(messages::Column::Iv, Expr::value(Vec::<u8>::new()))
+        .col_expr(messages::Column::AttachmentUrl, Expr::value(Option::<String>::None))
+        .col_expr(messages::Column::ThumbnailUrl, Expr::value(Option::<String>::None))
+        .col_expr(
+            messages::Column::SenderKeyDistribution,
+            Expr::value(Option::<Vec<u8>>::None),
+        )
+        .col_expr(messages::Column::Extra, Expr::value(serde_json::json!({})))
+        .col_expr(messages::Column::DeletedAt, Expr::cust("COALESCE(deleted_at, now())"))
+        .filter(messages::Column::SenderUserId.eq(user_id))
+        .exec(&txn)
+        .await?;
+
+    conv_members::Entity::delete_many()
+        .filter(conv_members::Column::UserId.eq(user_id))
+        .exec(&txn)
+        .await?;
+
+    conversations::Entity::update_many()
+        .col_expr(conversations::Column::CreatorId, Expr::value(Option::<Uuid>::None))
+        .filter(conversations::Column::CreatorId.eq(user_id))
+        .exec(&txn)
+        .await?;
+
+    blocks::Entity::delete_many()
+        .filter(
+            Condition::any()
+                .add(blocks::Column::BlockerId.eq(user_id))
+                .add(blocks::Column::BlockedId.eq(user_id)),
+        )
+        .exec(&txn)
+        .await?;
+
+    // Reports and moderation actions stay as the moderation record; reports
+    // the user filed are only detached from them
+    reports::Entity::update_many()
+        .col_expr(reports::Column::ReporterId, Expr::value(Option::<Uuid>::None))
+        .filter(reports::Column::ReporterId.eq(user_id))
+        .exec(&txn)
+        .await?;
+
+    // The tombstone frees the number, so a ban has to be kept against it
+    if user.is_banned() {
+        banned_phone_numbers::Entity::insert(banned_phone_numbers::ActiveModel {
+            phone_hash: Set(phone_number_hash(&user.phone_number)),
+            user_id: Set(user_id),
+            banned_at: Set(user.banned_at.unwrap_or_else(|| Utc::now().into())),
+        })
+        .on_conflict(
+            OnConflict::column(banned_phone_numbers::Column::PhoneHash)
+                .do_nothing()
+                .to_owned(),
+        )
+        .exec_without_returning(&txn)
+        .await?;
+    }
+
+    // ============ Profile ============
+
+    encrypted_profiles::Entity::delete_by_id(user_id)
+        .exec(&txn)
+        .await?;
+
+    let export_keys: Vec<Option<String>> = data_exports::Entity::find()
+        .select_only()
+        .column(data_exports::Column::BlobKey)
+        .filter(data_exports::Column::UserId.eq(user_id))
+        .into_tuple()
+        .all(&txn)
+        .await?;
+    data_exports::Entity::delete_many()
+        .filter(data_exports::Column::UserId.eq(user_id))
+        .exec(&txn)
+        .await?;
+
+    let images = [user.profile_picture.clone(), user.background_image.clone()];
+
+    // Frees the phone number and username for new registrations
+    let now = Utc::now();
+    let mut active_user: users::ActiveModel = user.into();
+    active_user.phone_number = Set(tombstone_phone_number(user_id));
+    active_user.phone_number_hash = Set(user_id.as_bytes().to_vec());
+    active_user.username = Set(None);
+    active_user.display_name = Set(None);
+    active_user.bio = Set(None);
+    active_user.profile_picture = Set(None);
+    active_user.background_image = Set(None);
+    active_user.last_seen_at = Set(None);
+    active_user.is_online = Set(false);
+    active_user.pin_hash = Set(None);
+    active_user.registration_lock = Set(false);
+    active_user.registration_lock_expires_at = Set(None);
+    active_user.pin_set_at = Set(None);
+    active_user.pin_reset_at = Set(None);
+    active_user.discoverability = Set(Visibility::Nobody.as_i16());
+    active_user.purged_at = Set(Some(now.into()));
+    active_user.updated_at = Set(now.into());
+    active_user.update(&txn).await?;
+
+    audit::record(&txn, AuditEvent::by_system(user_id, audit::ACCOUNT_PURGED)).await?;
+
+    txn.commit().await?;
+
+    // Blobs are removed only once the database no longer references them
+    for image in &images {
+        delete_stored_image(blob_store, image.as_deref()).await;
+    }
+    for key in export_keys.iter().flatten() {
+        if let Err(e) = blob_store.delete(key).await {
+            warn!("Failed to delete data export {}: {}", key, e);
+        }
+    }
+
+    info!("Purged account: {}", user_id);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::phone::PhonePolicy;
+
+    #[test]
+    fn test_download_url_signature() {
+        let export_id = Uuid::new_v4();
+        let url = download_url("secret", "https://api.example.com/", export_id, 1_700_000_000);
+        assert!(url.starts_with(&format!(
+            "https://api.example.com/api/v1/account/export/{}/download?expires=1700000000&signature=",
+            export_id
+        )));
+
+        let signature = BASE64_URL.decode(url.rsplit_once('=').unwrap().1).unwrap();
+        assert!(verify_download_signature("secret", export_id, 1_700_000_000, &signature));
+        assert!(!verify_download_signature("secret", export_id, 1_700_000_001, &signature));
+        assert!(!verify_download_signature("other", export_id, 1_700_000_000, &signature));
+        assert!(!verify_download_signature("secret", Uuid::new_v4(), 1_700_000_000, &signature));
+    }
+
+    #[test]
+    fn test_tombstone_phone_number_is_not_a_phone_number() {
+        let tombstone = tombstone_phone_number(Uuid::new_v4());
+        assert!(tombstone.len() <= 64);
+        assert!(PhonePolicy::default().canonicalize(&tombstone).is_err());
+    }
+}
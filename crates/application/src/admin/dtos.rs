@@ -0,0 +1,158 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+// ============ Admin JWT Claims ============
+
+/// Claims of an admin token. Admin tokens are signed with their own secret
+/// and audience, so user tokens are never accepted by the admin API and
+/// admin tokens never by the user API.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AdminClaims {
+    pub sub: String,
+    pub aud: String,
+    pub role: String,
+    pub exp: i64,
+    pub iat: i64,
+}
+
+// ============ Admin Auth ============
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct AdminLoginRequest {
+    #[validate(length(min = 1, max = 64, message = "Username must be between 1-64 characters"))]
+    pub username: String,
+    #[validate(length(min = 1, max = 256, message = "Password must be between 1-256 characters"))]
+    pub password: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AdminLoginResponse {
+    pub admin_id: Uuid,
+    pub access_token: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateAdminResponse {
+    pub admin_id: Uuid,
+    pub username: String,
+}
+
+// ============ Account Investigation ============
+
+/// Exactly one of the fields must be set
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct LookupUserQuery {
+    #[validate(length(min = 1, max = 64, message = "Username must be between 1-64 characters"))]
+    pub username: Option<String>,
+    /// Base64 SHA-256 of the E.164 number, the same token clients use for contact discovery
+    pub phone_hash: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AdminUserResponse {
+    pub user_id: Uuid,
+    pub phone_number: String,
+    pub username: Option<String>,
+    pub display_name: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub last_seen_at: Option<DateTime<Utc>>,
+    pub is_deleted: bool,
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub purged_at: Option<DateTime<Utc>>,
+    pub suspended_until: Option<DateTime<Utc>>,
+    pub banned_at: Option<DateTime<Utc>>,
+    pub has_pin: bool,
+    pub registration_lock: bool,
+    pub active_devices: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AdminDevice {
+    pub device_id: i64,
+    pub device_uuid: Uuid,
+    pub device_name: Option<String>,
+    pub platform: i16,
+    pub device_type: i16,
+    pub is_active: bool,
+    pub last_seen_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    pub linked_at: Option<DateTime<Utc>>,
+    pub signed_prekey_id: i32,
+    pub signed_prekey_created_at: DateTime<Utc>,
+    /// Not rotated within the configured interval
+    pub signed_prekey_stale: bool,
+    pub one_time_prekeys_remaining: u64,
+    /// One-time Kyber prekeys, not counting the last-resort key
+    pub pq_prekeys_remaining: u64,
+    pub pq_last_resort: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AdminDevicesResponse {
+    pub user_id: Uuid,
+    pub devices: Vec<AdminDevice>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AdminUnlinkDeviceResponse {
+    pub user_id: Uuid,
+    pub device_id: i64,
+    pub unlinked: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ClearRateLimitsResponse {
+    pub user_id: Uuid,
+    /// Redis keys that existed and were removed
+    pub cleared: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct ListAuditLogsQuery {
+    #[serde(default)]
+    #[validate(range(min = 1, max = 500, message = "Limit must be between 1-500"))]
+    pub limit: Option<u64>,
+    /// Only entries with a smaller audit ID, for paging backwards
+    pub before: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AuditLogEntry {
+    pub audit_id: i64,
+    pub user_id: Option<Uuid>,
+    pub device_id: Option<i64>,
+    pub actor: String,
+    pub action: String,
+    pub details: serde_json::Value,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListAuditLogsResponse {
+    pub entries: Vec<AuditLogEntry>,
+}
+
+// ============ Metrics ============
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct KeyMetricsResponse {
+    pub rotation_interval_seconds: i64,
+    pub active_devices: u64,
+    /// Active devices whose signed prekey is older than the rotation interval
+    pub stale_signed_prekeys: u64,
+    /// The longest-unrotated of those devices, oldest first
+    pub stale_devices: Vec<StaleSignedPreKeyDevice>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StaleSignedPreKeyDevice {
+    pub user_id: Uuid,
+    pub device_id: i64,
+    pub signed_prekey_id: i32,
+    pub signed_prekey_created_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+}
@@ -0,0 +1,67 @@
+use crate::admin::dtos::*;
+use crate::audit::{self, AuditEvent};
+use crate::AppResult;
+use chrono::{Duration, Utc};
+use core::entities::devices;
+use sea_orm::{
+    ColumnTrait, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder,
+    QuerySelect,
+};
+use tracing::instrument;
+use uuid::Uuid;
+
+// ============ Constants ============
+
+const STALE_DEVICES_LIMIT: u64 = 100;
+
+// ============ Key Metrics Use Case ============
+
+pub struct AdminKeyMetricsUseCase;
+
+impl AdminKeyMetricsUseCase {
+    /// Flag active devices that have not rotated their signed prekey within
+    /// `rotation_seconds`, typically clients that stopped running
+    #[instrument(skip(db), fields(admin_id = %admin_id))]
+    pub async fn execute(
+        db: &DatabaseConnection,
+        admin_id: Uuid,
+        rotation_seconds: i64,
+    ) -> AppResult<KeyMetricsResponse> {
+        let cutoff = Utc::now() - Duration::seconds(rotation_seconds);
+
+        let active_devices = devices::Entity::find()
+            .filter(devices::Column::IsActive.eq(true))
+            .count(db)
+            .await?;
+
+        let stale = devices::Entity::find()
+            .filter(devices::Column::IsActive.eq(true))
+            .filter(devices::Column::SignedPrekeyCreatedAt.lt(cutoff));
+
+        let stale_signed_prekeys = stale.clone().count(db).await?;
+        let stale_devices = stale
+            .order_by_asc(devices::Column::SignedPrekeyCreatedAt)
+            .limit(STALE_DEVICES_LIMIT)
+            .all(db)
+            .await?
+            .into_iter()
+            .map(|d| StaleSignedPreKeyDevice {
+                user_id: d.user_id,
+                device_id: d.device_id,
+                signed_prekey_id: d.signed_prekey_id,
+                signed_prekey_created_at: d.signed_prekey_created_at.into(),
+                last_seen_at: d.last_seen_at.into(),
+            })
+            .collect();
+
+        audit::record(db, AuditEvent::by_admin(admin_id, None, audit::ADMIN_KEY_METRICS_VIEWED))
+            .await?;
+
+        Ok(KeyMetricsResponse {
+            rotation_interval_seconds: rotation_seconds,
+            active_devices,
+            stale_signed_prekeys,
+            stale_devices,
+        })
+    }
+}
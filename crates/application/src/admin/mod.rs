@@ -0,0 +1,11 @@
+pub mod dtos;
+pub mod metrics;
+pub mod use_cases;
+pub mod users;
+
+pub use metrics::AdminKeyMetricsUseCase;
+pub use use_cases::{AdminLoginUseCase, AuthenticateAdminUseCase, CreateAdminUseCase};
+pub use users::{
+    AdminClearRateLimitsUseCase, AdminGetUserUseCase, AdminListAuditLogsUseCase,
+    AdminListDevicesUseCase, AdminLookupUserUseCase, AdminUnlinkDeviceUseCase,
+};
@@ -0,0 +1,243 @@
+use crate::admin::dtos::*;
+use crate::audit::{self, AuditEvent};
+use crate::rate_limit::check_rate_limit;
+use crate::{AppError, AppResult};
+use argon2::{
+    password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
+    Argon2,
+};
+use chrono::{Duration, Utc};
+use core::entities::admins;
+use jsonwebtoken::{decode, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use once_cell::sync::Lazy;
+use redis::aio::MultiplexedConnection;
+use regex::Regex;
+use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set};
+use tracing::{info, instrument, warn};
+use uuid::Uuid;
+use validator::Validate;
+
+// ============ Constants ============
+
+pub const ADMIN_TOKEN_AUDIENCE: &str = "vyry-admin";
+const ADMIN_ROLE: &str = "admin";
+const ADMIN_TOKEN_TTL_MINUTES: i64 = 30;
+const ADMIN_PASSWORD_MIN_LENGTH: usize = 12;
+const LOGIN_MAX_ATTEMPTS: u32 = 10;
+const LOGIN_WINDOW_SECONDS: i64 = 900;
+
+static ADMIN_USERNAME_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^[a-z0-9][a-z0-9._-]{2,63}$").unwrap());
+
+/// Verified against when the username is unknown, so both cases cost the same
+static DUMMY_PASSWORD_HASH: Lazy<String> = Lazy::new(|| {
+    hash_password("not-a-real-admin-password").expect("hashing a constant password")
+});
+
+// ============ Admin Login Use Case ============
+
+pub struct AdminLoginUseCase;
+
+impl AdminLoginUseCase {
+    #[instrument(skip(db, redis_conn, admin_jwt_secret, req), fields(username = %req.username))]
+    pub async fn execute(
+        db: &DatabaseConnection,
+        redis_conn: &mut MultiplexedConnection,
+        admin_jwt_secret: &str,
+        req: AdminLoginRequest,
+    ) -> AppResult<AdminLoginResponse> {
+        req.validate()
+            .map_err(|e| AppError::Validation(e.to_string()))?;
+
+        let username = req.username.to_lowercase();
+        check_rate_limit(
+            redis_conn,
+            &format!("admin_login:{}", username),
+            LOGIN_MAX_ATTEMPTS,
+            LOGIN_WINDOW_SECONDS,
+            "Too many login attempts. Please try again later.",
+        )
+        .await?;
+
+        let admin = admins::Entity::find()
+            .filter(admins::Column::Username.eq(&username))
+            .filter(admins::Column::IsActive.eq(true))
+            .one(db)
+            .await?;
+
+        let password_hash = admin
+            .as_ref()
+            .map(|a| a.password_hash.as_str())
+            .unwrap_or(DUMMY_PASSWORD_HASH.as_str());
+        let verified = verify_password(&req.password, password_hash)?;
+
+        let admin = match admin {
+            Some(admin) if verified => admin,
+            _ => {
+                warn!("Failed admin login for {}", username);
+                return Err(AppError::Authentication("Invalid username or password".to_string()));
+            }
+        };
+
+        let now = Utc::now();
+        let expires_at = now + Duration::minutes(ADMIN_TOKEN_TTL_MINUTES);
+        let claims = AdminClaims {
+            sub: admin.admin_id.to_string(),
+            aud: ADMIN_TOKEN_AUDIENCE.to_string(),
+            role: ADMIN_ROLE.to_string(),
+            iat: now.timestamp(),
+            exp: expires_at.timestamp(),
+        };
+        let access_token = encode(
+            &Header::default(),
+            &claims,
+            &EncodingKey::from_secret(admin_jwt_secret.as_bytes()),
+        )
+        .map_err(|e| AppError::Authentication(format!("JWT encoding error: {}", e)))?;
+
+        let admin_id = admin.admin_id;
+        let mut active_admin: admins::ActiveModel = admin.into();
+        active_admin.last_login_at = Set(Some(now.into()));
+        active_admin.update(db).await?;
+
+        audit::record(db, AuditEvent::by_admin(admin_id, None, audit::ADMIN_LOGIN)).await?;
+
+        info!("Admin {} logged in", username);
+
+        Ok(AdminLoginResponse {
+            admin_id,
+            access_token,
+            expires_at,
+        })
+    }
+}
+
+// ============ Authenticate Admin Use Case ============
+
+pub struct AuthenticateAdminUseCase;
+
+impl AuthenticateAdminUseCase {
+    /// Resolve an admin token to the admin it was issued to. Deactivated
+    /// admins lose access immediately rather than when their token expires.
+    pub async fn execute(
+        db: &DatabaseConnection,
+        admin_jwt_secret: &str,
+        token: &str,
+    ) -> AppResult<Uuid> {
+        let mut validation = Validation::new(Algorithm::HS256);
+        validation.set_audience(&[ADMIN_TOKEN_AUDIENCE]);
+
+        let claims = decode::<AdminClaims>(
+            token,
+            &DecodingKey::from_secret(admin_jwt_secret.as_bytes()),
+            &validation,
+        )
+        .map_err(|_| AppError::Authentication("Invalid or expired admin token".to_string()))?
+        .claims;
+
+        if claims.role != ADMIN_ROLE {
+            return Err(AppError::Authorization("Admin role required".to_string()));
+        }
+
+        let admin_id: Uuid = claims.sub.parse()?;
+        admins::Entity::find_by_id(admin_id)
+            .filter(admins::Column::IsActive.eq(true))
+            .one(db)
+            .await?
+            .ok_or_else(|| AppError::Authentication("Admin account is disabled".to_string()))?;
+
+        Ok(admin_id)
+    }
+}
+
+// ============ Create Admin Use Case ============
+
+pub struct CreateAdminUseCase;
+
+impl CreateAdminUseCase {
+    /// Used by the `create-admin` binary; there is no HTTP endpoint for it
+    #[instrument(skip(db, password))]
+    pub async fn execute(
+        db: &DatabaseConnection,
+        username: &str,
+        password: &str,
+    ) -> AppResult<CreateAdminResponse> {
+        let username = username.to_lowercase();
+        if !ADMIN_USERNAME_REGEX.is_match(&username) {
+            return Err(AppError::Validation(
+                "Username must be 3-64 characters: letters, digits, '.', '_' or '-'".to_string(),
+            ));
+        }
+        if password.chars().count() < ADMIN_PASSWORD_MIN_LENGTH {
+            return Err(AppError::Validation(format!(
+                "Password must be at least {} characters",
+                ADMIN_PASSWORD_MIN_LENGTH
+            )));
+        }
+
+        let exists = admins::Entity::find()
+            .filter(admins::Column::Username.eq(&username))
+            .one(db)
+            .await?
+            .is_some();
+        if exists {
+            return Err(AppError::Validation(format!("Admin {} already exists", username)));
+        }
+
+        let admin = admins::ActiveModel {
+            admin_id: Set(Uuid::new_v4()),
+            username: Set(username.clone()),
+            password_hash: Set(hash_password(password)?),
+            is_active: Set(true),
+            created_at: Set(Utc::now().into()),
+            last_login_at: Set(None),
+        }
+        .insert(db)
+        .await?;
+
+        info!("Created admin {}", username);
+
+        Ok(CreateAdminResponse {
+            admin_id: admin.admin_id,
+            username: admin.username,
+        })
+    }
+}
+
+// ============ Helpers ============
+
+fn hash_password(password: &str) -> AppResult<String> {
+    let salt = SaltString::generate(&mut OsRng);
+    Ok(Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .map_err(|e| AppError::Cryptographic(format!("Failed to hash password: {}", e)))?
+        .to_string())
+}
+
+fn verify_password(password: &str, password_hash: &str) -> AppResult<bool> {
+    let parsed_hash = PasswordHash::new(password_hash)
+        .map_err(|e| AppError::Cryptographic(format!("Invalid password hash: {}", e)))?;
+    Ok(Argon2::default()
+        .verify_password(password.as_bytes(), &parsed_hash)
+        .is_ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_admin_password_round_trip() {
+        let hash = hash_password("correct horse battery").unwrap();
+        assert!(verify_password("correct horse battery", &hash).unwrap());
+        assert!(!verify_password("wrong horse battery", &hash).unwrap());
+        assert!(!verify_password("correct horse battery", &DUMMY_PASSWORD_HASH).unwrap());
+    }
+
+    #[test]
+    fn test_admin_username_format() {
+        assert!(ADMIN_USERNAME_REGEX.is_match("ops.alice"));
+        assert!(!ADMIN_USERNAME_REGEX.is_match("al"));
+        assert!(!ADMIN_USERNAME_REGEX.is_match("alice@example.com"));
+    }
+}
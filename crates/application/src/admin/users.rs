@@ -0,0 +1,400 @@
+use crate::admin::dtos::*;
+use crate::audit::{self, AuditEvent};
+use crate::auth::otp;
+use crate::auth::phone::PhoneHasher;
+use crate::keys::use_cases::is_signed_prekey_stale;
+use crate::{AppError, AppResult};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use core::entities::{audit_logs, devices, kyber_prekeys, one_time_prekeys, users};
+use redis::aio::MultiplexedConnection;
+use redis::AsyncCommands;
+use sea_orm::sea_query::{Expr, Func};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter,
+    QueryOrder, QuerySelect, Set,
+};
+use std::collections::{HashMap, HashSet};
+use tracing::{info, instrument};
+use uuid::Uuid;
+use validator::Validate;
+
+// ============ Constants ============
+
+/// Prefixes of the per-user rate limit and lockout counters, "<prefix>:<user_id>"
+const USER_RATE_LIMIT_PREFIXES: &[&str] = &[
+    "change_number",
+    "contact_discovery_hashes",
+    "contact_discovery_requests",
+    "data_export",
+    "pin_attempts",
+    "pin_change",
+    "pin_remove",
+    "pin_reset",
+    "prekey_fetch",
+    "reports",
+    "username_lookup",
+];
+const AUDIT_LOG_DEFAULT_LIMIT: u64 = 100;
+
+// ============ Get User Use Case ============
+
+pub struct AdminGetUserUseCase;
+
+impl AdminGetUserUseCase {
+    #[instrument(skip(db), fields(admin_id = %admin_id, user_id = %user_id))]
+    pub async fn execute(
+        db: &DatabaseConnection,
+        admin_id: Uuid,
+        user_id: Uuid,
+    ) -> AppResult<AdminUserResponse> {
+        let user = find_user(db, user_id).await?;
+        audit::record(
+            db,
+            AuditEvent::by_admin(admin_id, Some(user_id), audit::ADMIN_USER_VIEWED),
+        )
+        .await?;
+        to_response(db, user).await
+    }
+}
+
+// ============ Lookup User Use Case ============
+
+pub struct AdminLookupUserUseCase;
+
+impl AdminLookupUserUseCase {
+    #[instrument(skip(db, phone_hasher, query), fields(admin_id = %admin_id))]
+    pub async fn execute(
+        db: &DatabaseConnection,
+        phone_hasher: &PhoneHasher,
+        admin_id: Uuid,
+        query: LookupUserQuery,
+    ) -> AppResult<AdminUserResponse> {
+        query
+            .validate()
+            .map_err(|e| AppError::Validation(e.to_string()))?;
+
+        let (lookup, condition) = match (query.username, query.phone_hash) {
+            (Some(username), None) => (
+                "username",
+                Expr::expr(Func::lower(Expr::col((
+                    users::Entity,
+                    users::Column::Username,
+                ))))
+                .eq(username.trim().to_lowercase()),
+            ),
+            (None, Some(phone_hash)) => {
+                let token = BASE64.decode(phone_hash.trim()).map_err(|_| {
+                    AppError::Validation("phone_hash must be base64 encoded".to_string())
+                })?;
+                if token.len() != 32 {
+                    return Err(AppError::Validation(
+                        "phone_hash must be a 32-byte SHA-256 digest".to_string(),
+                    ));
+                }
+                (
+                    "phone_hash",
+                    users::Column::PhoneNumberHash.eq(phone_hasher.hash_token(&token)),
+                )
+            }
+            _ => {
+                return Err(AppError::Validation(
+                    "Specify exactly one of username or phone_hash".to_string(),
+                ))
+            }
+        };
+
+        let user = users::Entity::find()
+            .filter(condition)
+            .one(db)
+            .await?
+            .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+
+        audit::record(
+            db,
+            AuditEvent::by_admin(admin_id, Some(user.user_id), audit::ADMIN_USER_VIEWED)
+                .with_details(serde_json::json!({ "lookup": lookup })),
+        )
+        .await?;
+
+        to_response(db, user).await
+    }
+}
+
+// ============ List User Devices Use Case ============
+
+pub struct AdminListDevicesUseCase;
+
+impl AdminListDevicesUseCase {
+    #[instrument(skip(db), fields(admin_id = %admin_id, user_id = %user_id))]
+    pub async fn execute(
+        db: &DatabaseConnection,
+        admin_id: Uuid,
+        user_id: Uuid,
+        rotation_seconds: i64,
+    ) -> AppResult<AdminDevicesResponse> {
+        find_user(db, user_id).await?;
+
+        let user_devices = devices::Entity::find()
+            .filter(devices::Column::UserId.eq(user_id))
+            .order_by_asc(devices::Column::DeviceId)
+            .all(db)
+            .await?;
+
+        let prekey_counts: HashMap<i64, i64> = one_time_prekeys::Entity::find()
+            .select_only()
+            .column(one_time_prekeys::Column::DeviceId)
+            .column_as(one_time_prekeys::Column::PrekeyId.count(), "count")
+            .filter(
+                one_time_prekeys::Column::DeviceId.is_in(user_devices.iter().map(|d| d.device_id)),
+            )
+            .group_by(one_time_prekeys::Column::DeviceId)
+            .into_tuple::<(i64, i64)>()
+            .all(db)
+            .await?
+            .into_iter()
+            .collect();
+
+        let mut pq_prekey_counts: HashMap<i64, u64> = HashMap::new();
+        let mut pq_last_resort: HashSet<i64> = HashSet::new();
+        let pq_rows: Vec<(i64, bool, i64)> = kyber_prekeys::Entity::find()
+            .select_only()
+            .column(kyber_prekeys::Column::DeviceId)
+            .column(kyber_prekeys::Column::IsLastResort)
+            .column_as(kyber_prekeys::Column::PrekeyId.count(), "count")
+            .filter(kyber_prekeys::Column::DeviceId.is_in(user_devices.iter().map(|d| d.device_id)))
+            .group_by(kyber_prekeys::Column::DeviceId)
+            .group_by(kyber_prekeys::Column::IsLastResort)
+            .into_tuple()
+            .all(db)
+            .await?;
+        for (device_id, is_last_resort, count) in pq_rows {
+            if is_last_resort {
+                pq_last_resort.insert(device_id);
+            } else {
+                pq_prekey_counts.insert(device_id, count as u64);
+            }
+        }
+
+        audit::record(
+            db,
+            AuditEvent::by_admin(admin_id, Some(user_id), audit::ADMIN_DEVICES_VIEWED),
+        )
+        .await?;
+
+        let devices = user_devices
+            .into_iter()
+            .map(|d| AdminDevice {
+                signed_prekey_stale: is_signed_prekey_stale(&d, rotation_seconds),
+                signed_prekey_created_at: d.signed_prekey_created_at.into(),
+                one_time_prekeys_remaining: prekey_counts.get(&d.device_id).copied().unwrap_or(0)
+                    as u64,
+                pq_prekeys_remaining: pq_prekey_counts.get(&d.device_id).copied().unwrap_or(0),
+                pq_last_resort: pq_last_resort.contains(&d.device_id),
+                device_id: d.device_id,
+                device_uuid: d.device_uuid,
+                device_name: d.device_name,
+                platform: d.platform,
+                device_type: d.device_type,
+                is_active: d.is_active,
+                last_seen_at: d.last_seen_at.into(),
+                created_at: d.created_at.into(),
+                linked_at: d.linked_at.map(Into::into),
+                signed_prekey_id: d.signed_prekey_id,
+            })
+            .collect();
+
+        Ok(AdminDevicesResponse { user_id, devices })
+    }
+}
+
+// ============ Force Unlink Device Use Case ============
+
+pub struct AdminUnlinkDeviceUseCase;
+
+impl AdminUnlinkDeviceUseCase {
+    /// Deactivate any device of the user, primary included. Its refresh token
+    /// stops working at once; the caller closes its socket.
+    #[instrument(skip(db), fields(admin_id = %admin_id, user_id = %user_id, device_id = device_id))]
+    pub async fn execute(
+        db: &DatabaseConnection,
+        admin_id: Uuid,
+        user_id: Uuid,
+        device_id: i64,
+    ) -> AppResult<AdminUnlinkDeviceResponse> {
+        let device = devices::Entity::find_by_id(device_id)
+            .filter(devices::Column::UserId.eq(user_id))
+            .one(db)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Device not found".to_string()))?;
+
+        let was_active = device.is_active;
+        if was_active {
+            let mut active_device: devices::ActiveModel = device.into();
+            active_device.is_active = Set(false);
+            active_device.update(db).await?;
+        }
+
+        audit::record(
+            db,
+            AuditEvent::by_admin(admin_id, Some(user_id), audit::ADMIN_DEVICE_UNLINKED)
+                .with_details(
+                    serde_json::json!({ "device_id": device_id, "was_active": was_active }),
+                ),
+        )
+        .await?;
+
+        info!(
+            "Admin {} unlinked device {} of user {}",
+            admin_id, device_id, user_id
+        );
+
+        Ok(AdminUnlinkDeviceResponse {
+            user_id,
+            device_id,
+            unlinked: was_active,
+        })
+    }
+}
+
+// ============ Clear Rate Limits Use Case ============
+
+pub struct AdminClearRateLimitsUseCase;
+
+impl AdminClearRateLimitsUseCase {
+    /// Remove the user's rate limit counters, PIN attempt counter and OTP
+    /// lockouts. Issued OTP codes are left alone.
+    #[instrument(skip(db, redis_conn), fields(admin_id = %admin_id, user_id = %user_id))]
+    pub async fn execute(
+        db: &DatabaseConnection,
+        redis_conn: &mut MultiplexedConnection,
+        admin_id: Uuid,
+        user_id: Uuid,
+    ) -> AppResult<ClearRateLimitsResponse> {
+        let user = find_user(db, user_id).await?;
+
+        let mut keys: Vec<String> = USER_RATE_LIMIT_PREFIXES
+            .iter()
+            .map(|prefix| format!("{}:{}", prefix, user_id))
+            .collect();
+        // A purged account's number is a tombstone with no counters behind it
+        if user.purged_at.is_none() {
+            let phone = user.phone_number.as_str();
+            keys.extend([
+                format!("otp_attempts:{}", phone),
+                otp::otp_failures_key(otp::OtpPurpose::Login, phone),
+                otp::otp_failures_key(otp::OtpPurpose::ChangeNumber, phone),
+                otp::phone_failures_key(phone),
+                otp::lockout_key(phone),
+                otp::lockout_count_key(phone),
+            ]);
+        }
+
+        let mut cleared = Vec::new();
+        for key in keys {
+            let removed: u32 = redis_conn.del(&key).await?;
+            if removed > 0 {
+                cleared.push(key);
+            }
+        }
+
+        audit::record(
+            db,
+            AuditEvent::by_admin(admin_id, Some(user_id), audit::ADMIN_RATE_LIMITS_CLEARED)
+                .with_details(serde_json::json!({ "cleared": cleared })),
+        )
+        .await?;
+
+        info!(
+            "Admin {} cleared {} rate limit keys of user {}",
+            admin_id,
+            cleared.len(),
+            user_id
+        );
+
+        Ok(ClearRateLimitsResponse { user_id, cleared })
+    }
+}
+
+// ============ List Audit Logs Use Case ============
+
+pub struct AdminListAuditLogsUseCase;
+
+impl AdminListAuditLogsUseCase {
+    /// A user's audit history, newest first
+    #[instrument(skip(db), fields(admin_id = %admin_id, user_id = %user_id))]
+    pub async fn execute(
+        db: &DatabaseConnection,
+        admin_id: Uuid,
+        user_id: Uuid,
+        query: ListAuditLogsQuery,
+    ) -> AppResult<ListAuditLogsResponse> {
+        query
+            .validate()
+            .map_err(|e| AppError::Validation(e.to_string()))?;
+
+        let mut select = audit_logs::Entity::find().filter(audit_logs::Column::UserId.eq(user_id));
+        if let Some(before) = query.before {
+            select = select.filter(audit_logs::Column::AuditId.lt(before));
+        }
+
+        let entries = select
+            .order_by_desc(audit_logs::Column::AuditId)
+            .limit(query.limit.unwrap_or(AUDIT_LOG_DEFAULT_LIMIT))
+            .all(db)
+            .await?
+            .into_iter()
+            .map(|entry| AuditLogEntry {
+                audit_id: entry.audit_id,
+                user_id: entry.user_id,
+                device_id: entry.device_id,
+                actor: entry.actor,
+                action: entry.action,
+                details: entry.details,
+                created_at: entry.created_at.into(),
+            })
+            .collect();
+
+        audit::record(
+            db,
+            AuditEvent::by_admin(admin_id, Some(user_id), audit::ADMIN_AUDIT_LOG_VIEWED),
+        )
+        .await?;
+
+        Ok(ListAuditLogsResponse { entries })
+    }
+}
+
+// ============ Helpers ============
+
+async fn find_user(db: &DatabaseConnection, user_id: Uuid) -> AppResult<users::Model> {
+    users::Entity::find_by_id(user_id)
+        .one(db)
+        .await?
+        .ok_or_else(|| AppError::NotFound("User not found".to_string()))
+}
+
+async fn to_response(db: &DatabaseConnection, user: users::Model) -> AppResult<AdminUserResponse> {
+    let active_devices = devices::Entity::find()
+        .filter(devices::Column::UserId.eq(user.user_id))
+        .filter(devices::Column::IsActive.eq(true))
+        .count(db)
+        .await?;
+
+    Ok(AdminUserResponse {
+        user_id: user.user_id,
+        phone_number: user.phone_number,
+        username: user.username,
+        display_name: user.display_name,
+        created_at: user.created_at.into(),
+        updated_at: user.updated_at.into(),
+        last_seen_at: user.last_seen_at.map(Into::into),
+        is_deleted: user.is_deleted,
+        deleted_at: user.deleted_at.map(Into::into),
+        purged_at: user.purged_at.map(Into::into),
+        suspended_until: user.suspended_until.map(Into::into),
+        banned_at: user.banned_at.map(Into::into),
+        has_pin: user.pin_hash.is_some(),
+        registration_lock: user.registration_lock,
+        active_devices,
+    })
+}
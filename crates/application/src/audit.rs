@@ -0,0 +1,100 @@
+use crate::AppResult;
+use chrono::Utc;
+use core::entities::audit_logs;
+use sea_orm::{ActiveModelTrait, ConnectionTrait, Set};
+use uuid::Uuid;
+
+// ============ Audit Actions ============
+
+pub const ACCOUNT_BANNED: &str = "account.banned";
+pub const ACCOUNT_DELETION_REQUESTED: &str = "account.deletion_requested";
+pub const ACCOUNT_RESTORED: &str = "account.restored";
+pub const ACCOUNT_PURGED: &str = "account.purged";
+pub const ACCOUNT_SUSPENDED: &str = "account.suspended";
+pub const ACCOUNT_WARNED: &str = "account.warned";
+pub const ADMIN_AUDIT_LOG_VIEWED: &str = "admin.audit_log_viewed";
+pub const ADMIN_DEVICE_UNLINKED: &str = "admin.device_unlinked";
+pub const ADMIN_DEVICES_VIEWED: &str = "admin.devices_viewed";
+pub const ADMIN_KEY_METRICS_VIEWED: &str = "admin.key_metrics_viewed";
+pub const ADMIN_LOGIN: &str = "admin.login";
+pub const ADMIN_RATE_LIMITS_CLEARED: &str = "admin.rate_limits_cleared";
+pub const ADMIN_USER_VIEWED: &str = "admin.user_viewed";
+pub const PHONE_NUMBER_CHANGED: &str = "phone_number.changed";
+/// Written by the phone number canonicalization migration for accounts it
+/// could not rewrite, not by application code
+pub const PHONE_NUMBER_CANONICALIZATION_SKIPPED: &str = "phone_number.canonicalization_skipped";
+pub const PIN_CHANGED: &str = "pin.changed";
+pub const PIN_REMOVED: &str = "pin.removed";
+pub const PIN_RESET_REQUESTED: &str = "pin.reset_requested";
+pub const PIN_RESET_CANCELLED: &str = "pin.reset_cancelled";
+pub const PIN_RESET_COMPLETED: &str = "pin.reset_completed";
+pub const REPORT_DISMISSED: &str = "report.dismissed";
+pub const REPORT_VIEWED: &str = "report.viewed";
+pub const SIGNED_PREKEY_ROTATED: &str = "signed_prekey.rotated";
+/// Written by the case-insensitive username migration, not by application code
+pub const USERNAME_RENAMED: &str = "username.renamed";
+
+/// A security-relevant event to be appended to `audit_logs`
+pub struct AuditEvent {
+    pub user_id: Option<Uuid>,
+    pub device_id: Option<i64>,
+    pub actor: String,
+    pub action: &'static str,
+    pub details: serde_json::Value,
+}
+
+impl AuditEvent {
+    /// Event performed by a user from one of their own devices
+    pub fn by_user(user_id: Uuid, device_id: i64, action: &'static str) -> Self {
+        Self {
+            user_id: Some(user_id),
+            device_id: Some(device_id),
+            actor: format!("user:{}", user_id),
+            action,
+            details: serde_json::json!({}),
+        }
+    }
+
+    /// Event performed by the server itself, e.g. a scheduled job
+    pub fn by_system(user_id: Uuid, action: &'static str) -> Self {
+        Self {
+            user_id: Some(user_id),
+            device_id: None,
+            actor: "system".to_string(),
+            action,
+            details: serde_json::json!({}),
+        }
+    }
+
+    /// Event performed by an operator through the admin API
+    pub fn by_admin(admin_id: Uuid, user_id: Option<Uuid>, action: &'static str) -> Self {
+        Self {
+            user_id,
+            device_id: None,
+            actor: format!("admin:{}", admin_id),
+            action,
+            details: serde_json::json!({}),
+        }
+    }
+
+    pub fn with_details(mut self, details: serde_json::Value) -> Self {
+        self.details = details;
+        self
+    }
+}
+
+/// Append an audit event. Accepts a connection or a transaction so the
+/// event commits atomically with the change it describes.
+pub async fn record<C: ConnectionTrait>(db: &C, event: AuditEvent) -> AppResult<()> {
+    let entry = audit_logs::ActiveModel {
+        user_id: Set(event.user_id),
+        device_id: Set(event.device_id),
+        actor: Set(event.actor),
+        action: Set(event.action.to_string()),
+        details: Set(event.details),
+        created_at: Set(Utc::now().into()),
+        ..Default::default()
+    };
+    entry.insert(db).await?;
+    Ok(())
+}
@@ -1,3 +1,5 @@
+use crate::keys::dtos::DeviceKeysUpload;
+use crate::users::dtos::{ProfileVisibility, Visibility};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
@@ -39,6 +41,8 @@ pub struct VerifyOtpRequest {
     pub device_name: Option<String>,
     #[validate(range(min = 1, max = 4, message = "Platform must be between 1-4"))]
     pub platform: Option<i16>, // 1 = iOS, 2 = Android, 3 = Web, 4 = Desktop
+    /// Generated on the device; checked by `parse_device_keys`
+    pub keys: DeviceKeysUpload,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -50,6 +54,8 @@ pub struct VerifyOtpResponse {
     pub is_new_user: bool,
     pub requires_profile_setup: bool,
     pub requires_pin: bool, // true if registration_lock is enabled
+    pub account_restored: bool, // true if this login cancelled a pending deletion
+    pub identity_key_changed: bool, // true if the replaced device had a different identity key
 }
 
 // ============ Profile Setup ============
@@ -60,6 +66,7 @@ pub struct SetupProfileRequest {
     pub display_name: String,
     #[serde(default)]
     #[validate(length(max = 50, message = "Username must be at most 50 characters"))]
+    #[validate(custom(function = "crate::auth::validate_username"))]
     pub username: Option<String>,
     #[serde(default)]
     #[validate(length(max = 500, message = "Bio must be at most 500 characters"))]
@@ -92,6 +99,8 @@ pub struct GetProfileResponse {
     pub bio: Option<String>,
     pub profile_picture_url: Option<String>,
     pub background_image_url: Option<String>,
+    pub discoverability: Visibility,
+    pub profile_visibility: ProfileVisibility,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -139,9 +148,44 @@ pub struct SkipPinSetupResponse {
     pub message: String,
 }
 
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct ChangePinRequest {
+    #[validate(length(min = 4, max = 32, message = "PIN must be between 4-32 characters"))]
+    pub current_pin: String,
+    #[validate(length(min = 4, max = 32, message = "PIN must be between 4-32 characters"))]
+    pub new_pin: String,
+    #[validate(must_match(other = "new_pin", message = "PINs do not match"))]
+    pub confirm_pin: String,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
+pub struct ChangePinResponse {
+    pub registration_lock_enabled: bool,
+    pub message: String,
+}
+
+/// Either the current PIN or a freshly requested OTP must be provided
+#[derive(Debug, Serialize, Deserialize, Validate)]
 pub struct RemovePinRequest {
-    pub current_pin: String,
+    #[serde(default)]
+    #[validate(length(min = 4, max = 32, message = "PIN must be between 4-32 characters"))]
+    pub current_pin: Option<String>,
+    #[serde(default)]
+    #[validate(length(min = 6, max = 6, message = "OTP must be exactly 6 digits"))]
+    pub otp: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RemovePinResponse {
+    pub removed: bool,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ForgotPinResponse {
+    pub status: String, // "pending", "cancelled" or "completed"
+    pub reset_available_at: Option<DateTime<Utc>>,
+    pub message: String,
 }
 
 // ============ Token Refresh ============
@@ -178,6 +222,8 @@ pub struct CompleteLinkingRequest {
     pub device_uuid: Uuid,
     pub device_name: Option<String>,
     pub platform: Option<i16>,
+    /// Generated on the new device; stored once the primary approves
+    pub keys: DeviceKeysUpload,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -232,6 +278,31 @@ pub struct UnlinkDeviceResponse {
     pub message: String,
 }
 
+// ============ Change Number ============
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct ChangeNumberRequest {
+    #[validate(custom(function = "crate::auth::validate_phone_number"))]
+    pub new_phone_number: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct ConfirmChangeNumberRequest {
+    #[validate(custom(function = "crate::auth::validate_phone_number"))]
+    pub new_phone_number: String,
+    #[validate(length(min = 6, max = 6, message = "OTP must be exactly 6 digits"))]
+    pub otp: String,
+    /// Required when the account has a PIN
+    pub pin: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ChangeNumberResponse {
+    pub user_id: Uuid,
+    pub phone_number: String,
+    pub changed_at: DateTime<Utc>,
+}
+
 // ============ Auth Error Types ============
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -1,4 +1,6 @@
 pub mod dtos;
+pub mod otp;
+pub mod phone;
 pub mod use_cases;
 mod validation;
 
@@ -6,8 +8,10 @@ pub use validation::{PHONE_REGEX, USERNAME_REGEX, validate_phone_number, validat
 
 // Re-export all use cases for easier imports
 pub use use_cases::{
-    ApproveLinkingUseCase, CheckPinStatusUseCase, CompleteLinkingUseCase, CreateLinkingSessionUseCase,
-    GetProfileUseCase, ListDevicesUseCase, RefreshTokenUseCase, RequestOtpUseCase,
-    SetupPinUseCase, SetupProfileUseCase, SkipPinSetupUseCase, UnlinkDeviceUseCase, VerifyOtpUseCase,
-    VerifyPinUseCase,
+    ApproveLinkingUseCase, AuthenticateUseCase, CancelPinResetUseCase, ChangeNumberUseCase, ChangePinUseCase,
+    CheckPinStatusUseCase, CompleteLinkingUseCase, CompletePinResetUseCase,
+    CreateLinkingSessionUseCase, GetProfileUseCase, ListDevicesUseCase, RefreshTokenUseCase,
+    RemovePinUseCase, RequestChangeNumberUseCase, RequestOtpUseCase, RequestPinResetUseCase,
+    SetupPinUseCase, SetupProfileUseCase, SkipPinSetupUseCase,
+    UnlinkDeviceUseCase, VerifyOtpUseCase, VerifyPinUseCase,
 };
\ No newline at end of file
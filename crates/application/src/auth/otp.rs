@@ -0,0 +1,255 @@
+use crate::{AppError, AppResult};
+use once_cell::sync::Lazy;
+use redis::aio::MultiplexedConnection;
+use redis::{AsyncCommands, Script};
+use tracing::warn;
+
+// ============ Constants ============
+
+pub const OTP_EXPIRY_SECONDS: u64 = 180;
+/// Wrong guesses allowed against a single issued code before it is invalidated
+const OTP_MAX_FAILED_ATTEMPTS: u32 = 5;
+/// Wrong guesses allowed per phone number (across codes and IPs) per window
+const PHONE_MAX_FAILED_ATTEMPTS: u32 = 15;
+const PHONE_FAILURE_WINDOW_SECONDS: i64 = 86400;
+const LOCKOUT_BASE_SECONDS: u64 = 60;
+const LOCKOUT_MAX_SECONDS: u64 = 86400;
+
+// ============ Purposes ============
+
+/// What an issued code authorises. Codes are stored per purpose so one sent to
+/// confirm a number change cannot be redeemed as a login, or the reverse.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OtpPurpose {
+    Login,
+    ChangeNumber,
+}
+
+impl OtpPurpose {
+    pub const ALL: [OtpPurpose; 2] = [OtpPurpose::Login, OtpPurpose::ChangeNumber];
+
+    /// Appended to the key prefixes; login keys keep their original names
+    fn namespace(self) -> &'static str {
+        match self {
+            OtpPurpose::Login => "",
+            OtpPurpose::ChangeNumber => "change_number:",
+        }
+    }
+}
+
+// ============ Redis Keys ============
+
+pub fn otp_key(purpose: OtpPurpose, phone_number: &str) -> String {
+    format!("otp:{}{}", purpose.namespace(), phone_number)
+}
+
+/// Failed guesses against the currently issued code
+pub fn otp_failures_key(purpose: OtpPurpose, phone_number: &str) -> String {
+    format!("otp_failures:{}{}", purpose.namespace(), phone_number)
+}
+
+/// Failed guesses against any code for this phone number
+pub fn phone_failures_key(phone_number: &str) -> String {
+    format!("otp_phone_failures:{}", phone_number)
+}
+
+pub fn lockout_key(phone_number: &str) -> String {
+    format!("otp_lockout:{}", phone_number)
+}
+
+/// Number of lockouts applied in the current window, drives the exponential backoff
+pub fn lockout_count_key(phone_number: &str) -> String {
+    format!("otp_lockout_count:{}", phone_number)
+}
+
+/// Atomically compare and consume an OTP.
+///
+/// KEYS[1] = otp key, KEYS[2] = per-OTP failure counter
+/// ARGV[1] = submitted code, ARGV[2] = max failed attempts
+///
+/// Returns `{status, failed_attempts}` where status is
+/// 1 = verified and deleted, 0 = no code issued or expired,
+/// -1 = mismatch, -2 = mismatch and the code has been invalidated.
+///
+/// The comparison runs inside the script so the GET, compare and DEL cannot
+/// race with a concurrent verification. It folds every byte with XOR/OR so its
+/// running time does not depend on where the first mismatch is.
+static VERIFY_OTP_SCRIPT: Lazy<Script> = Lazy::new(|| {
+    Script::new(
+        r#"
+local stored = redis.call('GET', KEYS[1])
+if not stored then
+  return {0, 0}
+end
+local submitted = ARGV[1]
+local diff = 0
+if string.len(stored) ~= string.len(submitted) then
+  diff = 1
+end
+for i = 1, string.len(stored) do
+  local a = string.byte(stored, i)
+  local b = string.byte(submitted, i) or 0
+  diff = bit.bor(diff, bit.bxor(a, b))
+end
+if diff == 0 then
+  redis.call('DEL', KEYS[1], KEYS[2])
+  return {1, 0}
+end
+local attempts = redis.call('INCR', KEYS[2])
+local ttl = redis.call('PTTL', KEYS[1])
+if ttl > 0 then
+  redis.call('PEXPIRE', KEYS[2], ttl)
+end
+if attempts >= tonumber(ARGV[2]) then
+  redis.call('DEL', KEYS[1], KEYS[2])
+  return {-2, attempts}
+end
+return {-1, attempts}
+"#,
+    )
+});
+
+/// Lockout duration after the `n`-th lockout in the window: 1m, 2m, 4m, ... capped at 24h
+pub fn lockout_seconds(lockout_count: u32) -> u64 {
+    let exponent = lockout_count.saturating_sub(1).min(20);
+    (LOCKOUT_BASE_SECONDS << exponent).min(LOCKOUT_MAX_SECONDS)
+}
+
+/// Fail with `LockedOut` while the phone number is locked
+pub async fn check_lockout(
+    redis_conn: &mut MultiplexedConnection,
+    phone_number: &str,
+) -> AppResult<()> {
+    let ttl: i64 = redis_conn.ttl(lockout_key(phone_number)).await?;
+    if ttl > 0 {
+        return Err(AppError::LockedOut(
+            "Too many incorrect OTP attempts. Please try again later.".to_string(),
+            ttl as u64,
+        ));
+    }
+    Ok(())
+}
+
+/// Store a freshly generated code, resetting the per-OTP failure counter
+pub async fn store_otp(
+    redis_conn: &mut MultiplexedConnection,
+    purpose: OtpPurpose,
+    phone_number: &str,
+    otp: &str,
+) -> AppResult<()> {
+    redis_conn
+        .set_ex::<_, _, ()>(otp_key(purpose, phone_number), otp, OTP_EXPIRY_SECONDS)
+        .await?;
+    redis_conn
+        .del::<_, ()>(otp_failures_key(purpose, phone_number))
+        .await?;
+    Ok(())
+}
+
+/// Verify an OTP issued for `purpose` and delete it on success. Wrong guesses
+/// are counted per code and per phone number across purposes; exhausting
+/// either applies an exponential lockout.
+pub async fn verify_and_consume(
+    redis_conn: &mut MultiplexedConnection,
+    purpose: OtpPurpose,
+    phone_number: &str,
+    otp: &str,
+) -> AppResult<()> {
+    check_lockout(redis_conn, phone_number).await?;
+
+    let (status, failed_attempts): (i64, i64) = VERIFY_OTP_SCRIPT
+        .key(otp_key(purpose, phone_number))
+        .key(otp_failures_key(purpose, phone_number))
+        .arg(otp)
+        .arg(OTP_MAX_FAILED_ATTEMPTS)
+        .invoke_async(redis_conn)
+        .await?;
+
+    match status {
+        1 => {
+            redis_conn
+                .del::<_, ()>(&[phone_failures_key(phone_number), lockout_count_key(phone_number)])
+                .await?;
+            Ok(())
+        }
+        0 => Err(AppError::Authentication("Invalid or expired OTP".to_string())),
+        _ => {
+            let phone_failures: u32 = redis_conn.incr(phone_failures_key(phone_number), 1).await?;
+            if phone_failures == 1 {
+                redis_conn
+                    .expire::<_, ()>(phone_failures_key(phone_number), PHONE_FAILURE_WINDOW_SECONDS)
+                    .await?;
+            }
+
+            warn!(
+                "Invalid OTP attempt for phone number: {} ({} for this code, {} in window)",
+                phone_number, failed_attempts, phone_failures
+            );
+
+            if status == -2 || phone_failures >= PHONE_MAX_FAILED_ATTEMPTS {
+                let seconds = apply_lockout(redis_conn, phone_number).await?;
+                return Err(AppError::LockedOut(
+                    "Too many incorrect OTP attempts. Please request a new code later.".to_string(),
+                    seconds,
+                ));
+            }
+
+            Err(AppError::Authentication("Invalid or expired OTP".to_string()))
+        }
+    }
+}
+
+async fn apply_lockout(
+    redis_conn: &mut MultiplexedConnection,
+    phone_number: &str,
+) -> AppResult<u64> {
+    let count_key = lockout_count_key(phone_number);
+    let lockout_count: u32 = redis_conn.incr(&count_key, 1).await?;
+    redis_conn
+        .expire::<_, ()>(&count_key, PHONE_FAILURE_WINDOW_SECONDS)
+        .await?;
+
+    let seconds = lockout_seconds(lockout_count);
+    redis_conn
+        .set_ex::<_, _, ()>(lockout_key(phone_number), lockout_count, seconds)
+        .await?;
+    // Any code still outstanding is useless once locked out
+    let outstanding: Vec<String> = OtpPurpose::ALL
+        .iter()
+        .flat_map(|&purpose| [otp_key(purpose, phone_number), otp_failures_key(purpose, phone_number)])
+        .collect();
+    redis_conn.del::<_, ()>(outstanding).await?;
+
+    warn!("OTP lockout #{} applied to phone number: {} for {}s", lockout_count, phone_number, seconds);
+    Ok(seconds)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lockout_seconds_doubles_and_caps() {
+        assert_eq!(lockout_seconds(0), 60);
+        assert_eq!(lockout_seconds(1), 60);
+        assert_eq!(lockout_seconds(2), 120);
+        assert_eq!(lockout_seconds(3), 240);
+        assert_eq!(lockout_seconds(11), 61440);
+        assert_eq!(lockout_seconds(12), LOCKOUT_MAX_SECONDS);
+        assert_eq!(lockout_seconds(u32::MAX), LOCKOUT_MAX_SECONDS);
+    }
+
+    #[test]
+    fn test_otp_keys_are_namespaced_by_purpose() {
+        let phone = "+66812345678";
+        assert_eq!(otp_key(OtpPurpose::Login, phone), "otp:+66812345678");
+        assert_eq!(
+            otp_key(OtpPurpose::ChangeNumber, phone),
+            "otp:change_number:+66812345678"
+        );
+        assert_ne!(
+            otp_failures_key(OtpPurpose::Login, phone),
+            otp_failures_key(OtpPurpose::ChangeNumber, phone)
+        );
+    }
+}
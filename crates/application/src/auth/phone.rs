@@ -0,0 +1,279 @@
+use crate::{AppError, AppResult};
+use hmac::{Hmac, Mac};
+use phonenumber::{Mode, PhoneNumber, Type};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+
+/// A phone number parsed against libphonenumber metadata and normalised to E.164
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CanonicalPhone {
+    /// E.164 form, e.g. "+66812345678". Used for lookups, Redis keys and hashing.
+    pub e164: String,
+    /// ISO 3166-1 alpha-2 region, e.g. "TH". `None` for non-geographic codes.
+    pub country: Option<String>,
+}
+
+impl CanonicalPhone {
+    /// SHA-256 of the E.164 form. This is what clients compute for contact discovery.
+    pub fn hash(&self) -> Vec<u8> {
+        phone_number_hash(&self.e164)
+    }
+}
+
+pub fn phone_number_hash(e164: &str) -> Vec<u8> {
+    Sha256::digest(e164.as_bytes()).to_vec()
+}
+
+/// Derives the value stored in `users.phone_number_hash`.
+///
+/// Without a key this is the plain SHA-256 of the E.164 number. With a key it
+/// is HMAC-SHA256(key, SHA-256(e164)), so the stored column cannot be reversed
+/// by hashing the phone-number space without the server secret. Clients always
+/// submit plain SHA-256 tokens; the server applies the key before lookup.
+#[derive(Clone, Default)]
+pub struct PhoneHasher {
+    key: Option<Vec<u8>>,
+}
+
+impl PhoneHasher {
+    pub fn new(key: Option<&str>) -> Self {
+        Self {
+            key: key.filter(|k| !k.is_empty()).map(|k| k.as_bytes().to_vec()),
+        }
+    }
+
+    pub fn is_keyed(&self) -> bool {
+        self.key.is_some()
+    }
+
+    /// Stored hash for a canonical number
+    pub fn hash(&self, phone: &CanonicalPhone) -> Vec<u8> {
+        self.hash_token(&phone.hash())
+    }
+
+    /// Map a client-submitted SHA-256 token to its stored form
+    pub fn hash_token(&self, token: &[u8]) -> Vec<u8> {
+        match &self.key {
+            Some(key) => {
+                let mut mac = Hmac::<Sha256>::new_from_slice(key)
+                    .expect("HMAC accepts keys of any length");
+                mac.update(token);
+                mac.finalize().into_bytes().to_vec()
+            }
+            None => token.to_vec(),
+        }
+    }
+}
+
+/// Parse and canonicalise an international phone number.
+///
+/// National trunk prefixes are stripped ("+660812345678" becomes
+/// "+66812345678"), numbers outside the region's numbering plan are rejected,
+/// and only ranges that can receive SMS (mobile, or fixed-line-or-mobile where
+/// the plan cannot tell them apart) are accepted.
+pub fn canonicalize(raw: &str) -> AppResult<CanonicalPhone> {
+    let number = parse_valid(raw)?;
+
+    match number.number_type(&phonenumber::metadata::DATABASE) {
+        Type::Mobile | Type::FixedLineOrMobile => {}
+        _ => {
+            return Err(AppError::Validation(
+                "Only mobile phone numbers can be registered".to_string(),
+            ))
+        }
+    }
+
+    Ok(CanonicalPhone {
+        e164: number.format().mode(Mode::E164).to_string(),
+        country: number.country().id().map(|id| id.as_ref().to_string()),
+    })
+}
+
+fn parse_valid(raw: &str) -> AppResult<PhoneNumber> {
+    let trimmed = raw.trim();
+    if !trimmed.starts_with('+') {
+        return Err(AppError::Validation(
+            "Phone number must be in international format starting with +".to_string(),
+        ));
+    }
+
+    let number = phonenumber::parse(None, trimmed)
+        .map_err(|_| AppError::Validation("Invalid phone number".to_string()))?;
+
+    if !phonenumber::is_valid(&number) {
+        return Err(AppError::Validation("Invalid phone number".to_string()));
+    }
+
+    Ok(number)
+}
+
+/// Whether a raw string parses to a valid number in its region's numbering plan
+pub fn is_valid_number(raw: &str) -> bool {
+    parse_valid(raw).is_ok()
+}
+
+// ============ Per-Country Policy ============
+
+/// Per-country registration rules, loaded from configuration
+#[derive(Debug, Clone, Default)]
+pub struct PhonePolicy {
+    /// If non-empty, only these regions may register
+    pub allowed_countries: Vec<String>,
+    /// Regions that may never register (applied after the allow list)
+    pub denied_countries: Vec<String>,
+    /// OTP requests allowed per rate-limit window, overriding the default per region
+    pub otp_limits: HashMap<String, u32>,
+}
+
+impl PhonePolicy {
+    /// Build from comma-separated settings, e.g. allowed = "TH,US",
+    /// denied = "KP", otp_limits = "TH:5,US:3"
+    pub fn parse(allowed: &str, denied: &str, otp_limits: &str) -> Result<Self, String> {
+        let list = |s: &str| -> Vec<String> {
+            s.split(',')
+                .map(|c| c.trim().to_uppercase())
+                .filter(|c| !c.is_empty())
+                .collect()
+        };
+
+        let mut limits = HashMap::new();
+        for entry in otp_limits.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let (country, limit) = entry
+                .split_once(':')
+                .ok_or_else(|| format!("Invalid OTP limit entry '{}', expected CC:N", entry))?;
+            let limit: u32 = limit
+                .trim()
+                .parse()
+                .map_err(|_| format!("Invalid OTP limit for {}", country))?;
+            limits.insert(country.trim().to_uppercase(), limit);
+        }
+
+        Ok(Self {
+            allowed_countries: list(allowed),
+            denied_countries: list(denied),
+            otp_limits: limits,
+        })
+    }
+
+    /// Reject numbers from regions that are not allowed to register
+    pub fn check(&self, phone: &CanonicalPhone) -> AppResult<()> {
+        let country = phone.country.as_deref().unwrap_or("001");
+
+        if !self.allowed_countries.is_empty()
+            && !self.allowed_countries.iter().any(|c| c == country)
+        {
+            return Err(AppError::Validation(
+                "Phone numbers from this country are not supported".to_string(),
+            ));
+        }
+
+        if self.denied_countries.iter().any(|c| c == country) {
+            return Err(AppError::Validation(
+                "Phone numbers from this country are not supported".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// OTP requests allowed per window for this number's region
+    pub fn otp_limit(&self, phone: &CanonicalPhone, default: u32) -> u32 {
+        phone
+            .country
+            .as_ref()
+            .and_then(|c| self.otp_limits.get(c))
+            .copied()
+            .unwrap_or(default)
+    }
+
+    /// Canonicalise a raw number and apply the region rules in one step
+    pub fn canonicalize(&self, raw: &str) -> AppResult<CanonicalPhone> {
+        let phone = canonicalize(raw)?;
+        self.check(&phone)?;
+        Ok(phone)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_canonicalize_strips_trunk_prefix() {
+        let with_trunk = canonicalize("+660812345678").unwrap();
+        let canonical = canonicalize("+66812345678").unwrap();
+        assert_eq!(with_trunk.e164, "+66812345678");
+        assert_eq!(with_trunk, canonical);
+        assert_eq!(with_trunk.hash(), canonical.hash());
+        assert_eq!(canonical.country.as_deref(), Some("TH"));
+    }
+
+    #[test]
+    fn test_canonicalize_strips_formatting() {
+        let phone = canonicalize("+1 (650) 253-0000").unwrap();
+        assert_eq!(phone.e164, "+16502530000");
+        assert_eq!(phone.country.as_deref(), Some("US"));
+    }
+
+    #[test]
+    fn test_canonicalize_rejects_invalid_and_non_mobile() {
+        // Missing +
+        assert!(canonicalize("66812345678").is_err());
+        // Too short for any plan
+        assert!(canonicalize("+66").is_err());
+        // Unassigned range
+        assert!(canonicalize("+66112345678").is_err());
+        // Thai fixed line (Bangkok)
+        assert!(canonicalize("+6621234567").is_err());
+        // UK freephone
+        assert!(canonicalize("+448001234567").is_err());
+    }
+
+    #[test]
+    fn test_phone_hasher_keyed_and_unkeyed() {
+        let phone = canonicalize("+66812345678").unwrap();
+
+        let plain = PhoneHasher::new(None);
+        assert!(!plain.is_keyed());
+        assert_eq!(plain.hash(&phone), phone.hash());
+        assert_eq!(PhoneHasher::new(Some("")).hash(&phone), phone.hash());
+
+        let keyed = PhoneHasher::new(Some("discovery-secret"));
+        assert!(keyed.is_keyed());
+        assert_eq!(keyed.hash(&phone).len(), 32);
+        assert_ne!(keyed.hash(&phone), phone.hash());
+        assert_eq!(keyed.hash(&phone), keyed.hash_token(&phone.hash()));
+        assert_ne!(
+            keyed.hash(&phone),
+            PhoneHasher::new(Some("other-secret")).hash(&phone)
+        );
+    }
+
+    #[test]
+    fn test_policy_allow_and_deny_lists() {
+        let th = canonicalize("+66812345678").unwrap();
+        let us = canonicalize("+16502530000").unwrap();
+
+        let allow_th = PhonePolicy::parse("th", "", "").unwrap();
+        assert!(allow_th.check(&th).is_ok());
+        assert!(allow_th.check(&us).is_err());
+
+        let deny_us = PhonePolicy::parse("", "US", "").unwrap();
+        assert!(deny_us.check(&th).is_ok());
+        assert!(deny_us.check(&us).is_err());
+    }
+
+    #[test]
+    fn test_policy_otp_limits() {
+        let th = canonicalize("+66812345678").unwrap();
+        let us = canonicalize("+16502530000").unwrap();
+
+        let policy = PhonePolicy::parse("", "", "TH:2, us:7").unwrap();
+        assert_eq!(policy.otp_limit(&th, 5), 2);
+        assert_eq!(policy.otp_limit(&us, 5), 7);
+        assert_eq!(PhonePolicy::default().otp_limit(&th, 5), 5);
+
+        assert!(PhonePolicy::parse("", "", "TH").is_err());
+        assert!(PhonePolicy::parse("", "", "TH:x").is_err());
+    }
+}
@@ -1,4 +1,13 @@
+use crate::account::use_cases::restore_account;
+use crate::audit::{self, AuditEvent};
 use crate::auth::dtos::*;
+use crate::auth::otp::{self, OtpPurpose};
+use crate::auth::phone::{CanonicalPhone, PhoneHasher, PhonePolicy};
+use crate::keys::parse_device_keys;
+use crate::media::use_cases::served_image_url;
+use crate::keys::use_cases::{record_identity_key, store_kyber_prekeys, store_one_time_prekeys};
+use crate::rate_limit::check_rate_limit;
+use crate::users::dtos::{ProfileVisibility, Visibility};
 use crate::{AppError, AppResult};
 use tracing::{info, instrument, warn};
 use validator::Validate;
@@ -11,19 +20,18 @@ use argon2::{
     Argon2,
 };
 use chrono::{Duration, Utc};
-use core::entities::{device_linking_sessions, devices, one_time_prekeys, users};
-use infrastructure::crypto::signal::{
-    generate_identity_keypair, generate_prekeys, generate_registration_id, generate_signed_prekey,
+use core::entities::{
+    banned_phone_numbers, device_linking_sessions, devices, kyber_prekeys, one_time_prekeys, users,
 };
-use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
+use jsonwebtoken::{decode, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
 use rand::Rng;
 use redis::aio::MultiplexedConnection;
 use redis::AsyncCommands;
+use sea_orm::sea_query::{Expr, Func};
 use sea_orm::{
-    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, Set,
-    TransactionTrait,
+    ActiveModelTrait, ColumnTrait, Condition, ConnectionTrait, DatabaseConnection, EntityTrait,
+    QueryFilter, QueryOrder, Set, TransactionTrait,
 };
-use sha2::{Digest, Sha256};
 use uuid::Uuid;
 
 // ============ Config ============
@@ -32,16 +40,24 @@ pub struct AuthConfig {
     pub jwt_secret: String,
     pub jwt_expiration: i64,
     pub refresh_token_expiration: i64,
+    pub phone_hasher: PhoneHasher,
 }
 
 // ============ Constants ============
 
-const OTP_EXPIRY_SECONDS: u64 = 180;
 const OTP_MAX_ATTEMPTS: u32 = 5;
 const PIN_MIN_LENGTH: usize = 4;
 const PIN_MAX_LENGTH: usize = 32;
+const PIN_MAX_ATTEMPTS: u32 = 5;
+const PIN_LOCKOUT_SECONDS: i64 = 3600;
+const PIN_CHANGE_MAX_REQUESTS: u32 = 5;
+const PIN_CHANGE_WINDOW_SECONDS: i64 = 3600;
+const PIN_RESET_MAX_REQUESTS: u32 = 3;
+const PIN_RESET_WINDOW_SECONDS: i64 = 86400;
+const CHANGE_NUMBER_MAX_REQUESTS: u32 = 5;
+const CHANGE_NUMBER_WINDOW_SECONDS: i64 = 86400;
 const LINKING_SESSION_EXPIRY_MINUTES: i64 = 5;
-const DEVICE_TYPE_PRIMARY: i16 = 1;
+pub(crate) const DEVICE_TYPE_PRIMARY: i16 = 1;
 const DEVICE_TYPE_LINKED: i16 = 2;
 
 // ============ Request OTP Use Case ============
@@ -49,55 +65,19 @@ const DEVICE_TYPE_LINKED: i16 = 2;
 pub struct RequestOtpUseCase;
 
 impl RequestOtpUseCase {
-    #[instrument(skip(redis_conn), fields(phone_number = %req.phone_number))]
+    #[instrument(skip(redis_conn, phone_policy), fields(phone_number = %req.phone_number))]
     pub async fn execute(
         redis_conn: &mut MultiplexedConnection,
+        phone_policy: &PhonePolicy,
         req: RequestOtpRequest,
     ) -> AppResult<String> {
         // Validate input
         req.validate()
             .map_err(|e| AppError::Validation(e.to_string()))?;
 
-        // Check rate limit
-        let attempts_key = format!("otp_attempts:{}", req.phone_number);
-        let attempts: Option<u32> = redis_conn
-            .get(&attempts_key)
-            .await
-            .map_err(|e| AppError::Redis(e.to_string()))?;
-
-        if attempts.unwrap_or(0) >= OTP_MAX_ATTEMPTS {
-            warn!("Rate limit exceeded for phone number: {}", req.phone_number);
-            return Err(AppError::RateLimitExceeded(
-                "Too many OTP requests. Please try again later.".to_string(),
-            ));
-        }
-
-        // Generate 6-digit OTP
-        let otp: String = (0..6)
-            .map(|_| rand::thread_rng().gen_range(0..10).to_string())
-            .collect();
-        let key = format!("otp:{}", req.phone_number);
-
-        // Store in Redis with expiration
-        redis_conn
-            .set_ex::<_, _, ()>(&key, &otp, OTP_EXPIRY_SECONDS)
-            .await
-            .map_err(|e| AppError::Redis(e.to_string()))?;
-
-        // Increment attempts counter
-        redis_conn
-            .incr::<_, _, ()>(&attempts_key, 1)
-            .await
-            .map_err(|e| AppError::Redis(e.to_string()))?;
-        redis_conn
-            .expire::<_, ()>(&attempts_key, 600) // 10 minutes window
-            .await
-            .map_err(|e| AppError::Redis(e.to_string()))?;
-
-        info!("OTP generated for phone number: {}", req.phone_number);
-        // TODO: In production, send OTP via SMS provider
-        // For now, return it for testing
-        Ok(otp)
+        // Canonicalise so every spelling of a number shares one OTP and rate limit
+        let phone = phone_policy.canonicalize(&req.phone_number)?;
+        issue_otp(redis_conn, phone_policy, &phone, OtpPurpose::Login).await
     }
 }
 
@@ -106,95 +86,80 @@ impl RequestOtpUseCase {
 pub struct VerifyOtpUseCase;
 
 impl VerifyOtpUseCase {
-    #[instrument(skip(db, redis_conn, config), fields(phone_number = %req.phone_number))]
+    #[instrument(skip(db, redis_conn, config, phone_policy), fields(phone_number = %req.phone_number))]
     pub async fn execute(
         db: &DatabaseConnection,
         redis_conn: &mut MultiplexedConnection,
         config: &AuthConfig,
+        phone_policy: &PhonePolicy,
         req: VerifyOtpRequest,
     ) -> AppResult<VerifyOtpResponse> {
         // Validate input
         req.validate()
             .map_err(|e| AppError::Validation(e.to_string()))?;
+        // Checked before the OTP is consumed so a bad upload can be retried
+        let keys = parse_device_keys(&req.keys)?;
 
-        let key = format!("otp:{}", req.phone_number);
-        
-        // First check if key exists
-        let exists: bool = redis_conn
-            .exists(&key)
-            .await
-            .map_err(|e| AppError::Redis(e.to_string()))?;
-        
-        if !exists {
-            warn!("OTP not found for phone number: {}", req.phone_number);
-            return Err(AppError::Authentication("Invalid or expired OTP".to_string()));
-        }
-        
-        // Check TTL to verify it hasn't expired
-        let ttl: i64 = redis_conn
-            .ttl(&key)
-            .await
-            .map_err(|e| AppError::Redis(e.to_string()))?;
-        
-        // TTL returns -2 if key doesn't exist (shouldn't happen after EXISTS check)
-        // -1 if key exists but has no expiration (shouldn't happen for OTP)
-        // > 0 if key exists and hasn't expired
-        // 0 if key exists but has just expired
-        if ttl <= 0 {
-            warn!("OTP expired for phone number: {} (TTL: {})", req.phone_number, ttl);
-            return Err(AppError::Authentication("Invalid or expired OTP".to_string()));
-        }
-        
-        // Get the stored OTP value
-        let stored_otp: Option<String> = redis_conn
-            .get(&key)
-            .await
-            .map_err(|e| AppError::Redis(e.to_string()))?;
-
-        // Double-check: if key was deleted between EXISTS and GET (race condition)
-        if stored_otp.is_none() {
-            warn!("OTP key was deleted between checks for phone number: {}", req.phone_number);
-            return Err(AppError::Authentication("Invalid or expired OTP".to_string()));
-        }
-
-        // Verify OTP value matches
-        if stored_otp.unwrap() != req.otp {
-            warn!("Invalid OTP attempt for phone number: {}", req.phone_number);
-            return Err(AppError::Authentication("Invalid or expired OTP".to_string()));
-        }
+        let phone = phone_policy.canonicalize(&req.phone_number)?;
+        let phone_hash = config.phone_hasher.hash(&phone);
 
-        // Delete OTP after successful verification
-        redis_conn
-            .del::<_, ()>(&key)
-            .await
-            .map_err(|e| AppError::Redis(e.to_string()))?;
+        // Atomically verify and consume the OTP; wrong guesses count towards lockout
+        otp::verify_and_consume(redis_conn, OtpPurpose::Login, &phone.e164, &req.otp).await?;
 
         // Start transaction
         let txn = db.begin().await.map_err(|e| AppError::Database(e.to_string()))?;
 
         // Check if user exists
         let existing_user = users::Entity::find()
-            .filter(users::Column::PhoneNumber.eq(&req.phone_number))
+            .filter(users::Column::PhoneNumber.eq(&phone.e164))
             .one(&txn)
             .await
             .map_err(|e| AppError::Database(e.to_string()))?;
 
+        let mut account_restored = false;
+        let mut previous_identity_key = None;
         let (user, is_new_user) = match existing_user {
             Some(u) => {
+                ensure_not_moderated(&u)?;
+
+                // Peers compare the new device's key against the one it replaces
+                previous_identity_key =
+                    Self::replaced_identity_key(&txn, u.user_id, req.device_uuid).await?;
+
                 // Existing user - kick old primary device if this is a new primary login
                 Self::kick_old_primary_device(&txn, u.user_id).await
                     .map_err(|e| AppError::Database(e.to_string()))?;
+
+                // Logging in during the deletion grace period cancels the deletion
+                let u = if u.is_deleted {
+                    account_restored = true;
+                    restore_account(&txn, u).await?
+                } else {
+                    u
+                };
+
+                // Re-derive the stored hash if the discovery key has changed since registration
+                let u = if u.phone_number_hash != phone_hash {
+                    let mut active_user: users::ActiveModel = u.into();
+                    active_user.phone_number_hash = Set(phone_hash.clone());
+                    active_user
+                        .update(&txn)
+                        .await
+                        .map_err(|e| AppError::Database(e.to_string()))?
+                } else {
+                    u
+                };
                 (u, false)
             }
             None => {
+                ensure_number_not_banned(&txn, &phone).await?;
+
                 // Create new user
-                // Hash phone number for privacy-preserving lookups
-                let phone_hash = Sha256::digest(req.phone_number.as_bytes()).to_vec();
-                
+                // Hash the canonical number for privacy-preserving lookups
                 let new_user = users::ActiveModel {
                     user_id: Set(Uuid::new_v4()),
-                    phone_number: Set(req.phone_number.clone()),
-                    phone_number_hash: Set(phone_hash),
+                    phone_number: Set(phone.e164.clone()),
+                    phone_number_hash: Set(phone_hash.clone()),
                     username: Set(None),
                     display_name: Set(None),
                     bio: Set(None),
@@ -204,12 +169,21 @@ impl VerifyOtpUseCase {
                     is_online: Set(false),
                     is_deleted: Set(false),
                     deleted_at: Set(None),
+                    purged_at: Set(None),
+                    suspended_until: Set(None),
+                    banned_at: Set(None),
                     created_at: Set(Utc::now().into()),
                     updated_at: Set(Utc::now().into()),
                     pin_hash: Set(None),
                     registration_lock: Set(false),
                     registration_lock_expires_at: Set(None),
                     pin_set_at: Set(None),
+                    pin_reset_at: Set(None),
+                    discoverability: Set(Visibility::Everyone.as_i16()),
+                    display_name_visibility: Set(Visibility::Everyone.as_i16()),
+                    bio_visibility: Set(Visibility::Everyone.as_i16()),
+                    profile_picture_visibility: Set(Visibility::Everyone.as_i16()),
+                    background_image_visibility: Set(Visibility::Everyone.as_i16()),
                 };
                 (new_user.insert(&txn).await.map_err(|e| AppError::Database(e.to_string()))?, true)
             }
@@ -217,16 +191,7 @@ impl VerifyOtpUseCase {
 
         // Check if profile setup is required
         let requires_profile_setup = user.display_name.is_none();
-        let requires_pin = user.registration_lock && user.pin_hash.is_some();
-
-        // Generate Signal Keys
-        let (identity_key_pair, _) = generate_identity_keypair()
-            .map_err(|e| AppError::Cryptographic(e.to_string()))?;
-        let registration_id = generate_registration_id();
-        let signed_prekey = generate_signed_prekey(&identity_key_pair, 1)
-            .map_err(|e| AppError::Cryptographic(e.to_string()))?;
-        let one_time_prekeys_list = generate_prekeys(1, 100)
-            .map_err(|e| AppError::Cryptographic(e.to_string()))?;
+        let requires_pin = user.is_registration_lock_active();
 
         // Check if device_uuid already exists (could be from previous failed registration)
         // If it exists, delete the old device and its prekeys to allow re-registration
@@ -242,6 +207,11 @@ impl VerifyOtpUseCase {
                 .exec(&txn)
                 .await
                 .map_err(|e| AppError::Database(e.to_string()))?;
+            kyber_prekeys::Entity::delete_many()
+                .filter(kyber_prekeys::Column::DeviceId.eq(existing_device.device_id))
+                .exec(&txn)
+                .await
+                .map_err(|e| AppError::Database(e.to_string()))?;
             
             // Delete the old device
             devices::Entity::delete_by_id(existing_device.device_id)
@@ -256,11 +226,12 @@ impl VerifyOtpUseCase {
             device_uuid: Set(req.device_uuid),
             device_name: Set(req.device_name.clone()),
             platform: Set(req.platform.unwrap_or(1)),
-            identity_key_public: Set(identity_key_pair.public_key),
-            registration_id: Set(registration_id as i32),
-            signed_prekey_id: Set(signed_prekey.id as i32),
-            signed_prekey_public: Set(signed_prekey.public_key),
-            signed_prekey_signature: Set(signed_prekey.signature),
+            identity_key_public: Set(keys.identity_key),
+            registration_id: Set(keys.registration_id),
+            signed_prekey_id: Set(keys.signed_prekey.id),
+            signed_prekey_public: Set(keys.signed_prekey.public_key),
+            signed_prekey_signature: Set(keys.signed_prekey.signature),
+            signed_prekey_created_at: Set(Utc::now().into()),
             last_seen_at: Set(Utc::now().into()),
             created_at: Set(Utc::now().into()),
             device_type: Set(DEVICE_TYPE_PRIMARY),
@@ -272,14 +243,24 @@ impl VerifyOtpUseCase {
 
         let device = device.insert(&txn).await.map_err(|e| AppError::Database(e.to_string()))?;
 
-        // Insert One Time Prekeys
-        for prekey in one_time_prekeys_list {
-            let otpk = one_time_prekeys::ActiveModel {
-                device_id: Set(device.device_id),
-                prekey_id: Set(prekey.id as i32),
-                public_key: Set(prekey.public_key),
-            };
-            otpk.insert(&txn).await.map_err(|e| AppError::Database(e.to_string()))?;
+        store_one_time_prekeys(&txn, device.device_id, keys.one_time_prekeys).await?;
+        store_kyber_prekeys(&txn, device.device_id, keys.kyber_prekeys).await?;
+        let identity_key_changed = record_identity_key(
+            &txn,
+            user.user_id,
+            device.device_id,
+            &device.identity_key_public,
+            previous_identity_key,
+        )
+        .await?;
+
+        if account_restored {
+            audit::record(
+                &txn,
+                AuditEvent::by_user(user.user_id, device.device_id, audit::ACCOUNT_RESTORED),
+            )
+            .await?;
+            info!("Account deletion cancelled for user: {}", user.user_id);
         }
 
         txn.commit().await.map_err(|e| AppError::Database(e.to_string()))?;
@@ -296,9 +277,37 @@ impl VerifyOtpUseCase {
             is_new_user,
             requires_profile_setup,
             requires_pin,
+            account_restored,
+            identity_key_changed,
         })
     }
 
+    /// Identity key of the device a new primary login replaces: the same
+    /// installation re-registering, otherwise the current primary device
+    async fn replaced_identity_key(
+        txn: &sea_orm::DatabaseTransaction,
+        user_id: Uuid,
+        device_uuid: Uuid,
+    ) -> AppResult<Option<Vec<u8>>> {
+        let replaced = devices::Entity::find()
+            .filter(devices::Column::UserId.eq(user_id))
+            .filter(
+                Condition::any()
+                    .add(devices::Column::DeviceUuid.eq(device_uuid))
+                    .add(
+                        Condition::all()
+                            .add(devices::Column::DeviceType.eq(DEVICE_TYPE_PRIMARY))
+                            .add(devices::Column::IsActive.eq(true)),
+                    ),
+            )
+            .order_by_desc(devices::Column::DeviceUuid.eq(device_uuid))
+            .order_by_desc(devices::Column::CreatedAt)
+            .one(txn)
+            .await?;
+
+        Ok(replaced.map(|device| device.identity_key_public))
+    }
+
     async fn kick_old_primary_device(
         txn: &sea_orm::DatabaseTransaction,
         user_id: Uuid,
@@ -404,9 +413,12 @@ impl SetupProfileUseCase {
                     return Err(AppError::Validation("Username can only contain letters, numbers, underscores, and hyphens (3-50 characters)".to_string()));
                 }
 
-                // Check if username is already taken
+                // Check if username is already taken (usernames are case-insensitive)
                 let existing_user = users::Entity::find()
-                    .filter(users::Column::Username.eq(username))
+                    .filter(
+                        Expr::expr(Func::lower(Expr::col(users::Column::Username)))
+                            .eq(username.to_lowercase()),
+                    )
                     .filter(users::Column::UserId.ne(user_id))
                     .one(db)
                     .await?;
@@ -425,41 +437,30 @@ impl SetupProfileUseCase {
             }
         }
 
-        // Validate profile picture URL if provided
-        if let Some(ref url) = req.profile_picture_url {
-            let url = url.trim();
-            if !url.is_empty() {
-                // Basic URL validation
-                if !url.starts_with("http://") && !url.starts_with("https://") {
-                    return Err(AppError::Validation("Profile picture URL must be a valid HTTP/HTTPS URL".to_string()));
-                }
-
-                if url.len() > 2048 {
-                    return Err(AppError::Validation("Profile picture URL too long (max 2048 characters)".to_string()));
-                }
-
-                // Additional URL format validation - check for valid URL structure
-                if url.len() < 10 || !url.contains("://") {
-                    return Err(AppError::Validation("Invalid profile picture URL format".to_string()));
-                }
-
-                // Check for valid domain structure (at least one dot after protocol)
-                if let Some(domain_part) = url.split("://").nth(1) {
-                    if domain_part.is_empty() || !domain_part.contains('.') {
-                        return Err(AppError::Validation("Invalid profile picture URL format".to_string()));
-                    }
-                } else {
-                    return Err(AppError::Validation("Invalid profile picture URL format".to_string()));
-                }
-            }
-        }
-
         // Find and update user
         let user = users::Entity::find_by_id(user_id)
             .one(db)
             .await?
             .ok_or_else(|| AppError::NotFound(format!("User {} not found", user_id)))?;
 
+        // Images are uploaded through the media endpoints so they are re-encoded
+        // and served by us. Here they can only be kept as they are or cleared,
+        // which keeps external hotlinks and tracking pixels out of profiles.
+        for (requested, current, kind) in [
+            (&req.profile_picture_url, &user.profile_picture, "avatar"),
+            (&req.background_image_url, &user.background_image, "background"),
+        ] {
+            if let Some(url) = requested {
+                let url = url.trim();
+                if !url.is_empty() && current.as_deref() != Some(url) {
+                    return Err(AppError::Validation(format!(
+                        "Upload the {} via POST /api/v1/media/profile/{}",
+                        kind, kind
+                    )));
+                }
+            }
+        }
+
         let now = Utc::now();
         let mut active_user: users::ActiveModel = user.into();
         active_user.display_name = Set(Some(display_name.to_string()));
@@ -501,8 +502,8 @@ impl SetupProfileUseCase {
             display_name: updated_user.display_name.unwrap_or_default(),
             username: updated_user.username,
             bio: updated_user.bio,
-            profile_picture_url: updated_user.profile_picture,
-            background_image_url: updated_user.background_image,
+            profile_picture_url: served_image_url(updated_user.profile_picture),
+            background_image_url: served_image_url(updated_user.background_image),
             updated_at: now,
         })
     }
@@ -528,14 +529,18 @@ impl GetProfileUseCase {
             "****".to_string()
         };
 
+        let profile_visibility = ProfileVisibility::from(&user);
+
         Ok(GetProfileResponse {
             user_id: user.user_id,
             phone_number: masked_phone,
             display_name: user.display_name,
             username: user.username,
             bio: user.bio,
-            profile_picture_url: user.profile_picture,
-            background_image_url: user.background_image,
+            profile_picture_url: served_image_url(user.profile_picture),
+            background_image_url: served_image_url(user.background_image),
+            discoverability: Visibility::from_i16(user.discoverability),
+            profile_visibility,
             created_at: user.created_at.into(),
             updated_at: user.updated_at.into(),
         })
@@ -570,12 +575,7 @@ impl SetupPinUseCase {
         }
 
         // Hash PIN with Argon2
-        let salt = SaltString::generate(&mut OsRng);
-        let argon2 = Argon2::default();
-        let pin_hash = argon2
-            .hash_password(req.pin.as_bytes(), &salt)
-            .map_err(|e| AppError::Cryptographic(format!("Failed to hash PIN: {}", e)))?
-            .to_string();
+        let pin_hash = hash_pin(&req.pin)?;
 
         // Update user
         let user = users::Entity::find_by_id(user_id)
@@ -589,11 +589,10 @@ impl SetupPinUseCase {
         active_user.registration_lock = Set(req.enable_registration_lock);
         active_user.pin_set_at = Set(Some(now.into()));
         active_user.updated_at = Set(now.into());
-
-        if req.enable_registration_lock {
-            // Registration lock never expires unless explicitly disabled
-            active_user.registration_lock_expires_at = Set(None);
-        }
+        // Registration lock never expires unless explicitly disabled; a new PIN
+        // also cancels any pending forgot-PIN reset
+        active_user.registration_lock_expires_at = Set(None);
+        active_user.pin_reset_at = Set(None);
 
         active_user.update(db).await?;
 
@@ -623,7 +622,7 @@ impl VerifyPinUseCase {
         let attempts_key = format!("pin_attempts:{}", user_id);
         let attempts: Option<u32> = redis_conn.get(&attempts_key).await?;
 
-        if attempts.unwrap_or(0) >= 5 {
+        if attempts.unwrap_or(0) >= PIN_MAX_ATTEMPTS {
             // Get TTL to show remaining lockout time
             let ttl: i64 = redis_conn.ttl(&attempts_key).await?;
             let lockout_remaining_seconds = if ttl > 0 {
@@ -672,7 +671,7 @@ impl VerifyPinUseCase {
         } else {
             // Increment failed attempts
             redis_conn.incr::<_, _, ()>(&attempts_key, 1).await?;
-            redis_conn.expire::<_, ()>(&attempts_key, 3600).await?; // 1 hour
+            redis_conn.expire::<_, ()>(&attempts_key, PIN_LOCKOUT_SECONDS).await?;
         }
 
         let current_attempts = if verified {
@@ -684,7 +683,7 @@ impl VerifyPinUseCase {
         Ok(VerifyPinResponse {
             verified,
             has_pin: true, // We know the user has a PIN if we reached this point
-            attempts_remaining: Some(5 - current_attempts),
+            attempts_remaining: Some(PIN_MAX_ATTEMPTS - current_attempts),
             lockout_remaining_seconds: None, // Only set when locked out
         })
     }
@@ -713,21 +712,14 @@ impl RefreshTokenUseCase {
             return Err(AppError::Authentication("Invalid token type".to_string()));
         }
 
-        // Verify device is still active
-        let device = devices::Entity::find_by_id(claims.device_id)
-            .one(db)
-            .await?
-            .ok_or_else(|| AppError::NotFound("Device not found".to_string()))?;
-
-        if !device.is_active {
-            return Err(AppError::Authorization("Device is no longer active".to_string()));
-        }
-
         let user_id: Uuid = claims.sub.parse()?;
 
+        // Verify device is still active and the account is not moderated
+        ensure_session_active(db, user_id, claims.device_id).await?;
+
         // Generate new tokens
         let (access_token, refresh_token) =
-            VerifyOtpUseCase::generate_tokens(config, user_id, device.device_id)?;
+            VerifyOtpUseCase::generate_tokens(config, user_id, claims.device_id)?;
 
         Ok(RefreshTokenResponse {
             access_token,
@@ -736,6 +728,36 @@ impl RefreshTokenUseCase {
     }
 }
 
+// ============ Authenticate Use Case ============
+
+pub struct AuthenticateUseCase;
+
+impl AuthenticateUseCase {
+    /// Resolve a bearer token to its claims. Revoked devices and suspended or
+    /// banned accounts lose access immediately rather than when the token expires.
+    pub async fn execute(
+        db: &DatabaseConnection,
+        jwt_secret: &str,
+        token: &str,
+    ) -> AppResult<Claims> {
+        let claims = decode::<Claims>(
+            token,
+            &DecodingKey::from_secret(jwt_secret.as_bytes()),
+            &Validation::new(Algorithm::HS256),
+        )
+        .map_err(|_| AppError::Authentication("Invalid or expired token".to_string()))?
+        .claims;
+
+        let user_id: Uuid = claims
+            .sub
+            .parse()
+            .map_err(|_| AppError::Authentication("Invalid or expired token".to_string()))?;
+        ensure_session_active(db, user_id, claims.device_id).await?;
+
+        Ok(claims)
+    }
+}
+
 // ============ Create Linking Session Use Case ============
 
 pub struct CreateLinkingSessionUseCase;
@@ -778,6 +800,7 @@ impl CreateLinkingSessionUseCase {
             status: Set(1), // Pending
             new_device_uuid: Set(None),
             new_device_name: Set(None),
+            new_device_keys: Set(None),
             expires_at: Set(expires_at.into()),
             created_at: Set(Utc::now().into()),
             approved_at: Set(None),
@@ -813,6 +836,11 @@ impl CompleteLinkingUseCase {
         db: &DatabaseConnection,
         req: CompleteLinkingRequest,
     ) -> AppResult<CompleteLinkingResponse> {
+        // Reject bad keys now rather than after the primary has approved
+        parse_device_keys(&req.keys)?;
+        let keys = serde_json::to_value(&req.keys)
+            .map_err(|e| AppError::Internal(format!("Failed to store device keys: {}", e)))?;
+
         // Find session by token
         let session = device_linking_sessions::Entity::find()
             .filter(device_linking_sessions::Column::QrCodeToken.eq(&req.qr_code_token))
@@ -829,6 +857,7 @@ impl CompleteLinkingUseCase {
         let mut active_session: device_linking_sessions::ActiveModel = session.clone().into();
         active_session.new_device_uuid = Set(Some(req.device_uuid));
         active_session.new_device_name = Set(req.device_name.clone());
+        active_session.new_device_keys = Set(Some(keys));
         active_session.update(db).await?;
 
         Ok(CompleteLinkingResponse {
@@ -880,14 +909,13 @@ impl ApproveLinkingUseCase {
                 .await?
                 .ok_or_else(|| AppError::NotFound("Primary device not found".to_string()))?;
 
-            // Generate Signal Keys for new device
-            let (identity_key_pair, _) = generate_identity_keypair()
-                .map_err(|e| AppError::Cryptographic(e.to_string()))?;
-            let registration_id = generate_registration_id();
-            let signed_prekey = generate_signed_prekey(&identity_key_pair, 1)
-                .map_err(|e| AppError::Cryptographic(e.to_string()))?;
-            let one_time_prekeys_list = generate_prekeys(1, 100)
-                .map_err(|e| AppError::Cryptographic(e.to_string()))?;
+            // Keys the new device uploaded when it scanned the code
+            let upload = session
+                .new_device_keys
+                .clone()
+                .and_then(|keys| serde_json::from_value(keys).ok())
+                .ok_or_else(|| AppError::NotFound("No device waiting for approval".to_string()))?;
+            let keys = parse_device_keys(&upload)?;
 
             // Create linked device
             let new_device = devices::ActiveModel {
@@ -895,11 +923,12 @@ impl ApproveLinkingUseCase {
                 device_uuid: Set(new_device_uuid),
                 device_name: Set(session.new_device_name.clone()),
                 platform: Set(3), // Default to Web for linked devices
-                identity_key_public: Set(identity_key_pair.public_key),
-                registration_id: Set(registration_id as i32),
-                signed_prekey_id: Set(signed_prekey.id as i32),
-                signed_prekey_public: Set(signed_prekey.public_key),
-                signed_prekey_signature: Set(signed_prekey.signature),
+                identity_key_public: Set(keys.identity_key),
+                registration_id: Set(keys.registration_id),
+                signed_prekey_id: Set(keys.signed_prekey.id),
+                signed_prekey_public: Set(keys.signed_prekey.public_key),
+                signed_prekey_signature: Set(keys.signed_prekey.signature),
+                signed_prekey_created_at: Set(Utc::now().into()),
                 last_seen_at: Set(Utc::now().into()),
                 created_at: Set(Utc::now().into()),
                 device_type: Set(DEVICE_TYPE_LINKED),
@@ -911,15 +940,16 @@ impl ApproveLinkingUseCase {
 
             let new_device = new_device.insert(&txn).await?;
 
-            // Insert One Time Prekeys
-            for prekey in one_time_prekeys_list {
-                let otpk = one_time_prekeys::ActiveModel {
-                    device_id: Set(new_device.device_id),
-                    prekey_id: Set(prekey.id as i32),
-                    public_key: Set(prekey.public_key),
-                };
-                otpk.insert(&txn).await?;
-            }
+            store_one_time_prekeys(&txn, new_device.device_id, keys.one_time_prekeys).await?;
+            store_kyber_prekeys(&txn, new_device.device_id, keys.kyber_prekeys).await?;
+            record_identity_key(
+                &txn,
+                new_device.user_id,
+                new_device.device_id,
+                &new_device.identity_key_public,
+                None,
+            )
+            .await?;
 
             // Update session status
             active_session.status = Set(2); // Approved
@@ -1077,3 +1107,605 @@ impl SkipPinSetupUseCase {
         })
     }
 }
+
+// ============ Change PIN Use Case ============
+
+pub struct ChangePinUseCase;
+
+impl ChangePinUseCase {
+    #[instrument(skip(db, redis_conn, req), fields(user_id = %user_id))]
+    pub async fn execute(
+        db: &DatabaseConnection,
+        redis_conn: &mut MultiplexedConnection,
+        user_id: Uuid,
+        device_id: i64,
+        req: ChangePinRequest,
+    ) -> AppResult<ChangePinResponse> {
+        req.validate()
+            .map_err(|e| AppError::Validation(e.to_string()))?;
+
+        check_rate_limit(
+            redis_conn,
+            &format!("pin_change:{}", user_id),
+            PIN_CHANGE_MAX_REQUESTS,
+            PIN_CHANGE_WINDOW_SECONDS,
+            "Too many PIN change requests. Please try again later.",
+        )
+        .await?;
+
+        let user = users::Entity::find_by_id(user_id)
+            .one(db)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("User {} not found", user_id)))?;
+
+        let pin_hash = user
+            .pin_hash
+            .clone()
+            .ok_or_else(|| AppError::Validation("No PIN set".to_string()))?;
+
+        verify_pin_with_lockout(redis_conn, user_id, &pin_hash, &req.current_pin).await?;
+
+        if req.new_pin == req.current_pin {
+            return Err(AppError::Validation(
+                "New PIN must be different from the current PIN".to_string(),
+            ));
+        }
+
+        let new_pin_hash = hash_pin(&req.new_pin)?;
+        let registration_lock_enabled = user.registration_lock;
+
+        let txn = db.begin().await?;
+
+        let now = Utc::now();
+        let mut active_user: users::ActiveModel = user.into();
+        active_user.pin_hash = Set(Some(new_pin_hash));
+        active_user.pin_set_at = Set(Some(now.into()));
+        // Proving knowledge of the PIN cancels any pending forgot-PIN reset
+        active_user.pin_reset_at = Set(None);
+        active_user.updated_at = Set(now.into());
+        active_user.update(&txn).await?;
+
+        audit::record(&txn, AuditEvent::by_user(user_id, device_id, audit::PIN_CHANGED)).await?;
+
+        txn.commit().await?;
+
+        info!("PIN changed for user {}", user_id);
+
+        Ok(ChangePinResponse {
+            registration_lock_enabled,
+            message: "PIN changed successfully".to_string(),
+        })
+    }
+}
+
+// ============ Remove PIN Use Case ============
+
+pub struct RemovePinUseCase;
+
+impl RemovePinUseCase {
+    #[instrument(skip(db, redis_conn, req), fields(user_id = %user_id))]
+    pub async fn execute(
+        db: &DatabaseConnection,
+        redis_conn: &mut MultiplexedConnection,
+        user_id: Uuid,
+        device_id: i64,
+        req: RemovePinRequest,
+    ) -> AppResult<RemovePinResponse> {
+        req.validate()
+            .map_err(|e| AppError::Validation(e.to_string()))?;
+
+        if req.current_pin.is_none() && req.otp.is_none() {
+            return Err(AppError::Validation(
+                "Either current_pin or otp is required".to_string(),
+            ));
+        }
+
+        check_rate_limit(
+            redis_conn,
+            &format!("pin_remove:{}", user_id),
+            PIN_CHANGE_MAX_REQUESTS,
+            PIN_CHANGE_WINDOW_SECONDS,
+            "Too many PIN removal requests. Please try again later.",
+        )
+        .await?;
+
+        let user = users::Entity::find_by_id(user_id)
+            .one(db)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("User {} not found", user_id)))?;
+
+        let pin_hash = user
+            .pin_hash
+            .clone()
+            .ok_or_else(|| AppError::Validation("No PIN set".to_string()))?;
+
+        let method = if let Some(ref pin) = req.current_pin {
+            verify_pin_with_lockout(redis_conn, user_id, &pin_hash, pin).await?;
+            "pin"
+        } else {
+            let code = req.otp.as_deref().unwrap_or_default();
+            otp::verify_and_consume(redis_conn, OtpPurpose::Login, &user.phone_number, code).await?;
+            "otp"
+        };
+
+        let txn = db.begin().await?;
+
+        let mut active_user: users::ActiveModel = user.into();
+        active_user.pin_hash = Set(None);
+        active_user.registration_lock = Set(false);
+        active_user.registration_lock_expires_at = Set(None);
+        active_user.pin_reset_at = Set(None);
+        active_user.updated_at = Set(Utc::now().into());
+        active_user.update(&txn).await?;
+
+        audit::record(
+            &txn,
+            AuditEvent::by_user(user_id, device_id, audit::PIN_REMOVED)
+                .with_details(serde_json::json!({ "method": method })),
+        )
+        .await?;
+
+        txn.commit().await?;
+
+        info!("PIN removed for user {} via {}", user_id, method);
+
+        Ok(RemovePinResponse {
+            removed: true,
+            message: "PIN removed and Registration Lock disabled".to_string(),
+        })
+    }
+}
+
+// ============ Forgot PIN Use Cases ============
+
+pub struct RequestPinResetUseCase;
+
+impl RequestPinResetUseCase {
+    /// Start the forgot-PIN cooling-off period. The PIN and registration lock
+    /// stay in force until `cooldown_seconds` have elapsed, giving the owner's
+    /// other devices time to cancel.
+    #[instrument(skip(db, redis_conn), fields(user_id = %user_id))]
+    pub async fn execute(
+        db: &DatabaseConnection,
+        redis_conn: &mut MultiplexedConnection,
+        user_id: Uuid,
+        device_id: i64,
+        cooldown_seconds: i64,
+    ) -> AppResult<ForgotPinResponse> {
+        check_rate_limit(
+            redis_conn,
+            &format!("pin_reset:{}", user_id),
+            PIN_RESET_MAX_REQUESTS,
+            PIN_RESET_WINDOW_SECONDS,
+            "Too many PIN reset requests. Please try again later.",
+        )
+        .await?;
+
+        let user = users::Entity::find_by_id(user_id)
+            .one(db)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("User {} not found", user_id)))?;
+
+        if user.pin_hash.is_none() {
+            return Err(AppError::Validation("No PIN set".to_string()));
+        }
+
+        // Already pending: never extend or restart the existing cooling-off period
+        if let Some(expires_at) = user.pin_reset_at {
+            return Ok(ForgotPinResponse {
+                status: "pending".to_string(),
+                reset_available_at: Some(expires_at.with_timezone(&Utc)),
+                message: "PIN reset already requested".to_string(),
+            });
+        }
+
+        let reset_available_at = Utc::now() + Duration::seconds(cooldown_seconds);
+
+        let txn = db.begin().await?;
+
+        let mut active_user: users::ActiveModel = user.into();
+        active_user.pin_reset_at = Set(Some(reset_available_at.into()));
+        active_user.updated_at = Set(Utc::now().into());
+        active_user.update(&txn).await?;
+
+        audit::record(
+            &txn,
+            AuditEvent::by_user(user_id, device_id, audit::PIN_RESET_REQUESTED).with_details(
+                serde_json::json!({ "reset_available_at": reset_available_at.to_rfc3339() }),
+            ),
+        )
+        .await?;
+
+        txn.commit().await?;
+
+        warn!("PIN reset requested for user {} from device {}", user_id, device_id);
+
+        Ok(ForgotPinResponse {
+            status: "pending".to_string(),
+            reset_available_at: Some(reset_available_at),
+            message: "PIN reset requested. Your PIN will be cleared after the waiting period".to_string(),
+        })
+    }
+}
+
+pub struct CancelPinResetUseCase;
+
+impl CancelPinResetUseCase {
+    #[instrument(skip(db), fields(user_id = %user_id))]
+    pub async fn execute(
+        db: &DatabaseConnection,
+        user_id: Uuid,
+        device_id: i64,
+    ) -> AppResult<ForgotPinResponse> {
+        let user = users::Entity::find_by_id(user_id)
+            .one(db)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("User {} not found", user_id)))?;
+
+        if user.pin_reset_at.is_none() {
+            return Err(AppError::Validation("No PIN reset is pending".to_string()));
+        }
+
+        let txn = db.begin().await?;
+
+        let mut active_user: users::ActiveModel = user.into();
+        active_user.pin_reset_at = Set(None);
+        active_user.updated_at = Set(Utc::now().into());
+        active_user.update(&txn).await?;
+
+        audit::record(&txn, AuditEvent::by_user(user_id, device_id, audit::PIN_RESET_CANCELLED)).await?;
+
+        txn.commit().await?;
+
+        Ok(ForgotPinResponse {
+            status: "cancelled".to_string(),
+            reset_available_at: None,
+            message: "PIN reset cancelled".to_string(),
+        })
+    }
+}
+
+pub struct CompletePinResetUseCase;
+
+impl CompletePinResetUseCase {
+    #[instrument(skip(db), fields(user_id = %user_id))]
+    pub async fn execute(
+        db: &DatabaseConnection,
+        user_id: Uuid,
+        device_id: i64,
+    ) -> AppResult<ForgotPinResponse> {
+        let user = users::Entity::find_by_id(user_id)
+            .one(db)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("User {} not found", user_id)))?;
+
+        let reset_available_at = user
+            .pin_reset_at
+            .map(|t| t.with_timezone(&Utc))
+            .ok_or_else(|| AppError::Validation("No PIN reset is pending".to_string()))?;
+
+        if Utc::now() < reset_available_at {
+            return Err(AppError::Authorization(format!(
+                "PIN reset is not available until {}",
+                reset_available_at.to_rfc3339()
+            )));
+        }
+
+        let txn = db.begin().await?;
+
+        let mut active_user: users::ActiveModel = user.into();
+        active_user.pin_hash = Set(None);
+        active_user.registration_lock = Set(false);
+        active_user.pin_reset_at = Set(None);
+        active_user.pin_set_at = Set(None);
+        active_user.updated_at = Set(Utc::now().into());
+        active_user.update(&txn).await?;
+
+        audit::record(&txn, AuditEvent::by_user(user_id, device_id, audit::PIN_RESET_COMPLETED)).await?;
+
+        txn.commit().await?;
+
+        warn!("PIN reset completed for user {}", user_id);
+
+        Ok(ForgotPinResponse {
+            status: "completed".to_string(),
+            reset_available_at: None,
+            message: "PIN cleared and Registration Lock disabled".to_string(),
+        })
+    }
+}
+
+// ============ Change Number Use Cases ============
+
+pub struct RequestChangeNumberUseCase;
+
+impl RequestChangeNumberUseCase {
+    /// Send an OTP to the new number. Returns the code, like `RequestOtpUseCase`.
+    #[instrument(skip(db, redis_conn, phone_policy, req), fields(user_id = %user_id))]
+    pub async fn execute(
+        db: &DatabaseConnection,
+        redis_conn: &mut MultiplexedConnection,
+        phone_policy: &PhonePolicy,
+        user_id: Uuid,
+        device_id: i64,
+        req: ChangeNumberRequest,
+    ) -> AppResult<String> {
+        req.validate()
+            .map_err(|e| AppError::Validation(e.to_string()))?;
+
+        let user = load_for_number_change(db, user_id, device_id).await?;
+        let phone = phone_policy.canonicalize(&req.new_phone_number)?;
+        // Whether another account holds the number is only revealed once its
+        // OTP has been confirmed, so this cannot be used to probe for users
+        ensure_not_current_number(&user, &phone.e164)?;
+
+        check_rate_limit(
+            redis_conn,
+            &format!("change_number:{}", user_id),
+            CHANGE_NUMBER_MAX_REQUESTS,
+            CHANGE_NUMBER_WINDOW_SECONDS,
+            "Too many number change requests. Please try again later.",
+        )
+        .await?;
+
+        // The new number gets the same rate limits and lockouts as a login, but
+        // its code is stored apart so it cannot be redeemed as one
+        issue_otp(redis_conn, phone_policy, &phone, OtpPurpose::ChangeNumber).await
+    }
+}
+
+pub struct ChangeNumberUseCase;
+
+impl ChangeNumberUseCase {
+    /// Move the account to a verified new number. The user id, devices, keys
+    /// and sessions are untouched, so contacts keep talking to the same identity.
+    #[instrument(skip(db, redis_conn, config, phone_policy, req), fields(user_id = %user_id))]
+    pub async fn execute(
+        db: &DatabaseConnection,
+        redis_conn: &mut MultiplexedConnection,
+        config: &AuthConfig,
+        phone_policy: &PhonePolicy,
+        user_id: Uuid,
+        device_id: i64,
+        req: ConfirmChangeNumberRequest,
+    ) -> AppResult<ChangeNumberResponse> {
+        req.validate()
+            .map_err(|e| AppError::Validation(e.to_string()))?;
+
+        let user = load_for_number_change(db, user_id, device_id).await?;
+        let phone = phone_policy.canonicalize(&req.new_phone_number)?;
+        ensure_not_current_number(&user, &phone.e164)?;
+
+        // A stolen primary device alone must not be enough to move the account
+        if let Some(pin_hash) = user.pin_hash.as_deref() {
+            let pin = req
+                .pin
+                .as_deref()
+                .ok_or_else(|| AppError::Validation("PIN is required to change number".to_string()))?;
+            verify_pin_with_lockout(redis_conn, user_id, pin_hash, pin).await?;
+        }
+
+        otp::verify_and_consume(redis_conn, OtpPurpose::ChangeNumber, &phone.e164, &req.otp).await?;
+
+        let txn = db.begin().await?;
+
+        // Checked inside the transaction; the unique index settles any race
+        ensure_number_available(&txn, &phone.e164).await?;
+        ensure_number_not_banned(&txn, &phone).await?;
+
+        let now = Utc::now();
+        let mut active_user: users::ActiveModel = user.into();
+        active_user.phone_number = Set(phone.e164.clone());
+        active_user.phone_number_hash = Set(config.phone_hasher.hash(&phone));
+        active_user.updated_at = Set(now.into());
+        active_user.update(&txn).await?;
+
+        audit::record(
+            &txn,
+            AuditEvent::by_user(user_id, device_id, audit::PHONE_NUMBER_CHANGED),
+        )
+        .await?;
+
+        txn.commit().await?;
+
+        info!("Phone number changed for user {}", user_id);
+
+        Ok(ChangeNumberResponse {
+            user_id,
+            phone_number: phone.e164,
+            changed_at: now,
+        })
+    }
+}
+
+// ============ PIN / OTP Helpers ============
+
+/// Banned and suspended accounts cannot sign in. Their devices were revoked
+/// when the action was taken.
+fn ensure_not_moderated(user: &users::Model) -> AppResult<()> {
+    if user.is_banned() {
+        return Err(AppError::Authorization("This account has been banned".to_string()));
+    }
+    if let Some(until) = user.suspended_until.filter(|_| user.is_suspended()) {
+        return Err(AppError::Authorization(format!(
+            "This account is suspended until {}",
+            until.to_rfc3339()
+        )));
+    }
+    Ok(())
+}
+
+/// The device must still be linked to the user and the account must not be
+/// purged, suspended or banned. Moderation deactivates devices, but a lapsed
+/// suspension does not reactivate them, so both are checked.
+async fn ensure_session_active(db: &DatabaseConnection, user_id: Uuid, device_id: i64) -> AppResult<()> {
+    let (_, user) = devices::Entity::find_by_id(device_id)
+        .filter(devices::Column::UserId.eq(user_id))
+        .filter(devices::Column::IsActive.eq(true))
+        .find_also_related(users::Entity)
+        .one(db)
+        .await?
+        .ok_or_else(|| AppError::Authorization("Device is no longer active".to_string()))?;
+
+    let user = user
+        .filter(|u| u.purged_at.is_none())
+        .ok_or_else(|| AppError::Authentication("Account no longer exists".to_string()))?;
+    ensure_not_moderated(&user)
+}
+
+/// Purging a banned account frees its number, so the ban is checked by number
+async fn ensure_number_not_banned<C: ConnectionTrait>(db: &C, phone: &CanonicalPhone) -> AppResult<()> {
+    let banned = banned_phone_numbers::Entity::find_by_id(phone.hash())
+        .one(db)
+        .await?
+        .is_some();
+    if banned {
+        return Err(AppError::Authorization("This phone number has been banned".to_string()));
+    }
+    Ok(())
+}
+
+/// Generate and store a code for `purpose`, applying the per-number request
+/// limit and lockout. Returns the code, which is not yet sent by SMS.
+async fn issue_otp(
+    redis_conn: &mut MultiplexedConnection,
+    phone_policy: &PhonePolicy,
+    phone: &CanonicalPhone,
+    purpose: OtpPurpose,
+) -> AppResult<String> {
+    let phone_number = phone.e164.as_str();
+
+    // Check rate limit
+    let attempts_key = format!("otp_attempts:{}", phone_number);
+    let attempts: Option<u32> = redis_conn
+        .get(&attempts_key)
+        .await
+        .map_err(|e| AppError::Redis(e.to_string()))?;
+
+    if attempts.unwrap_or(0) >= phone_policy.otp_limit(phone, OTP_MAX_ATTEMPTS) {
+        warn!("Rate limit exceeded for phone number: {}", phone_number);
+        return Err(AppError::RateLimitExceeded(
+            "Too many OTP requests. Please try again later.".to_string(),
+        ));
+    }
+
+    // A locked-out number cannot request fresh codes to keep guessing
+    otp::check_lockout(redis_conn, phone_number).await?;
+
+    // Generate 6-digit OTP
+    let code: String = (0..6)
+        .map(|_| rand::thread_rng().gen_range(0..10).to_string())
+        .collect();
+
+    // Store in Redis with expiration
+    otp::store_otp(redis_conn, purpose, phone_number, &code).await?;
+
+    // Increment attempts counter
+    redis_conn
+        .incr::<_, _, ()>(&attempts_key, 1)
+        .await
+        .map_err(|e| AppError::Redis(e.to_string()))?;
+    redis_conn
+        .expire::<_, ()>(&attempts_key, 600) // 10 minutes window
+        .await
+        .map_err(|e| AppError::Redis(e.to_string()))?;
+
+    info!("OTP generated for phone number: {}", phone_number);
+    // TODO: In production, send OTP via SMS provider
+    // For now, return it for testing
+    Ok(code)
+}
+
+fn hash_pin(pin: &str) -> AppResult<String> {
+    let salt = SaltString::generate(&mut OsRng);
+    Ok(Argon2::default()
+        .hash_password(pin.as_bytes(), &salt)
+        .map_err(|e| AppError::Cryptographic(format!("Failed to hash PIN: {}", e)))?
+        .to_string())
+}
+
+/// Verify a PIN against its hash, sharing the lockout counter with `VerifyPinUseCase`
+async fn verify_pin_with_lockout(
+    redis_conn: &mut MultiplexedConnection,
+    user_id: Uuid,
+    pin_hash: &str,
+    pin: &str,
+) -> AppResult<()> {
+    let attempts_key = format!("pin_attempts:{}", user_id);
+    let attempts: Option<u32> = redis_conn.get(&attempts_key).await?;
+
+    if attempts.unwrap_or(0) >= PIN_MAX_ATTEMPTS {
+        let ttl: i64 = redis_conn.ttl(&attempts_key).await?;
+        return Err(AppError::LockedOut(
+            "Too many incorrect PIN attempts. Please try again later.".to_string(),
+            ttl.max(0) as u64,
+        ));
+    }
+
+    let parsed_hash = PasswordHash::new(pin_hash)
+        .map_err(|e| AppError::Cryptographic(format!("Invalid PIN hash: {}", e)))?;
+
+    if Argon2::default()
+        .verify_password(pin.as_bytes(), &parsed_hash)
+        .is_err()
+    {
+        redis_conn.incr::<_, _, ()>(&attempts_key, 1).await?;
+        redis_conn.expire::<_, ()>(&attempts_key, PIN_LOCKOUT_SECONDS).await?;
+        return Err(AppError::Authentication("Incorrect PIN".to_string()));
+    }
+
+    redis_conn.del::<_, ()>(&attempts_key).await?;
+    Ok(())
+}
+
+// ============ Change Number Helpers ============
+
+/// Only the active primary device of a live account may change its number
+async fn load_for_number_change(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    device_id: i64,
+) -> AppResult<users::Model> {
+    let device = devices::Entity::find_by_id(device_id)
+        .filter(devices::Column::UserId.eq(user_id))
+        .filter(devices::Column::IsActive.eq(true))
+        .one(db)
+        .await?
+        .ok_or_else(|| AppError::Authorization("Device is not active".to_string()))?;
+
+    if device.device_type != DEVICE_TYPE_PRIMARY {
+        return Err(AppError::Authorization(
+            "Only the primary device can change the phone number".to_string(),
+        ));
+    }
+
+    users::Entity::find_by_id(user_id)
+        .filter(users::Column::IsDeleted.eq(false))
+        .one(db)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("User {} not found", user_id)))
+}
+
+fn ensure_not_current_number(user: &users::Model, e164: &str) -> AppResult<()> {
+    if user.phone_number == e164 {
+        return Err(AppError::Validation(
+            "This is already your phone number".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+async fn ensure_number_available<C: ConnectionTrait>(db: &C, e164: &str) -> AppResult<()> {
+    let taken = users::Entity::find()
+        .filter(users::Column::PhoneNumber.eq(e164))
+        .one(db)
+        .await?
+        .is_some();
+    if taken {
+        return Err(AppError::Validation(
+            "Phone number is already registered to another account".to_string(),
+        ));
+    }
+
+    Ok(())
+}
@@ -1,10 +1,26 @@
 #[cfg(test)]
 mod tests {
     use crate::auth::dtos::*;
+    use crate::keys::dtos::{DeviceKeysUpload, SignedPreKeyUpload};
     use crate::AppError;
     use validator::Validate;
     use uuid::Uuid;
 
+    fn device_keys() -> DeviceKeysUpload {
+        DeviceKeysUpload {
+            registration_id: 1,
+            identity_key: String::new(),
+            signed_prekey: SignedPreKeyUpload {
+                id: 1,
+                public_key: String::new(),
+                signature: String::new(),
+            },
+            one_time_prekeys: Vec::new(),
+            pq_prekeys: Vec::new(),
+            pq_last_resort_prekey: None,
+        }
+    }
+
     #[test]
     fn test_request_otp_validation() {
         // Valid phone number
@@ -35,6 +51,7 @@ mod tests {
             device_uuid: Uuid::new_v4(),
             device_name: Some("Test Device".to_string()),
             platform: Some(1),
+            keys: device_keys(),
         };
         assert!(valid_req.validate().is_ok());
 
@@ -45,6 +62,7 @@ mod tests {
             device_uuid: Uuid::new_v4(),
             device_name: None,
             platform: None,
+            keys: device_keys(),
         };
         assert!(invalid_req.validate().is_err());
 
@@ -55,6 +73,7 @@ mod tests {
             device_uuid: Uuid::new_v4(),
             device_name: None,
             platform: Some(5), // Invalid platform
+            keys: device_keys(),
         };
         assert!(invalid_req2.validate().is_err());
     }
@@ -129,6 +148,81 @@ mod tests {
         assert!(invalid_req2.validate().is_err());
     }
 
+    #[test]
+    fn test_change_pin_validation() {
+        // Valid request
+        let valid_req = ChangePinRequest {
+            current_pin: "1234".to_string(),
+            new_pin: "567890".to_string(),
+            confirm_pin: "567890".to_string(),
+        };
+        assert!(valid_req.validate().is_ok());
+
+        // New PINs don't match
+        let invalid_req = ChangePinRequest {
+            current_pin: "1234".to_string(),
+            new_pin: "567890".to_string(),
+            confirm_pin: "567891".to_string(),
+        };
+        assert!(invalid_req.validate().is_err());
+
+        // New PIN too short
+        let invalid_req2 = ChangePinRequest {
+            current_pin: "1234".to_string(),
+            new_pin: "567".to_string(),
+            confirm_pin: "567".to_string(),
+        };
+        assert!(invalid_req2.validate().is_err());
+    }
+
+    #[test]
+    fn test_remove_pin_validation() {
+        // Valid with PIN
+        let with_pin = RemovePinRequest {
+            current_pin: Some("1234".to_string()),
+            otp: None,
+        };
+        assert!(with_pin.validate().is_ok());
+
+        // Valid with OTP
+        let with_otp = RemovePinRequest {
+            current_pin: None,
+            otp: Some("123456".to_string()),
+        };
+        assert!(with_otp.validate().is_ok());
+
+        // Invalid OTP length
+        let invalid_req = RemovePinRequest {
+            current_pin: None,
+            otp: Some("12345".to_string()),
+        };
+        assert!(invalid_req.validate().is_err());
+    }
+
+    #[test]
+    fn test_change_number_validation() {
+        let valid_req = ConfirmChangeNumberRequest {
+            new_phone_number: "+66812345678".to_string(),
+            otp: "123456".to_string(),
+            pin: None,
+        };
+        assert!(valid_req.validate().is_ok());
+
+        // Invalid phone number
+        let invalid_phone = ChangeNumberRequest {
+            new_phone_number: "0812345678".to_string(),
+        };
+        assert!(invalid_phone.validate().is_err());
+
+        // Invalid OTP length
+        let invalid_otp = ConfirmChangeNumberRequest {
+            new_phone_number: "+66812345678".to_string(),
+            otp: "12345".to_string(),
+            pin: Some("1234".to_string()),
+        };
+        assert!(invalid_otp.validate().is_err());
+    }
+
     #[test]
     fn test_app_error_status_codes() {
         let auth_error = AppError::Authentication("test".to_string());
@@ -143,5 +237,15 @@ mod tests {
         assert_eq!(rate_limit_error.status_code(), 429);
         assert_eq!(rate_limit_error.error_code(), "RATE_LIMITED");
         assert_eq!(rate_limit_error.retry_after_seconds(), Some(60));
+
+        let locked_out_error = AppError::LockedOut("test".to_string(), 240);
+        assert_eq!(locked_out_error.status_code(), 429);
+        assert_eq!(locked_out_error.error_code(), "LOCKED_OUT");
+        assert_eq!(locked_out_error.retry_after_seconds(), Some(240));
+
+        let conflict_error = AppError::Conflict("test".to_string());
+        assert_eq!(conflict_error.status_code(), 409);
+        assert_eq!(conflict_error.error_code(), "CONFLICT");
+        assert_eq!(conflict_error.retry_after_seconds(), None);
     }
 }
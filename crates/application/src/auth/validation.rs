@@ -8,25 +8,21 @@ pub static PHONE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"^\+[1-9]\d{1,14}
 /// Username regex: alphanumeric, underscore, hyphen, 3-50 chars
 pub static USERNAME_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"^[a-zA-Z0-9_-]{3,50}$").unwrap());
 
-/// Custom validator for phone number
+/// Custom validator for phone number: E.164 shape plus numbering-plan validity
 pub fn validate_phone_number(phone: &str) -> Result<(), ValidationError> {
-    if PHONE_REGEX.is_match(phone) {
+    if PHONE_REGEX.is_match(phone) && super::phone::is_valid_number(phone) {
         Ok(())
     } else {
         Err(ValidationError::new("invalid_phone_number"))
     }
 }
 
-/// Custom validator for username (handles Option)
-pub fn validate_username(username: Option<&str>) -> Result<(), ValidationError> {
-    match username {
-        Some(u) if !u.is_empty() => {
-            if USERNAME_REGEX.is_match(u) {
-                Ok(())
-            } else {
-                Err(ValidationError::new("invalid_username"))
-            }
-        }
-        _ => Ok(()), // None or empty is valid (optional field)
+/// Custom validator for username. Empty is valid: it clears the username.
+pub fn validate_username(username: &str) -> Result<(), ValidationError> {
+    let username = username.trim();
+    if username.is_empty() || USERNAME_REGEX.is_match(username) {
+        Ok(())
+    } else {
+        Err(ValidationError::new("invalid_username"))
     }
 }
@@ -0,0 +1,32 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+// ============ Blocks ============
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BlockUserResponse {
+    pub blocked_user_id: Uuid,
+    pub blocked_at: DateTime<Utc>,
+    /// The blocker's encrypted profile must be re-uploaded under a new profile key
+    pub profile_key_rotation_required: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UnblockUserResponse {
+    pub unblocked: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BlockedUser {
+    pub user_id: Uuid,
+    pub username: Option<String>,
+    pub display_name: Option<String>,
+    pub blocked_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListBlockedUsersResponse {
+    pub blocked: Vec<BlockedUser>,
+    pub total: usize,
+}
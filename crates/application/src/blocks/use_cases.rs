@@ -0,0 +1,197 @@
+use crate::blocks::dtos::*;
+use crate::profiles::use_cases::require_profile_key_rotation;
+use crate::{AppError, AppResult};
+use chrono::Utc;
+use core::entities::{blocks, users};
+use sea_orm::sea_query::{OnConflict, Query, SelectStatement};
+use sea_orm::{
+    ColumnTrait, Condition, ConnectionTrait, DatabaseConnection, EntityTrait, PaginatorTrait,
+    QueryFilter, QueryOrder, Set, TransactionTrait,
+};
+use tracing::{info, instrument};
+use uuid::Uuid;
+
+// ============ Constants ============
+
+const MAX_BLOCKED_USERS: u64 = 1000;
+
+// ============ Block User Use Case ============
+
+pub struct BlockUserUseCase;
+
+impl BlockUserUseCase {
+    /// Block `blocked_id`. Blocking again is a no-op that keeps the original time.
+    #[instrument(skip(db), fields(user_id = %user_id, blocked_id = %blocked_id))]
+    pub async fn execute(
+        db: &DatabaseConnection,
+        user_id: Uuid,
+        blocked_id: Uuid,
+    ) -> AppResult<BlockUserResponse> {
+        if blocked_id == user_id {
+            return Err(AppError::Validation("You cannot block yourself".to_string()));
+        }
+
+        users::Entity::find_by_id(blocked_id)
+            .filter(users::Column::IsDeleted.eq(false))
+            .one(db)
+            .await?
+            .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+
+        let blocked_count = blocks::Entity::find()
+            .filter(blocks::Column::BlockerId.eq(user_id))
+            .count(db)
+            .await?;
+        if blocked_count >= MAX_BLOCKED_USERS {
+            return Err(AppError::Validation(format!(
+                "You can block at most {} users",
+                MAX_BLOCKED_USERS
+            )));
+        }
+
+        let txn = db.begin().await?;
+
+        blocks::Entity::insert(blocks::ActiveModel {
+            blocker_id: Set(user_id),
+            blocked_id: Set(blocked_id),
+            created_at: Set(Utc::now().into()),
+        })
+        .on_conflict(
+            OnConflict::columns([blocks::Column::BlockerId, blocks::Column::BlockedId])
+                .do_nothing()
+                .to_owned(),
+        )
+        .exec_without_returning(&txn)
+        .await?;
+
+        // The blocked user may hold the current profile key
+        let profile_key_rotation_required = require_profile_key_rotation(&txn, user_id).await?;
+
+        let block = blocks::Entity::find_by_id((user_id, blocked_id))
+            .one(&txn)
+            .await?
+            .ok_or_else(|| AppError::Internal("Block was not stored".to_string()))?;
+
+        txn.commit().await?;
+
+        info!("User {} blocked {}", user_id, blocked_id);
+
+        Ok(BlockUserResponse {
+            blocked_user_id: blocked_id,
+            blocked_at: block.created_at.into(),
+            profile_key_rotation_required,
+        })
+    }
+}
+
+// ============ Unblock User Use Case ============
+
+pub struct UnblockUserUseCase;
+
+impl UnblockUserUseCase {
+    #[instrument(skip(db), fields(user_id = %user_id, blocked_id = %blocked_id))]
+    pub async fn execute(
+        db: &DatabaseConnection,
+        user_id: Uuid,
+        blocked_id: Uuid,
+    ) -> AppResult<UnblockUserResponse> {
+        let result = blocks::Entity::delete_by_id((user_id, blocked_id))
+            .exec(db)
+            .await?;
+
+        Ok(UnblockUserResponse {
+            unblocked: result.rows_affected > 0,
+        })
+    }
+}
+
+// ============ List Blocked Users Use Case ============
+
+pub struct ListBlockedUsersUseCase;
+
+impl ListBlockedUsersUseCase {
+    #[instrument(skip(db), fields(user_id = %user_id))]
+    pub async fn execute(
+        db: &DatabaseConnection,
+        user_id: Uuid,
+    ) -> AppResult<ListBlockedUsersResponse> {
+        let rows = blocks::Entity::find()
+            .filter(blocks::Column::BlockerId.eq(user_id))
+            .find_also_related(users::Entity)
+            .order_by_desc(blocks::Column::CreatedAt)
+            .all(db)
+            .await?;
+
+        let blocked: Vec<BlockedUser> = rows
+            .into_iter()
+            .map(|(block, user)| BlockedUser {
+                user_id: block.blocked_id,
+                username: user.as_ref().and_then(|u| u.username.clone()),
+                display_name: user.and_then(|u| u.display_name),
+                blocked_at: block.created_at.into(),
+            })
+            .collect();
+
+        Ok(ListBlockedUsersResponse {
+            total: blocked.len(),
+            blocked,
+        })
+    }
+}
+
+// ============ Helpers ============
+
+/// Whether `blocker_id` has blocked `user_id`
+pub async fn has_blocked<C: ConnectionTrait>(
+    db: &C,
+    blocker_id: Uuid,
+    user_id: Uuid,
+) -> AppResult<bool> {
+    Ok(blocks::Entity::find_by_id((blocker_id, user_id))
+        .one(db)
+        .await?
+        .is_some())
+}
+
+/// Whether either user has blocked the other. Used where any interaction
+/// between the two should stop, such as typing indicators and receipts.
+pub async fn is_blocked_either_way<C: ConnectionTrait>(
+    db: &C,
+    a: Uuid,
+    b: Uuid,
+) -> AppResult<bool> {
+    let count = blocks::Entity::find()
+        .filter(
+            Condition::any()
+                .add(
+                    Condition::all()
+                        .add(blocks::Column::BlockerId.eq(a))
+                        .add(blocks::Column::BlockedId.eq(b)),
+                )
+                .add(
+                    Condition::all()
+                        .add(blocks::Column::BlockerId.eq(b))
+                        .add(blocks::Column::BlockedId.eq(a)),
+                ),
+        )
+        .count(db)
+        .await?;
+    Ok(count > 0)
+}
+
+/// `SELECT blocker_id` of everyone who has blocked `user_id`
+pub fn blockers_of(user_id: Uuid) -> SelectStatement {
+    Query::select()
+        .column(blocks::Column::BlockerId)
+        .from(blocks::Entity)
+        .and_where(blocks::Column::BlockedId.eq(user_id))
+        .to_owned()
+}
+
+/// `SELECT blocked_id` of everyone `user_id` has blocked
+pub fn blocked_by(user_id: Uuid) -> SelectStatement {
+    Query::select()
+        .column(blocks::Column::BlockedId)
+        .from(blocks::Entity)
+        .and_where(blocks::Column::BlockerId.eq(user_id))
+        .to_owned()
+}
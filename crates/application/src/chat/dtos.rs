@@ -1,3 +1,5 @@
+use crate::blocks::dtos::BlockUserResponse;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -28,3 +30,36 @@ pub enum DeliveryStatusType {
     Delivered,
     Read,
 }
+
+// ============ Message Requests ============
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MessageRequest {
+    pub conversation_id: Uuid,
+    /// `None` once the requester's account has been purged
+    pub requester_id: Option<Uuid>,
+    pub requester_username: Option<String>,
+    pub requester_display_name: Option<String>,
+    pub requested_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListMessageRequestsResponse {
+    pub requests: Vec<MessageRequest>,
+    pub total: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AcceptMessageRequestResponse {
+    pub conversation_id: Uuid,
+    pub requester_id: Option<Uuid>,
+    pub accepted_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DeclineMessageRequestResponse {
+    pub conversation_id: Uuid,
+    pub declined_at: DateTime<Utc>,
+    /// Set by decline-and-block
+    pub block: Option<BlockUserResponse>,
+}
@@ -1,4 +1,9 @@
 pub mod dtos;
+pub mod requests;
 pub mod use_cases;
 pub mod sync_messages;
 pub mod update_status;
+
+pub use requests::{
+    AcceptMessageRequestUseCase, DeclineMessageRequestUseCase, ListMessageRequestsUseCase,
+};
@@ -0,0 +1,160 @@
+use crate::blocks::BlockUserUseCase;
+use crate::chat::dtos::*;
+use crate::{AppError, AppResult};
+use chrono::Utc;
+use core::entities::conv_members::{self, RequestStatus};
+use core::entities::{conversations, users};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait,
+    PaginatorTrait, QueryFilter, QueryOrder, Set,
+};
+use tracing::{info, instrument};
+use uuid::Uuid;
+
+// ============ List Message Requests Use Case ============
+
+pub struct ListMessageRequestsUseCase;
+
+impl ListMessageRequestsUseCase {
+    #[instrument(skip(db), fields(user_id = %user_id))]
+    pub async fn execute(
+        db: &DatabaseConnection,
+        user_id: Uuid,
+    ) -> AppResult<ListMessageRequestsResponse> {
+        let pending = conv_members::Entity::find()
+            .filter(conv_members::Column::UserId.eq(user_id))
+            .filter(conv_members::Column::RequestStatus.eq(i16::from(RequestStatus::Pending)))
+            .filter(conv_members::Column::LeftAt.is_null())
+            .find_also_related(conversations::Entity)
+            .order_by_desc(conv_members::Column::JoinedAt)
+            .all(db)
+            .await?;
+
+        let mut requests = Vec::with_capacity(pending.len());
+        for (member, conversation) in pending {
+            let requester_id = conversation.and_then(|c| c.creator_id);
+            let requester = match requester_id {
+                Some(id) => users::Entity::find_by_id(id).one(db).await?,
+                None => None,
+            };
+
+            requests.push(MessageRequest {
+                conversation_id: member.conv_id,
+                requester_id,
+                requester_username: requester.as_ref().and_then(|u| u.username.clone()),
+                requester_display_name: requester.and_then(|u| u.display_name),
+                requested_at: member.joined_at.into(),
+            });
+        }
+
+        let total = requests.len();
+        Ok(ListMessageRequestsResponse { requests, total })
+    }
+}
+
+// ============ Accept Message Request Use Case ============
+
+pub struct AcceptMessageRequestUseCase;
+
+impl AcceptMessageRequestUseCase {
+    #[instrument(skip(db), fields(user_id = %user_id, conv_id = %conv_id))]
+    pub async fn execute(
+        db: &DatabaseConnection,
+        user_id: Uuid,
+        conv_id: Uuid,
+    ) -> AppResult<AcceptMessageRequestResponse> {
+        let member = find_pending_request(db, user_id, conv_id).await?;
+
+        let mut active_member: conv_members::ActiveModel = member.into();
+        active_member.request_status = Set(RequestStatus::Accepted.into());
+        active_member.update(db).await?;
+
+        info!("User {} accepted message request {}", user_id, conv_id);
+
+        Ok(AcceptMessageRequestResponse {
+            conversation_id: conv_id,
+            requester_id: requester_of(db, conv_id).await?,
+            accepted_at: Utc::now(),
+        })
+    }
+}
+
+// ============ Decline Message Request Use Case ============
+
+pub struct DeclineMessageRequestUseCase;
+
+impl DeclineMessageRequestUseCase {
+    /// Leave the conversation without telling the requester. With `block` the
+    /// requester is also blocked, so new conversations never reach the user.
+    #[instrument(skip(db), fields(user_id = %user_id, conv_id = %conv_id))]
+    pub async fn execute(
+        db: &DatabaseConnection,
+        user_id: Uuid,
+        conv_id: Uuid,
+        block: bool,
+    ) -> AppResult<DeclineMessageRequestResponse> {
+        let member = find_pending_request(db, user_id, conv_id).await?;
+
+        let now = Utc::now();
+        let mut active_member: conv_members::ActiveModel = member.into();
+        active_member.request_status = Set(RequestStatus::Declined.into());
+        active_member.left_at = Set(Some(now.into()));
+        active_member.update(db).await?;
+
+        info!("User {} declined message request {}", user_id, conv_id);
+
+        let block = match requester_of(db, conv_id).await? {
+            Some(requester_id) if block => {
+                Some(BlockUserUseCase::execute(db, user_id, requester_id).await?)
+            }
+            _ => None,
+        };
+
+        Ok(DeclineMessageRequestResponse {
+            conversation_id: conv_id,
+            declined_at: now,
+            block,
+        })
+    }
+}
+
+// ============ Helpers ============
+
+/// Whether any of `user_ids` still has the conversation in their request
+/// inbox. Until it is accepted, read receipts and typing indicators are not
+/// forwarded in either direction.
+pub async fn has_pending_request<C: ConnectionTrait>(
+    db: &C,
+    conv_id: Uuid,
+    user_ids: [Uuid; 2],
+) -> AppResult<bool> {
+    let pending = conv_members::Entity::find()
+        .filter(conv_members::Column::ConvId.eq(conv_id))
+        .filter(conv_members::Column::UserId.is_in(user_ids))
+        // Anything but an accepted status withholds, matching `Model::request_status`
+        .filter(conv_members::Column::RequestStatus.ne(i16::from(RequestStatus::Accepted)))
+        .count(db)
+        .await?;
+    Ok(pending > 0)
+}
+
+async fn find_pending_request(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    conv_id: Uuid,
+) -> AppResult<conv_members::Model> {
+    conv_members::Entity::find_by_id((conv_id, user_id))
+        .filter(conv_members::Column::RequestStatus.eq(i16::from(RequestStatus::Pending)))
+        .filter(conv_members::Column::LeftAt.is_null())
+        .one(db)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Message request not found".to_string()))
+}
+
+/// Requests only exist in personal conversations, which the requester created
+async fn requester_of(db: &DatabaseConnection, conv_id: Uuid) -> AppResult<Option<Uuid>> {
+    Ok(conversations::Entity::find_by_id(conv_id)
+        .one(db)
+        .await?
+        .and_then(|c| c.creator_id))
+}
@@ -1,25 +1,120 @@
 use super::dtos::SendMessageRequest;
-use core::entities::{message_deliveries, messages};
+use crate::blocks::use_cases::has_blocked;
+use crate::users::use_cases::contacts_among;
+use crate::{AppError, AppResult};
+use core::entities::conv_members::{self, RequestStatus};
+use core::entities::{conversations, devices, message_deliveries, messages};
 use sea_orm::{
     ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set, TransactionTrait,
 };
 use chrono::Utc;
 
+const CONV_TYPE_PERSONAL: i16 = 1;
+
 pub struct SendMessageUseCase;
 
 impl SendMessageUseCase {
-    pub async fn execute(
-        db: &DatabaseConnection,
-        req: SendMessageRequest,
-    ) -> Result<(), String> {
-        let txn = db.begin().await.map_err(|e| e.to_string())?;
+    /// Store a message for delivery. Returns `false` when the recipient has
+    /// blocked the sender or declined the conversation: nothing is stored or
+    /// forwarded, but the sender sees the same outcome as a successful send.
+    ///
+    /// The first message to an unknown conversation id opens a personal
+    /// conversation. If the two users are not contacts yet it lands in the
+    /// recipient's message request inbox. Conversations the sender or the
+    /// recipient are not members of are reported as not found, and so is a
+    /// recipient device that is not an active device of the recipient.
+    pub async fn execute(db: &DatabaseConnection, req: SendMessageRequest) -> AppResult<bool> {
+        if has_blocked(db, req.recipient_id, req.sender_id).await? {
+            return Ok(false);
+        }
+
+        let txn = db.begin().await?;
+
+        // Deliveries are synced by device id alone, so the device must belong to
+        // the recipient the block and membership checks ran against
+        devices::Entity::find_by_id(req.recipient_device_id)
+            .filter(devices::Column::UserId.eq(req.recipient_id))
+            .filter(devices::Column::IsActive.eq(true))
+            .one(&txn)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Device not found".to_string()))?;
+
+        // 0. Check membership, opening the conversation on first contact
+        let members = conv_members::Entity::find()
+            .filter(conv_members::Column::ConvId.eq(req.conversation_id))
+            .all(&txn)
+            .await?;
+
+        if members.is_empty() {
+            if req.recipient_id == req.sender_id {
+                return Err(AppError::NotFound("Conversation not found".to_string()));
+            }
+
+            let is_contact = contacts_among(db, req.recipient_id, vec![req.sender_id])
+                .await?
+                .contains(&req.sender_id);
+            let recipient_status = if is_contact {
+                RequestStatus::Accepted
+            } else {
+                RequestStatus::Pending
+            };
+
+            let now = Utc::now();
+            conversations::ActiveModel {
+                conv_id: Set(req.conversation_id),
+                conv_type: Set(CONV_TYPE_PERSONAL),
+                name: Set(None),
+                avatar: Set(None),
+                created_at: Set(now.into()),
+                creator_id: Set(Some(req.sender_id)),
+                metadata: Set(serde_json::json!({})),
+            }
+            .insert(&txn)
+            .await?;
+
+            for (user_id, status) in [
+                (req.sender_id, RequestStatus::Accepted),
+                (req.recipient_id, recipient_status),
+            ] {
+                conv_members::ActiveModel {
+                    conv_id: Set(req.conversation_id),
+                    user_id: Set(user_id),
+                    role: Set(0),
+                    joined_at: Set(now.into()),
+                    left_at: Set(None),
+                    request_status: Set(status.into()),
+                }
+                .insert(&txn)
+                .await?;
+            }
+        } else {
+            let sender = members
+                .iter()
+                .find(|m| m.user_id == req.sender_id && m.left_at.is_none())
+                .ok_or_else(|| AppError::NotFound("Conversation not found".to_string()))?;
+            let recipient = members
+                .iter()
+                .find(|m| m.user_id == req.recipient_id)
+                .ok_or_else(|| AppError::NotFound("Conversation not found".to_string()))?;
+
+            // Declined or left: indistinguishable from delivery for the sender
+            if recipient.left_at.is_some() {
+                return Ok(false);
+            }
+
+            // Replying to a message request accepts it
+            if sender.request_status() == RequestStatus::Pending {
+                let mut active_member: conv_members::ActiveModel = sender.clone().into();
+                active_member.request_status = Set(RequestStatus::Accepted.into());
+                active_member.update(&txn).await?;
+            }
+        }
 
         // 1. Check if message exists (deduplication)
         let message = messages::Entity::find()
             .filter(messages::Column::ClientMessageId.eq(req.client_message_id))
             .one(&txn)
-            .await
-            .map_err(|e| e.to_string())?;
+            .await?;
 
         let message_id = if let Some(msg) = message {
             msg.message_id
@@ -34,9 +129,10 @@ impl SendMessageUseCase {
                 content: Set("".to_string()), // Placeholder for sender's copy
                 iv: Set(Vec::new()),
                 sent_at: Set(Utc::now().into()),
+                extra: Set(serde_json::json!({})),
                 ..Default::default()
             };
-            let inserted_msg = new_msg.insert(&txn).await.map_err(|e| e.to_string())?;
+            let inserted_msg = new_msg.insert(&txn).await?;
             inserted_msg.message_id
         };
 
@@ -46,8 +142,7 @@ impl SendMessageUseCase {
             .filter(message_deliveries::Column::MessageId.eq(message_id))
             .filter(message_deliveries::Column::DeviceId.eq(req.recipient_device_id))
             .one(&txn)
-            .await
-            .map_err(|e| e.to_string())?
+            .await?
             .is_some();
 
         if !delivery_exists {
@@ -57,11 +152,11 @@ impl SendMessageUseCase {
                 content: Set(Some(req.content)),
                 ..Default::default()
             };
-            delivery.insert(&txn).await.map_err(|e| e.to_string())?;
+            delivery.insert(&txn).await?;
         }
 
-        txn.commit().await.map_err(|e| e.to_string())?;
+        txn.commit().await?;
 
-        Ok(())
+        Ok(true)
     }
 }
@@ -0,0 +1,27 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+// ============ Contact Discovery ============
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct DiscoverContactsRequest {
+    /// Base64 SHA-256 of each canonical E.164 number, or its first 10 bytes
+    #[validate(length(min = 1, max = 1000, message = "Between 1-1000 hashes per request"))]
+    pub hashes: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DiscoveredContact {
+    pub hash: String, // As submitted, so the client can map it back to its address book
+    pub user_id: Uuid,
+    pub username: Option<String>,
+    pub display_name: Option<String>,
+    pub profile_picture_url: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DiscoverContactsResponse {
+    pub contacts: Vec<DiscoveredContact>,
+    pub quota_remaining: u32, // Distinct hashes that may still be looked up in the current window
+}
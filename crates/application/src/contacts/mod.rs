@@ -0,0 +1,4 @@
+pub mod dtos;
+pub mod use_cases;
+
+pub use use_cases::{DiscoverContactsUseCase, RehashPhoneNumbersUseCase};
@@ -0,0 +1,222 @@
+use crate::auth::phone::{phone_number_hash, PhoneHasher};
+use crate::blocks::use_cases::blockers_of;
+use crate::contacts::dtos::*;
+use crate::rate_limit::{check_cardinality_limit, check_rate_limit};
+use crate::users::use_cases::{contacts_among, project_profile};
+use crate::{AppError, AppResult};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use core::entities::users;
+use redis::aio::MultiplexedConnection;
+use sea_orm::sea_query::Expr;
+use sea_orm::{
+    ColumnTrait, Condition, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, QuerySelect,
+};
+use std::collections::HashMap;
+use tracing::{info, instrument};
+use uuid::Uuid;
+use validator::Validate;
+
+// ============ Constants ============
+
+const FULL_HASH_BYTES: usize = 32;
+/// Truncated tokens are matched against an expression index on this prefix length
+const TRUNCATED_HASH_BYTES: usize = 10;
+const DISCOVERY_MAX_REQUESTS: u32 = 30;
+const DISCOVERY_WINDOW_SECONDS: i64 = 3600;
+/// Distinct hashes a user may look up per day, the main defence against enumeration
+const DISCOVERY_MAX_DISTINCT_HASHES: u32 = 10_000;
+const DISCOVERY_QUOTA_WINDOW_SECONDS: i64 = 86400;
+const REHASH_BATCH_SIZE: u64 = 500;
+
+// ============ Discover Contacts Use Case ============
+
+pub struct DiscoverContactsUseCase;
+
+impl DiscoverContactsUseCase {
+    #[instrument(skip(db, redis_conn, phone_hasher, req), fields(user_id = %user_id, batch = req.hashes.len()))]
+    pub async fn execute(
+        db: &DatabaseConnection,
+        redis_conn: &mut MultiplexedConnection,
+        phone_hasher: &PhoneHasher,
+        user_id: Uuid,
+        req: DiscoverContactsRequest,
+    ) -> AppResult<DiscoverContactsResponse> {
+        // Validate input
+        req.validate()
+            .map_err(|e| AppError::Validation(e.to_string()))?;
+
+        // Stored hash (or prefix) -> token as submitted
+        let mut full_hashes: HashMap<Vec<u8>, String> = HashMap::new();
+        let mut prefixes: HashMap<Vec<u8>, String> = HashMap::new();
+
+        for token in &req.hashes {
+            let bytes = BASE64
+                .decode(token)
+                .map_err(|_| AppError::Validation("Hashes must be base64 encoded".to_string()))?;
+
+            match bytes.len() {
+                FULL_HASH_BYTES => {
+                    full_hashes.insert(phone_hasher.hash_token(&bytes), token.clone());
+                }
+                // A keyed stored hash shares no prefix with the client's SHA-256
+                TRUNCATED_HASH_BYTES if phone_hasher.is_keyed() => {
+                    return Err(AppError::Validation(
+                        "Truncated hashes are not supported by this server".to_string(),
+                    ))
+                }
+                TRUNCATED_HASH_BYTES => {
+                    prefixes.insert(bytes, token.clone());
+                }
+                _ => {
+                    return Err(AppError::Validation(format!(
+                        "Each hash must be a {}-byte SHA-256 digest or its {}-byte prefix",
+                        FULL_HASH_BYTES, TRUNCATED_HASH_BYTES
+                    )))
+                }
+            }
+        }
+
+        // ============ Quotas ============
+
+        check_rate_limit(
+            redis_conn,
+            &format!("contact_discovery_requests:{}", user_id),
+            DISCOVERY_MAX_REQUESTS,
+            DISCOVERY_WINDOW_SECONDS,
+            "Too many contact discovery requests. Please try again later.",
+        )
+        .await?;
+
+        let quota_remaining = check_cardinality_limit(
+            redis_conn,
+            &format!("contact_discovery_hashes:{}", user_id),
+            &req.hashes,
+            DISCOVERY_MAX_DISTINCT_HASHES,
+            DISCOVERY_QUOTA_WINDOW_SECONDS,
+            "Contact discovery quota exceeded. Please try again tomorrow.",
+        )
+        .await?;
+
+        // ============ Lookup ============
+
+        let mut matches = Condition::any();
+        if !full_hashes.is_empty() {
+            matches = matches.add(users::Column::PhoneNumberHash.is_in(full_hashes.keys().cloned()));
+        }
+        if !prefixes.is_empty() {
+            matches = matches.add(
+                Expr::expr(Expr::cust(format!(
+                    "substring(phone_number_hash from 1 for {})",
+                    TRUNCATED_HASH_BYTES
+                )))
+                .is_in(prefixes.keys().cloned()),
+            );
+        }
+
+        let found = users::Entity::find()
+            .filter(matches)
+            .filter(users::Column::IsDeleted.eq(false))
+            .filter(users::Column::UserId.ne(user_id))
+            .filter(users::Column::UserId.not_in_subquery(blockers_of(user_id)))
+            .all(db)
+            .await
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        // Profile fields follow the same visibility rules as the public profile
+        let existing_contacts =
+            contacts_among(db, user_id, found.iter().map(|u| u.user_id).collect()).await?;
+
+        let mut contacts = Vec::new();
+        for user in found {
+            let submitted: Vec<String> = [
+                full_hashes.get(&user.phone_number_hash),
+                user.phone_number_hash
+                    .get(..TRUNCATED_HASH_BYTES)
+                    .and_then(|prefix| prefixes.get(prefix)),
+            ]
+            .into_iter()
+            .flatten()
+            .cloned()
+            .collect();
+
+            let is_contact = existing_contacts.contains(&user.user_id);
+            let profile = project_profile(user, user_id, is_contact);
+            for hash in submitted {
+                contacts.push(DiscoveredContact {
+                    hash,
+                    user_id: profile.user_id,
+                    username: profile.username.clone(),
+                    display_name: profile.display_name.clone(),
+                    profile_picture_url: profile.profile_picture_url.clone(),
+                });
+            }
+        }
+
+        info!(
+            "Contact discovery matched {} of {} hashes",
+            contacts.len(),
+            req.hashes.len()
+        );
+
+        Ok(DiscoverContactsResponse {
+            contacts,
+            quota_remaining,
+        })
+    }
+}
+
+// ============ Rehash Phone Numbers Use Case ============
+
+pub struct RehashPhoneNumbersUseCase;
+
+impl RehashPhoneNumbersUseCase {
+    /// Bring every stored phone number hash in line with the current discovery
+    /// key, so users registered before the key was set or rotated can be found
+    /// without waiting for their next login. Returns the number of users updated.
+    #[instrument(skip(db, phone_hasher))]
+    pub async fn execute(db: &DatabaseConnection, phone_hasher: &PhoneHasher) -> AppResult<u64> {
+        let mut updated = 0;
+        let mut after: Option<Uuid> = None;
+
+        loop {
+            let mut query = users::Entity::find()
+                .select_only()
+                .columns([
+                    users::Column::UserId,
+                    users::Column::PhoneNumber,
+                    users::Column::PhoneNumberHash,
+                ])
+                // Purged accounts hold a tombstone in place of a number
+                .filter(users::Column::PurgedAt.is_null())
+                .order_by_asc(users::Column::UserId)
+                .limit(REHASH_BATCH_SIZE);
+            if let Some(after) = after {
+                query = query.filter(users::Column::UserId.gt(after));
+            }
+            let batch: Vec<(Uuid, String, Vec<u8>)> = query.into_tuple().all(db).await?;
+
+            for (user_id, phone_number, stored) in &batch {
+                let expected = phone_hasher.hash_token(&phone_number_hash(phone_number));
+                if *stored == expected {
+                    continue;
+                }
+                users::Entity::update_many()
+                    .col_expr(users::Column::PhoneNumberHash, Expr::value(expected))
+                    .filter(users::Column::UserId.eq(*user_id))
+                    .exec(db)
+                    .await?;
+                updated += 1;
+            }
+
+            match batch.last() {
+                Some((user_id, _, _)) if batch.len() as u64 == REHASH_BATCH_SIZE => {
+                    after = Some(*user_id)
+                }
+                _ => break,
+            }
+        }
+
+        info!("Rehashed {} phone numbers", updated);
+        Ok(updated)
+    }
+}
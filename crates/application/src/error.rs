@@ -16,9 +16,15 @@ pub enum AppError {
     /// Not found errors
     NotFound(String),
 
+    /// Conflicts with the current state of a resource
+    Conflict(String),
+
     /// Rate limiting errors
     RateLimitExceeded(String),
 
+    /// Temporary lockout after repeated failures (message, retry after seconds)
+    LockedOut(String, u64),
+
     /// Database errors
     Database(String),
 
@@ -54,10 +60,18 @@ impl fmt::Display for AppError {
                 f.write_str("Resource not found: ")?;
                 f.write_str(msg)
             }
+            AppError::Conflict(msg) => {
+                f.write_str("Conflict: ")?;
+                f.write_str(msg)
+            }
             AppError::RateLimitExceeded(msg) => {
                 f.write_str("Rate limit exceeded: ")?;
                 f.write_str(msg)
             }
+            AppError::LockedOut(msg, _) => {
+                f.write_str("Locked out: ")?;
+                f.write_str(msg)
+            }
             AppError::Database(msg) => {
                 f.write_str("Database error: ")?;
                 f.write_str(msg)
@@ -92,7 +106,8 @@ impl AppError {
             AppError::Authorization(_) => 403,
             AppError::Validation(_) => 400,
             AppError::NotFound(_) => 404,
-            AppError::RateLimitExceeded(_) => 429,
+            AppError::Conflict(_) => 409,
+            AppError::RateLimitExceeded(_) | AppError::LockedOut(_, _) => 429,
             AppError::Database(_) | AppError::Redis(_) | AppError::Internal(_) => 500,
             AppError::Cryptographic(_) => 500,
             AppError::Configuration(_) => 500,
@@ -106,7 +121,9 @@ impl AppError {
             AppError::Authorization(_) => "AUTHORIZATION_FAILED",
             AppError::Validation(_) => "VALIDATION_ERROR",
             AppError::NotFound(_) => "NOT_FOUND",
+            AppError::Conflict(_) => "CONFLICT",
             AppError::RateLimitExceeded(_) => "RATE_LIMITED",
+            AppError::LockedOut(_, _) => "LOCKED_OUT",
             AppError::Database(_) => "DATABASE_ERROR",
             AppError::Redis(_) => "REDIS_ERROR",
             AppError::Cryptographic(_) => "CRYPTOGRAPHIC_ERROR",
@@ -119,6 +136,7 @@ impl AppError {
     pub fn retry_after_seconds(&self) -> Option<u64> {
         match self {
             AppError::RateLimitExceeded(_) => Some(60),
+            AppError::LockedOut(_, seconds) => Some(*seconds),
             _ => None,
         }
     }
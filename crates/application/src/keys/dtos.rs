@@ -1,5 +1,10 @@
+use crate::transparency::dtos::InclusionProof;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use validator::Validate;
 
+/// Public keys are serialized with their Signal type byte, ready for a
+/// libsignal `PreKeyBundle`
 #[derive(Debug, Serialize, Deserialize)]
 pub struct PreKeyBundleResponse {
     pub device_id: i64,
@@ -7,6 +12,18 @@ pub struct PreKeyBundleResponse {
     pub identity_key: Vec<u8>,
     pub signed_prekey: SignedPreKeyDto,
     pub one_time_prekey: Option<PreKeyDto>,
+    /// Signed ML-KEM-1024 prekey for PQXDH. A one-time key when the device has
+    /// one left, otherwise its last-resort key.
+    pub pq_prekey: Option<KyberPreKeyDto>,
+    /// Inclusion of this device's identity key in the key transparency log
+    pub key_transparency: Option<InclusionProof>,
+}
+
+/// Bundles for every active device of a user
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PreKeyBundlesResponse {
+    pub user_id: uuid::Uuid,
+    pub devices: Vec<PreKeyBundleResponse>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -21,3 +38,163 @@ pub struct PreKeyDto {
     pub id: i32,
     pub key: Vec<u8>,
 }
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct KyberPreKeyDto {
+    pub id: i32,
+    pub key: Vec<u8>,
+    pub signature: Vec<u8>,
+    pub last_resort: bool,
+}
+
+// ============ Key Upload ============
+
+/// Public key material generated on the device. Keys are base64 encoded in the
+/// Signal wire format (type byte then key) and signatures are XEdDSA by the
+/// identity key; the private halves never leave the device.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct DeviceKeysUpload {
+    #[validate(range(min = 1, max = 16383, message = "Registration ID must be between 1-16383"))]
+    pub registration_id: i32,
+    /// Curve25519 identity key, type byte 0x05
+    pub identity_key: String,
+    #[validate(nested)]
+    pub signed_prekey: SignedPreKeyUpload,
+    #[validate(length(max = 100, message = "At most 100 one-time prekeys per upload"))]
+    pub one_time_prekeys: Vec<PreKeyUpload>,
+    #[serde(default)]
+    #[validate(length(max = 100, message = "At most 100 Kyber prekeys per upload"))]
+    pub pq_prekeys: Vec<KyberPreKeyUpload>,
+    /// Replaces the device's current last-resort Kyber prekey
+    #[serde(default)]
+    pub pq_last_resort_prekey: Option<KyberPreKeyUpload>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct SignedPreKeyUpload {
+    #[validate(range(min = 1, max = 16777215, message = "Prekey ID must be between 1-16777215"))]
+    pub id: i32,
+    /// Curve25519 public key, type byte 0x05
+    pub public_key: String,
+    /// Identity key signature over the serialized `public_key`
+    pub signature: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PreKeyUpload {
+    pub id: i32,
+    /// Curve25519 public key, type byte 0x05
+    pub public_key: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KyberPreKeyUpload {
+    pub id: i32,
+    /// ML-KEM-1024 encapsulation key, type byte 0x08
+    pub public_key: String,
+    /// Identity key signature over the serialized `public_key`
+    pub signature: String,
+}
+
+// ============ Replenishment ============
+
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct UploadPreKeysRequest {
+    #[serde(default)]
+    #[validate(length(max = 100, message = "At most 100 one-time prekeys per upload"))]
+    pub one_time_prekeys: Vec<PreKeyUpload>,
+    #[serde(default)]
+    #[validate(length(max = 100, message = "At most 100 Kyber prekeys per upload"))]
+    pub pq_prekeys: Vec<KyberPreKeyUpload>,
+    #[serde(default)]
+    pub pq_last_resort_prekey: Option<KyberPreKeyUpload>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UploadPreKeysResponse {
+    pub uploaded: u64,
+    pub count: u64,
+    pub pq_uploaded: u64,
+    pub pq_count: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PreKeyCountResponse {
+    pub count: u64,
+    /// One-time Kyber prekeys, not counting the last-resort key
+    pub pq_count: u64,
+    pub pq_last_resort: bool,
+    /// Below this many keys clients should upload a new batch
+    pub low_threshold: u64,
+}
+
+impl PreKeyCountResponse {
+    /// Whether the device should be told to replenish. Devices that never
+    /// uploaded Kyber prekeys are only nagged about EC keys.
+    pub fn is_low(&self) -> bool {
+        self.count < self.low_threshold
+            || (self.pq_last_resort && self.pq_count < self.low_threshold)
+    }
+}
+
+// ============ Signed PreKey Rotation ============
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SignedPreKeyStatusResponse {
+    pub id: i32,
+    pub created_at: DateTime<Utc>,
+    /// The key is older than the rotation interval and should be replaced
+    pub rotation_due: bool,
+    /// Key replaced by the last rotation. Clients keep its private half until
+    /// `previous_expires_at` so sessions started from an older bundle still work.
+    pub previous_id: Option<i32>,
+    pub previous_expires_at: Option<DateTime<Utc>>,
+}
+
+// ============ Identity Keys ============
+
+/// Current identity keys of a user's active devices, for computing safety numbers
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IdentityKeysResponse {
+    pub user_id: uuid::Uuid,
+    pub devices: Vec<DeviceIdentityKey>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DeviceIdentityKey {
+    pub device_id: i64,
+    /// Serialized with its type byte
+    pub identity_key: Vec<u8>,
+    /// When this key became the user's, per the identity key history.
+    /// Re-registering with the same key does not move it.
+    pub since: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct ListIdentityChangesQuery {
+    /// `id` of the last change the device has seen
+    #[serde(default)]
+    pub after: Option<i64>,
+    #[serde(default)]
+    #[validate(range(min = 1, max = 500, message = "Limit must be between 1-500"))]
+    pub limit: Option<u64>,
+}
+
+/// Identity keys registered by the caller's contacts and other devices, oldest
+/// first. The persisted form of `IdentityChanged`, for devices that were offline.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IdentityChangesResponse {
+    pub changes: Vec<IdentityChange>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IdentityChange {
+    pub id: i64,
+    pub user_id: uuid::Uuid,
+    pub device_id: i64,
+    /// Serialized with its type byte
+    pub identity_key: Vec<u8>,
+    /// Set when the key replaced a different one on re-registration
+    pub previous_identity_key: Option<Vec<u8>>,
+    pub created_at: DateTime<Utc>,
+}
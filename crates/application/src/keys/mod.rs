@@ -1,2 +1,8 @@
 pub mod dtos;
 pub mod use_cases;
+mod validation;
+
+pub use validation::{
+    parse_device_keys, parse_kyber_prekeys, parse_one_time_prekeys, parse_signed_prekey,
+    DeviceKeys, KyberPreKeys, PreKey, SignedPreKey,
+};
@@ -1,63 +1,739 @@
-use super::dtos::{PreKeyBundleResponse, PreKeyDto, SignedPreKeyDto};
-use core::entities::{devices, one_time_prekeys};
-use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder};
+use super::dtos::{
+    DeviceIdentityKey, IdentityChange, IdentityChangesResponse, IdentityKeysResponse,
+    ListIdentityChangesQuery, KyberPreKeyDto, PreKeyBundleResponse, PreKeyBundlesResponse, PreKeyCountResponse, PreKeyDto,
+    SignedPreKeyDto, SignedPreKeyStatusResponse, SignedPreKeyUpload, UploadPreKeysRequest,
+    UploadPreKeysResponse,
+};
+use super::{
+    parse_kyber_prekeys, parse_one_time_prekeys, parse_signed_prekey, KyberPreKeys, PreKey,
+};
+use crate::audit::{self, AuditEvent};
+use crate::rate_limit::check_rate_limit;
+use crate::transparency::use_cases::{append_binding, inclusion_proof, latest_tree_head};
+use crate::transparency::dtos::InclusionProof;
+use crate::transparency::LogSigner;
+use crate::users::use_cases::list_contact_ids;
+use crate::{AppError, AppResult};
+use chrono::{Duration, Utc};
+use crypto::{DJB_TYPE, KYBER_1024_TYPE};
+use core::entities::{devices, identity_key_history, kyber_prekeys, one_time_prekeys, users};
+use redis::aio::MultiplexedConnection;
+use std::collections::HashMap;
+use sea_orm::sea_query::Expr;
+use sea_orm::prelude::DateTimeWithTimeZone;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, Condition, ConnectionTrait, DatabaseConnection, DbBackend, EntityTrait,
+    PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, Set, Statement, TransactionTrait,
+};
+use tracing::{info, instrument};
 use uuid::Uuid;
+use validator::Validate;
+
+// ============ Constants ============
+
+/// Devices are told to replenish once fewer keys than this remain
+pub const PREKEY_LOW_THRESHOLD: u64 = 10;
+/// Most one-time prekeys of each kind a device may have stored at once
+const MAX_STORED_PREKEYS: u64 = 500;
+/// Every bundle fetch consumes one-time prekeys, so fetches are limited per
+/// requester to stop them draining other users' keys
+const PREKEY_FETCH_MAX_REQUESTS: u32 = 200;
+const PREKEY_FETCH_WINDOW_SECONDS: i64 = 3600;
+const IDENTITY_CHANGES_DEFAULT_LIMIT: u64 = 100;
+
+// ============ Get PreKey Bundle Use Case ============
 
 pub struct GetPreKeyBundleUseCase;
 
 impl GetPreKeyBundleUseCase {
+    /// Bundle for one active device of `user_id`, claiming one one-time
+    /// prekey of each kind. Deleted users and unlinked devices are not found.
+    #[instrument(skip(db, signer), fields(user_id = %user_id, device_id = device_id))]
     pub async fn execute(
         db: &DatabaseConnection,
+        signer: &LogSigner,
         user_id: Uuid,
         device_id: i64,
-    ) -> Result<PreKeyBundleResponse, String> {
+    ) -> AppResult<PreKeyBundleResponse> {
+        users::Entity::find_by_id(user_id)
+            .filter(users::Column::IsDeleted.eq(false))
+            .one(db)
+            .await?
+            .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+
         // 1. Fetch Device
         let device = devices::Entity::find()
             .filter(devices::Column::UserId.eq(user_id))
             .filter(devices::Column::DeviceId.eq(device_id))
+            .filter(devices::Column::IsActive.eq(true))
             .one(db)
-            .await
-            .map_err(|e| e.to_string())?
-            .ok_or("Device not found")?;
-
-        // 2. Fetch One One-Time Prekey
-        // We need to fetch one and delete it (or mark as used).
-        // For simplicity, we just fetch one. In a real Signal implementation, 
-        // we should delete it, but that requires a transaction and might run out of keys.
-        // Signal server usually deletes it.
-        
-        let prekey = one_time_prekeys::Entity::find()
+            .await?
+            .ok_or_else(|| AppError::NotFound("Device not found".to_string()))?;
+
+        // 2. Claim one one-time prekey of each kind; they are never handed out again
+        let txn = db.begin().await?;
+        let prekey = claim_one_time_prekey(&txn, device_id).await?;
+        let pq_prekey = claim_kyber_prekey(&txn, device_id).await?;
+        txn.commit().await?;
+
+        // 3. Prove the identity key is the one the log committed to
+        let tree_head = latest_tree_head(db, signer).await?;
+        let proof = inclusion_proof(db, &tree_head, device_id).await?;
+
+        Ok(prekey_bundle(device, prekey, pq_prekey, proof))
+    }
+}
+
+// ============ Get All Devices PreKey Bundles Use Case ============
+
+pub struct GetPreKeyBundlesUseCase;
+
+impl GetPreKeyBundlesUseCase {
+    /// Bundles for every active device of `user_id`, claiming one one-time
+    /// prekey per device. The requester's own device is left out.
+    #[instrument(skip(db, redis_conn, signer), fields(requester_id = %requester_id, user_id = %user_id))]
+    pub async fn execute(
+        db: &DatabaseConnection,
+        redis_conn: &mut MultiplexedConnection,
+        signer: &LogSigner,
+        requester_id: Uuid,
+        requester_device_id: i64,
+        user_id: Uuid,
+    ) -> AppResult<PreKeyBundlesResponse> {
+        check_prekey_fetch_limit(redis_conn, requester_id).await?;
+
+        users::Entity::find_by_id(user_id)
+            .filter(users::Column::IsDeleted.eq(false))
+            .one(db)
+            .await?
+            .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+
+        // One tree head for all proofs, so clients verify a single signature
+        let tree_head = latest_tree_head(db, signer).await?;
+
+        let txn = db.begin().await?;
+
+        let active_devices = devices::Entity::find()
+            .filter(devices::Column::UserId.eq(user_id))
+            .filter(devices::Column::IsActive.eq(true))
+            .filter(devices::Column::DeviceId.ne(requester_device_id))
+            .order_by_asc(devices::Column::DeviceId)
+            .all(&txn)
+            .await?;
+
+        let mut bundles = Vec::with_capacity(active_devices.len());
+        for device in active_devices {
+            let prekey = claim_one_time_prekey(&txn, device.device_id).await?;
+            let pq_prekey = claim_kyber_prekey(&txn, device.device_id).await?;
+            let proof = inclusion_proof(&txn, &tree_head, device.device_id).await?;
+            bundles.push(prekey_bundle(device, prekey, pq_prekey, proof));
+        }
+
+        txn.commit().await?;
+
+        Ok(PreKeyBundlesResponse {
+            user_id,
+            devices: bundles,
+        })
+    }
+}
+
+// ============ Upload PreKeys Use Case ============
+
+pub struct UploadPreKeysUseCase;
+
+impl UploadPreKeysUseCase {
+    /// Add one-time prekeys for the caller's device, and optionally replace its
+    /// last-resort Kyber prekey. IDs already stored are rejected rather than
+    /// overwritten, since a peer may have fetched that key.
+    #[instrument(skip(db, req), fields(user_id = %user_id, device_id = device_id, batch = req.one_time_prekeys.len(), pq_batch = req.pq_prekeys.len()))]
+    pub async fn execute(
+        db: &DatabaseConnection,
+        user_id: Uuid,
+        device_id: i64,
+        req: UploadPreKeysRequest,
+    ) -> AppResult<UploadPreKeysResponse> {
+        req.validate()
+            .map_err(|e| AppError::Validation(e.to_string()))?;
+        if req.one_time_prekeys.is_empty()
+            && req.pq_prekeys.is_empty()
+            && req.pq_last_resort_prekey.is_none()
+        {
+            return Err(AppError::Validation("Upload at least one prekey".to_string()));
+        }
+        let prekeys = parse_one_time_prekeys(&req.one_time_prekeys)?;
+
+        let txn = db.begin().await?;
+
+        // Locking the device row serialises concurrent uploads, so the cap holds
+        let device = devices::Entity::find_by_id(device_id)
+            .filter(devices::Column::UserId.eq(user_id))
+            .filter(devices::Column::IsActive.eq(true))
+            .lock_exclusive()
+            .one(&txn)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Device not found".to_string()))?;
+
+        let kyber = parse_kyber_prekeys(
+            &device.identity_key_public,
+            &req.pq_prekeys,
+            req.pq_last_resort_prekey.as_ref(),
+        )?;
+
+        let colliding: Vec<i32> = one_time_prekeys::Entity::find()
+            .select_only()
+            .column(one_time_prekeys::Column::PrekeyId)
             .filter(one_time_prekeys::Column::DeviceId.eq(device_id))
-            .order_by_asc(one_time_prekeys::Column::PrekeyId) // Get oldest
+            .filter(one_time_prekeys::Column::PrekeyId.is_in(prekeys.iter().map(|p| p.id)))
+            .into_tuple()
+            .all(&txn)
+            .await?;
+        if let Some(id) = colliding.first() {
+            return Err(AppError::Validation(format!("Prekey ID {} is already stored", id)));
+        }
+
+        let stored = count_one_time_prekeys(&txn, device_id).await?;
+        let uploaded = prekeys.len() as u64;
+        if stored + uploaded > MAX_STORED_PREKEYS {
+            return Err(AppError::Validation(format!(
+                "A device may store at most {} one-time prekeys ({} stored)",
+                MAX_STORED_PREKEYS, stored
+            )));
+        }
+
+        // The last-resort key being replaced does not count as a collision
+        let mut kyber_ids: Vec<i32> = kyber.one_time.iter().map(|p| p.id).collect();
+        kyber_ids.extend(kyber.last_resort.as_ref().map(|p| p.id));
+        let mut kyber_colliding = kyber_prekeys::Entity::find()
+            .select_only()
+            .column(kyber_prekeys::Column::PrekeyId)
+            .filter(kyber_prekeys::Column::DeviceId.eq(device_id))
+            .filter(kyber_prekeys::Column::PrekeyId.is_in(kyber_ids));
+        if kyber.last_resort.is_some() {
+            kyber_colliding = kyber_colliding.filter(kyber_prekeys::Column::IsLastResort.eq(false));
+        }
+        let kyber_colliding: Vec<i32> = kyber_colliding.into_tuple().all(&txn).await?;
+        if let Some(id) = kyber_colliding.first() {
+            return Err(AppError::Validation(format!(
+                "Kyber prekey ID {} is already stored",
+                id
+            )));
+        }
+
+        let pq_stored = count_kyber_prekeys(&txn, device_id).await?;
+        let pq_uploaded = kyber.one_time.len() as u64;
+        if pq_stored + pq_uploaded > MAX_STORED_PREKEYS {
+            return Err(AppError::Validation(format!(
+                "A device may store at most {} Kyber prekeys ({} stored)",
+                MAX_STORED_PREKEYS, pq_stored
+            )));
+        }
+
+        store_one_time_prekeys(&txn, device_id, prekeys).await?;
+        store_kyber_prekeys(&txn, device_id, kyber).await?;
+        txn.commit().await?;
+
+        info!(
+            "Stored {} one-time and {} Kyber prekeys for device {}",
+            uploaded, pq_uploaded, device_id
+        );
+
+        Ok(UploadPreKeysResponse {
+            uploaded,
+            count: stored + uploaded,
+            pq_uploaded,
+            pq_count: pq_stored + pq_uploaded,
+        })
+    }
+}
+
+// ============ Get PreKey Count Use Case ============
+
+pub struct GetPreKeyCountUseCase;
+
+impl GetPreKeyCountUseCase {
+    #[instrument(skip(db))]
+    pub async fn execute(db: &DatabaseConnection, device_id: i64) -> AppResult<PreKeyCountResponse> {
+        Ok(PreKeyCountResponse {
+            count: count_one_time_prekeys(db, device_id).await?,
+            pq_count: count_kyber_prekeys(db, device_id).await?,
+            pq_last_resort: has_kyber_last_resort(db, device_id).await?,
+            low_threshold: PREKEY_LOW_THRESHOLD,
+        })
+    }
+}
+
+// ============ Rotate Signed PreKey Use Case ============
+
+pub struct RotateSignedPreKeyUseCase;
+
+impl RotateSignedPreKeyUseCase {
+    /// Replace the device's signed prekey. The old key stays on record for
+    /// `grace_seconds`; retrying an upload that already succeeded is a no-op.
+    #[instrument(skip(db, req), fields(user_id = %user_id, device_id = device_id, signed_prekey_id = req.id))]
+    pub async fn execute(
+        db: &DatabaseConnection,
+        user_id: Uuid,
+        device_id: i64,
+        grace_seconds: i64,
+        req: SignedPreKeyUpload,
+    ) -> AppResult<SignedPreKeyStatusResponse> {
+        let txn = db.begin().await?;
+
+        let device = devices::Entity::find_by_id(device_id)
+            .filter(devices::Column::UserId.eq(user_id))
+            .filter(devices::Column::IsActive.eq(true))
+            .lock_exclusive()
+            .one(&txn)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Device not found".to_string()))?;
+
+        let signed_prekey = parse_signed_prekey(&device.identity_key_public, &req)?;
+
+        if signed_prekey.id == device.signed_prekey_id {
+            if signed_prekey.public_key == device.signed_prekey_public {
+                return Ok(signed_prekey_status(&device, false));
+            }
+            return Err(AppError::Validation(format!(
+                "Signed prekey ID {} is already in use",
+                signed_prekey.id
+            )));
+        }
+        if Some(signed_prekey.id) == device.previous_signed_prekey_id {
+            return Err(AppError::Validation(format!(
+                "Signed prekey ID {} was used by the previous key",
+                signed_prekey.id
+            )));
+        }
+
+        let now = Utc::now();
+        let previous_id = device.signed_prekey_id;
+        let mut active_device: devices::ActiveModel = device.clone().into();
+        active_device.previous_signed_prekey_id = Set(Some(device.signed_prekey_id));
+        active_device.previous_signed_prekey_public = Set(Some(device.signed_prekey_public));
+        active_device.previous_signed_prekey_signature = Set(Some(device.signed_prekey_signature));
+        active_device.previous_signed_prekey_expires_at =
+            Set(Some((now + Duration::seconds(grace_seconds)).into()));
+        active_device.signed_prekey_id = Set(signed_prekey.id);
+        active_device.signed_prekey_public = Set(signed_prekey.public_key);
+        active_device.signed_prekey_signature = Set(signed_prekey.signature);
+        active_device.signed_prekey_created_at = Set(now.into());
+        let device = active_device.update(&txn).await?;
+
+        audit::record(
+            &txn,
+            AuditEvent::by_user(user_id, device_id, audit::SIGNED_PREKEY_ROTATED).with_details(
+                serde_json::json!({ "previous_id": previous_id, "id": device.signed_prekey_id }),
+            ),
+        )
+        .await?;
+
+        txn.commit().await?;
+
+        info!("Signed prekey of device {} rotated to {}", device_id, device.signed_prekey_id);
+
+        Ok(signed_prekey_status(&device, false))
+    }
+}
+
+// ============ Get Signed PreKey Status Use Case ============
+
+pub struct GetSignedPreKeyStatusUseCase;
+
+impl GetSignedPreKeyStatusUseCase {
+    #[instrument(skip(db))]
+    pub async fn execute(
+        db: &DatabaseConnection,
+        device_id: i64,
+        rotation_seconds: i64,
+    ) -> AppResult<SignedPreKeyStatusResponse> {
+        let device = devices::Entity::find_by_id(device_id)
             .one(db)
-            .await
-            .map_err(|e| e.to_string())?;
-
-        // If we found a prekey, we should ideally delete it.
-        if let Some(ref pk) = prekey {
-             // TODO: Delete prekey in a transaction? 
-             // For now, let's just return it. 
-             // If we delete it, we need to make sure we don't run out.
-             // The client should replenish keys.
-             let _ = one_time_prekeys::Entity::delete_by_id((pk.device_id, pk.prekey_id))
-                 .exec(db)
-                 .await
-                 .map_err(|e| e.to_string())?;
-        }
-
-        Ok(PreKeyBundleResponse {
-            device_id: device.device_id,
-            registration_id: device.registration_id,
-            identity_key: device.identity_key_public,
-            signed_prekey: SignedPreKeyDto {
-                id: device.signed_prekey_id,
-                key: device.signed_prekey_public,
-                signature: device.signed_prekey_signature,
-            },
-            one_time_prekey: prekey.map(|pk| PreKeyDto {
-                id: pk.prekey_id,
-                key: pk.public_key,
-            }),
+            .await?
+            .ok_or_else(|| AppError::NotFound("Device not found".to_string()))?;
+
+        let rotation_due = is_signed_prekey_stale(&device, rotation_seconds);
+        Ok(signed_prekey_status(&device, rotation_due))
+    }
+}
+
+// ============ Expire Previous Signed PreKeys Use Case ============
+
+pub struct ExpirePreviousSignedPreKeysUseCase;
+
+impl ExpirePreviousSignedPreKeysUseCase {
+    /// Forget replaced signed prekeys whose grace period has ended
+    #[instrument(skip(db))]
+    pub async fn execute(db: &DatabaseConnection) -> AppResult<u64> {
+        let result = devices::Entity::update_many()
+            .col_expr(devices::Column::PreviousSignedPrekeyId, Expr::value(Option::<i32>::None))
+            .col_expr(devices::Column::PreviousSignedPrekeyPublic, Expr::value(Option::<Vec<u8>>::None))
+            .col_expr(
+                devices::Column::PreviousSignedPrekeySignature,
+                Expr::value(Option::<Vec<u8>>::None),
+            )
+            .col_expr(
+                devices::Column::PreviousSignedPrekeyExpiresAt,
+                Expr::value(Option::<chrono::DateTime<Utc>>::None),
+            )
+            .filter(devices::Column::PreviousSignedPrekeyExpiresAt.lte(Utc::now()))
+            .exec(db)
+            .await?;
+
+        if result.rows_affected > 0 {
+            info!("Expired {} previous signed prekeys", result.rows_affected);
+        }
+        Ok(result.rows_affected)
+    }
+}
+
+// ============ Get Identity Keys Use Case ============
+
+pub struct GetIdentityKeysUseCase;
+
+impl GetIdentityKeysUseCase {
+    #[instrument(skip(db), fields(user_id = %user_id))]
+    pub async fn execute(db: &DatabaseConnection, user_id: Uuid) -> AppResult<IdentityKeysResponse> {
+        users::Entity::find_by_id(user_id)
+            .filter(users::Column::IsDeleted.eq(false))
+            .one(db)
+            .await?
+            .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+
+        let history = identity_key_history::Entity::find()
+            .filter(identity_key_history::Column::UserId.eq(user_id))
+            .order_by_asc(identity_key_history::Column::Id)
+            .all(db)
+            .await?;
+
+        // A key counts from when it was first registered, until a
+        // re-registration replaces it with a different one
+        let mut first_seen: HashMap<Vec<u8>, DateTimeWithTimeZone> = HashMap::new();
+        for entry in history {
+            if let Some(previous) = &entry.previous_identity_key {
+                first_seen.remove(previous);
+            }
+            first_seen.entry(entry.identity_key).or_insert(entry.created_at);
+        }
+
+        let devices = devices::Entity::find()
+            .filter(devices::Column::UserId.eq(user_id))
+            .filter(devices::Column::IsActive.eq(true))
+            .order_by_asc(devices::Column::DeviceId)
+            .all(db)
+            .await?
+            .into_iter()
+            .map(|device| DeviceIdentityKey {
+                device_id: device.device_id,
+                identity_key: with_key_type(DJB_TYPE, &device.identity_key_public),
+                since: first_seen
+                    .get(&device.identity_key_public)
+                    .copied()
+                    .unwrap_or(device.created_at)
+                    .into(),
+            })
+            .collect();
+
+        Ok(IdentityKeysResponse { user_id, devices })
+    }
+}
+
+// ============ List Identity Changes Use Case ============
+
+pub struct ListIdentityChangesUseCase;
+
+impl ListIdentityChangesUseCase {
+    /// Key bindings of the caller's contacts and of their own other devices
+    /// recorded after `query.after`. Devices call this on reconnect to catch
+    /// up on `IdentityChanged` events they missed while offline.
+    #[instrument(skip(db, query), fields(user_id = %user_id))]
+    pub async fn execute(
+        db: &DatabaseConnection,
+        user_id: Uuid,
+        device_id: i64,
+        query: ListIdentityChangesQuery,
+    ) -> AppResult<IdentityChangesResponse> {
+        query
+            .validate()
+            .map_err(|e| AppError::Validation(e.to_string()))?;
+
+        let mut user_ids = list_contact_ids(db, user_id).await?;
+        user_ids.push(user_id);
+
+        let changes = identity_key_history::Entity::find()
+            .filter(identity_key_history::Column::UserId.is_in(user_ids))
+            .filter(identity_key_history::Column::Id.gt(query.after.unwrap_or(0)))
+            .filter(
+                Condition::any()
+                    .add(identity_key_history::Column::UserId.ne(user_id))
+                    .add(identity_key_history::Column::DeviceId.ne(device_id)),
+            )
+            .order_by_asc(identity_key_history::Column::Id)
+            .limit(query.limit.unwrap_or(IDENTITY_CHANGES_DEFAULT_LIMIT))
+            .all(db)
+            .await?
+            .into_iter()
+            .map(|entry| IdentityChange {
+                id: entry.id,
+                user_id: entry.user_id,
+                device_id: entry.device_id,
+                identity_key: with_key_type(DJB_TYPE, &entry.identity_key),
+                previous_identity_key: entry
+                    .previous_identity_key
+                    .map(|key| with_key_type(DJB_TYPE, &key)),
+                created_at: entry.created_at.into(),
+            })
+            .collect();
+
+        Ok(IdentityChangesResponse { changes })
+    }
+}
+
+// ============ Helpers ============
+
+/// Count a bundle fetch against the requester's limit
+pub async fn check_prekey_fetch_limit(
+    redis_conn: &mut MultiplexedConnection,
+    requester_id: Uuid,
+) -> AppResult<()> {
+    check_rate_limit(
+        redis_conn,
+        &format!("prekey_fetch:{}", requester_id),
+        PREKEY_FETCH_MAX_REQUESTS,
+        PREKEY_FETCH_WINDOW_SECONDS,
+        "Too many key requests. Please try again later.",
+    )
+    .await
+}
+
+fn prekey_bundle(
+    device: devices::Model,
+    prekey: Option<one_time_prekeys::Model>,
+    pq_prekey: Option<kyber_prekeys::Model>,
+    key_transparency: Option<InclusionProof>,
+) -> PreKeyBundleResponse {
+    PreKeyBundleResponse {
+        device_id: device.device_id,
+        registration_id: device.registration_id,
+        identity_key: with_key_type(DJB_TYPE, &device.identity_key_public),
+        signed_prekey: SignedPreKeyDto {
+            id: device.signed_prekey_id,
+            key: with_key_type(DJB_TYPE, &device.signed_prekey_public),
+            signature: device.signed_prekey_signature,
+        },
+        one_time_prekey: prekey.map(|pk| PreKeyDto {
+            id: pk.prekey_id,
+            key: with_key_type(DJB_TYPE, &pk.public_key),
+        }),
+        pq_prekey: pq_prekey.map(|pk| KyberPreKeyDto {
+            id: pk.prekey_id,
+            key: with_key_type(KYBER_1024_TYPE, &pk.public_key),
+            signature: pk.signature,
+            last_resort: pk.is_last_resort,
+        }),
+        key_transparency,
+    }
+}
+
+/// Keys are stored bare; bundles carry them in the Signal wire format
+fn with_key_type(key_type: u8, key: &[u8]) -> Vec<u8> {
+    [&[key_type][..], key].concat()
+}
+
+/// Whether the device's signed prekey is older than the rotation interval
+pub fn is_signed_prekey_stale(device: &devices::Model, rotation_seconds: i64) -> bool {
+    Utc::now() - Duration::seconds(rotation_seconds) > device.signed_prekey_created_at
+}
+
+fn signed_prekey_status(device: &devices::Model, rotation_due: bool) -> SignedPreKeyStatusResponse {
+    // An expired previous key may not have been cleared by the job yet
+    let previous = device
+        .previous_signed_prekey_expires_at
+        .filter(|expires_at| *expires_at > Utc::now())
+        .and(device.previous_signed_prekey_id);
+
+    SignedPreKeyStatusResponse {
+        id: device.signed_prekey_id,
+        created_at: device.signed_prekey_created_at.into(),
+        rotation_due,
+        previous_id: previous,
+        previous_expires_at: previous.and(device.previous_signed_prekey_expires_at.map(Into::into)),
+    }
+}
+
+/// Atomically remove and return the device's oldest one-time prekey.
+/// Concurrent claims skip rows another transaction has locked instead of
+/// waiting on them, so no key is ever returned twice.
+pub async fn claim_one_time_prekey<C: ConnectionTrait>(
+    db: &C,
+    device_id: i64,
+) -> AppResult<Option<one_time_prekeys::Model>> {
+    let stmt = Statement::from_sql_and_values(
+        DbBackend::Postgres,
+        r#"DELETE FROM one_time_prekeys
+           WHERE (device_id, prekey_id) = (
+               SELECT device_id, prekey_id FROM one_time_prekeys
+               WHERE device_id = $1
+               ORDER BY prekey_id
+               LIMIT 1
+               FOR UPDATE SKIP LOCKED
+           )
+           RETURNING device_id, prekey_id, public_key"#,
+        [device_id.into()],
+    );
+
+    Ok(one_time_prekeys::Entity::find()
+        .from_raw_sql(stmt)
+        .one(db)
+        .await?)
+}
+
+/// Claim one of the device's one-time Kyber prekeys the same way as
+/// [`claim_one_time_prekey`]. Once they run out the last-resort key is
+/// returned instead; it is shared by every fetch and never deleted.
+pub async fn claim_kyber_prekey<C: ConnectionTrait>(
+    db: &C,
+    device_id: i64,
+) -> AppResult<Option<kyber_prekeys::Model>> {
+    let stmt = Statement::from_sql_and_values(
+        DbBackend::Postgres,
+        r#"DELETE FROM kyber_prekeys
+           WHERE (device_id, prekey_id) = (
+               SELECT device_id, prekey_id FROM kyber_prekeys
+               WHERE device_id = $1 AND NOT is_last_resort
+               ORDER BY prekey_id
+               LIMIT 1
+               FOR UPDATE SKIP LOCKED
+           )
+           RETURNING device_id, prekey_id, public_key, signature, is_last_resort, created_at"#,
+        [device_id.into()],
+    );
+
+    if let Some(prekey) = kyber_prekeys::Entity::find()
+        .from_raw_sql(stmt)
+        .one(db)
+        .await?
+    {
+        return Ok(Some(prekey));
+    }
+
+    Ok(kyber_prekeys::Entity::find()
+        .filter(kyber_prekeys::Column::DeviceId.eq(device_id))
+        .filter(kyber_prekeys::Column::IsLastResort.eq(true))
+        .one(db)
+        .await?)
+}
+
+pub async fn count_one_time_prekeys<C: ConnectionTrait>(db: &C, device_id: i64) -> AppResult<u64> {
+    Ok(one_time_prekeys::Entity::find()
+        .filter(one_time_prekeys::Column::DeviceId.eq(device_id))
+        .count(db)
+        .await?)
+}
+
+/// One-time Kyber prekeys stored for a device, not counting the last-resort key
+pub async fn count_kyber_prekeys<C: ConnectionTrait>(db: &C, device_id: i64) -> AppResult<u64> {
+    Ok(kyber_prekeys::Entity::find()
+        .filter(kyber_prekeys::Column::DeviceId.eq(device_id))
+        .filter(kyber_prekeys::Column::IsLastResort.eq(false))
+        .count(db)
+        .await?)
+}
+
+pub async fn has_kyber_last_resort<C: ConnectionTrait>(db: &C, device_id: i64) -> AppResult<bool> {
+    Ok(kyber_prekeys::Entity::find()
+        .filter(kyber_prekeys::Column::DeviceId.eq(device_id))
+        .filter(kyber_prekeys::Column::IsLastResort.eq(true))
+        .count(db)
+        .await?
+        > 0)
+}
+
+/// Store verified one-time prekeys for a device
+pub(crate) async fn store_one_time_prekeys<C: ConnectionTrait>(
+    db: &C,
+    device_id: i64,
+    prekeys: Vec<PreKey>,
+) -> AppResult<()> {
+    if prekeys.is_empty() {
+        return Ok(());
+    }
+
+    one_time_prekeys::Entity::insert_many(prekeys.into_iter().map(|prekey| {
+        one_time_prekeys::ActiveModel {
+            device_id: Set(device_id),
+            prekey_id: Set(prekey.id),
+            public_key: Set(prekey.public_key),
+        }
+    }))
+    .exec(db)
+    .await?;
+
+    Ok(())
+}
+
+/// Store verified Kyber prekeys for a device. A new last-resort key replaces
+/// the previous one.
+pub(crate) async fn store_kyber_prekeys<C: ConnectionTrait>(
+    db: &C,
+    device_id: i64,
+    prekeys: KyberPreKeys,
+) -> AppResult<()> {
+    if prekeys.last_resort.is_some() {
+        kyber_prekeys::Entity::delete_many()
+            .filter(kyber_prekeys::Column::DeviceId.eq(device_id))
+            .filter(kyber_prekeys::Column::IsLastResort.eq(true))
+            .exec(db)
+            .await?;
+    }
+
+    let rows = prekeys
+        .one_time
+        .into_iter()
+        .map(|prekey| (prekey, false))
+        .chain(prekeys.last_resort.map(|prekey| (prekey, true)))
+        .map(|(prekey, is_last_resort)| kyber_prekeys::ActiveModel {
+            device_id: Set(device_id),
+            prekey_id: Set(prekey.id),
+            public_key: Set(prekey.public_key),
+            signature: Set(prekey.signature),
+            is_last_resort: Set(is_last_resort),
+            ..Default::default()
         })
+        .collect::<Vec<_>>();
+    if rows.is_empty() {
+        return Ok(());
+    }
+
+    kyber_prekeys::Entity::insert_many(rows).exec(db).await?;
+
+    Ok(())
+}
+
+/// Add a device's identity key to the user's history and the key
+/// transparency log. Returns whether it differs from `previous`, the key of
+/// the device it replaces.
+pub async fn record_identity_key<C: ConnectionTrait>(
+    db: &C,
+    user_id: Uuid,
+    device_id: i64,
+    identity_key: &[u8],
+    previous: Option<Vec<u8>>,
+) -> AppResult<bool> {
+    let previous = previous.filter(|key| key != identity_key);
+    let changed = previous.is_some();
+
+    identity_key_history::ActiveModel {
+        user_id: Set(user_id),
+        device_id: Set(device_id),
+        identity_key: Set(identity_key.to_vec()),
+        previous_identity_key: Set(previous),
+        ..Default::default()
     }
+    .insert(db)
+    .await?;
+
+    append_binding(db, user_id, device_id, identity_key).await?;
+
+    Ok(changed)
 }
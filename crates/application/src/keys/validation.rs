@@ -0,0 +1,287 @@
+use crate::keys::dtos::{DeviceKeysUpload, KyberPreKeyUpload, PreKeyUpload, SignedPreKeyUpload};
+use crate::{AppError, AppResult};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use crypto::{verify_kyber_prekey, verify_signed_prekey, KyberPublicKey, PublicKey};
+use std::collections::HashSet;
+use validator::Validate;
+
+// ============ Constants ============
+
+const SIGNATURE_BYTES: usize = crypto::xeddsa::SIGNATURE_LENGTH;
+/// Prekey IDs are 24-bit on the wire
+const MAX_PREKEY_ID: i32 = 0xFF_FFFF;
+
+// ============ Validated Keys ============
+
+/// Decoded public keys whose signed prekey has been checked against the identity key.
+/// Keys arrive in the Signal wire format and are kept without their type byte.
+#[derive(Debug, Clone)]
+pub struct DeviceKeys {
+    pub registration_id: i32,
+    pub identity_key: Vec<u8>,
+    pub signed_prekey: SignedPreKey,
+    pub one_time_prekeys: Vec<PreKey>,
+    pub kyber_prekeys: KyberPreKeys,
+}
+
+#[derive(Debug, Clone)]
+pub struct SignedPreKey {
+    pub id: i32,
+    pub public_key: Vec<u8>,
+    pub signature: Vec<u8>,
+}
+
+#[derive(Debug, Clone)]
+pub struct PreKey {
+    pub id: i32,
+    pub public_key: Vec<u8>,
+}
+
+/// Kyber prekeys whose signatures have been checked against the identity key
+#[derive(Debug, Clone, Default)]
+pub struct KyberPreKeys {
+    pub one_time: Vec<SignedPreKey>,
+    pub last_resort: Option<SignedPreKey>,
+}
+
+impl KyberPreKeys {
+    pub fn is_empty(&self) -> bool {
+        self.one_time.is_empty() && self.last_resort.is_none()
+    }
+}
+
+/// Decode and verify a device's uploaded keys. Nothing is stored unless the
+/// signed prekey carries a valid signature from the identity key.
+pub fn parse_device_keys(upload: &DeviceKeysUpload) -> AppResult<DeviceKeys> {
+    upload
+        .validate()
+        .map_err(|e| AppError::Validation(e.to_string()))?;
+
+    let identity_key = decode_public_key("identity_key", &upload.identity_key)?
+        .public_key_bytes()
+        .to_vec();
+    let signed_prekey = parse_signed_prekey(&identity_key, &upload.signed_prekey)?;
+    let one_time_prekeys = parse_one_time_prekeys(&upload.one_time_prekeys)?;
+    let kyber_prekeys = parse_kyber_prekeys(
+        &identity_key,
+        &upload.pq_prekeys,
+        upload.pq_last_resort_prekey.as_ref(),
+    )?;
+
+    Ok(DeviceKeys {
+        registration_id: upload.registration_id,
+        identity_key,
+        signed_prekey,
+        one_time_prekeys,
+        kyber_prekeys,
+    })
+}
+
+/// Decode a signed prekey and check its XEdDSA signature against the device's
+/// bare Curve25519 identity key
+pub fn parse_signed_prekey(
+    identity_key: &[u8],
+    upload: &SignedPreKeyUpload,
+) -> AppResult<SignedPreKey> {
+    upload
+        .validate()
+        .map_err(|e| AppError::Validation(e.to_string()))?;
+
+    let identity_key = identity_public_key(identity_key)?;
+    let public_key = decode_public_key("signed_prekey.public_key", &upload.public_key)?;
+    let signature = decode_signature("signed_prekey.signature", &upload.signature)?;
+
+    verify_signed_prekey(&identity_key, &public_key, &signature)
+        .map_err(|_| AppError::Validation("Invalid signed prekey signature".to_string()))?;
+
+    Ok(SignedPreKey {
+        id: upload.id,
+        public_key: public_key.public_key_bytes().to_vec(),
+        signature,
+    })
+}
+
+/// Decode one-time prekeys, rejecting out-of-range and repeated IDs
+pub fn parse_one_time_prekeys(prekeys: &[PreKeyUpload]) -> AppResult<Vec<PreKey>> {
+    let mut seen = HashSet::new();
+    prekeys
+        .iter()
+        .map(|prekey| {
+            if !(1..=MAX_PREKEY_ID).contains(&prekey.id) {
+                return Err(AppError::Validation(format!(
+                    "Prekey ID must be between 1-{}",
+                    MAX_PREKEY_ID
+                )));
+            }
+            if !seen.insert(prekey.id) {
+                return Err(AppError::Validation(format!(
+                    "Duplicate prekey ID {}",
+                    prekey.id
+                )));
+            }
+            Ok(PreKey {
+                id: prekey.id,
+                public_key: decode_public_key("one_time_prekeys.public_key", &prekey.public_key)?
+                    .public_key_bytes()
+                    .to_vec(),
+            })
+        })
+        .collect()
+}
+
+/// Decode Kyber prekeys and check each signature against the device's identity
+/// key. One-time and last-resort keys share an ID space.
+pub fn parse_kyber_prekeys(
+    identity_key: &[u8],
+    one_time: &[KyberPreKeyUpload],
+    last_resort: Option<&KyberPreKeyUpload>,
+) -> AppResult<KyberPreKeys> {
+    let identity_key = identity_public_key(identity_key)?;
+    let mut seen = HashSet::new();
+    let mut parse = |prekey: &KyberPreKeyUpload| {
+        if !(1..=MAX_PREKEY_ID).contains(&prekey.id) {
+            return Err(AppError::Validation(format!(
+                "Kyber prekey ID must be between 1-{}",
+                MAX_PREKEY_ID
+            )));
+        }
+        if !seen.insert(prekey.id) {
+            return Err(AppError::Validation(format!(
+                "Duplicate Kyber prekey ID {}",
+                prekey.id
+            )));
+        }
+        let public_key = KyberPublicKey::deserialize(&decode_base64(
+            "pq_prekeys.public_key",
+            &prekey.public_key,
+        )?)
+        .map_err(|e| AppError::Validation(format!("pq_prekeys.public_key: {}", e)))?;
+        let signature = decode_signature("pq_prekeys.signature", &prekey.signature)?;
+
+        verify_kyber_prekey(&identity_key, &public_key, &signature).map_err(|_| {
+            AppError::Validation(format!("Invalid Kyber prekey {}", prekey.id))
+        })?;
+
+        Ok(SignedPreKey {
+            id: prekey.id,
+            public_key: public_key.public_key_bytes().to_vec(),
+            signature,
+        })
+    };
+
+    Ok(KyberPreKeys {
+        one_time: one_time.iter().map(&mut parse).collect::<AppResult<_>>()?,
+        last_resort: last_resort.map(parse).transpose()?,
+    })
+}
+
+fn identity_public_key(identity_key: &[u8]) -> AppResult<PublicKey> {
+    PublicKey::from_djb_public_key_bytes(identity_key)
+        .map_err(|e| AppError::Validation(format!("identity_key: {}", e)))
+}
+
+/// A type-prefixed Curve25519 key
+fn decode_public_key(field: &str, value: &str) -> AppResult<PublicKey> {
+    PublicKey::deserialize(&decode_base64(field, value)?)
+        .map_err(|e| AppError::Validation(format!("{}: {}", field, e)))
+}
+
+fn decode_signature(field: &str, value: &str) -> AppResult<Vec<u8>> {
+    Some(decode_base64(field, value)?)
+        .filter(|bytes| bytes.len() == SIGNATURE_BYTES)
+        .ok_or_else(|| {
+            AppError::Validation(format!("{} must be {} bytes", field, SIGNATURE_BYTES))
+        })
+}
+
+fn decode_base64(field: &str, value: &str) -> AppResult<Vec<u8>> {
+    BASE64
+        .decode(value)
+        .map_err(|_| AppError::Validation(format!("{} must be base64 encoded", field)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keys::dtos::{PreKeyUpload, SignedPreKeyUpload};
+    use crypto::generate::{
+        generate_identity_keypair, generate_kyber_public_key, generate_prekeys,
+        generate_signed_prekey, sign_kyber_prekey,
+    };
+    use crypto::KeyPair;
+
+    fn kyber_upload(identity: &KeyPair, id: u32) -> KyberPreKeyUpload {
+        let prekey = sign_kyber_prekey(identity, id, generate_kyber_public_key());
+        KyberPreKeyUpload {
+            id: prekey.id as i32,
+            public_key: BASE64.encode(prekey.public_key.serialize()),
+            signature: BASE64.encode(&prekey.signature),
+        }
+    }
+
+    fn client_upload() -> DeviceKeysUpload {
+        let identity = generate_identity_keypair();
+        let signed_prekey = generate_signed_prekey(&identity, 1);
+        DeviceKeysUpload {
+            registration_id: 42,
+            identity_key: BASE64.encode(identity.public_key.serialize()),
+            signed_prekey: SignedPreKeyUpload {
+                id: signed_prekey.id as i32,
+                public_key: BASE64.encode(signed_prekey.key_pair.public_key.serialize()),
+                signature: BASE64.encode(&signed_prekey.signature),
+            },
+            one_time_prekeys: generate_prekeys(1, 3)
+                .into_iter()
+                .map(|prekey| PreKeyUpload {
+                    id: prekey.id as i32,
+                    public_key: BASE64.encode(prekey.key_pair.public_key.serialize()),
+                })
+                .collect(),
+            pq_prekeys: (1..=2).map(|id| kyber_upload(&identity, id)).collect(),
+            pq_last_resort_prekey: Some(kyber_upload(&identity, 100)),
+        }
+    }
+
+    #[test]
+    fn test_parse_device_keys() {
+        let upload = client_upload();
+        let keys = parse_device_keys(&upload).unwrap();
+        assert_eq!(keys.identity_key.len(), crypto::keys::PUBLIC_KEY_LENGTH);
+        assert_eq!(keys.one_time_prekeys.len(), 3);
+        assert_eq!(keys.kyber_prekeys.one_time.len(), 2);
+        assert_eq!(keys.kyber_prekeys.last_resort.as_ref().unwrap().id, 100);
+
+        // A signed prekey swapped for another key no longer verifies
+        let mut forged = upload.clone();
+        forged.signed_prekey.public_key = forged.one_time_prekeys[0].public_key.clone();
+        assert!(parse_device_keys(&forged).is_err());
+
+        let mut duplicate = upload.clone();
+        duplicate.one_time_prekeys[1].id = duplicate.one_time_prekeys[0].id;
+        assert!(parse_device_keys(&duplicate).is_err());
+
+        let mut out_of_range = upload.clone();
+        out_of_range.one_time_prekeys[0].id = 0;
+        assert!(parse_one_time_prekeys(&out_of_range.one_time_prekeys).is_err());
+
+        // Kyber prekeys must be signed by this device's identity key
+        let other = generate_identity_keypair();
+        let mut foreign = upload.clone();
+        foreign.pq_prekeys[0] = kyber_upload(&other, 1);
+        assert!(parse_device_keys(&foreign).is_err());
+
+        // One-time and last-resort Kyber keys share an ID space
+        let mut kyber_duplicate = upload.clone();
+        kyber_duplicate.pq_last_resort_prekey.as_mut().unwrap().id = 2;
+        assert!(parse_device_keys(&kyber_duplicate).is_err());
+
+        // Keys must carry their Signal type byte
+        let mut bare = upload.clone();
+        bare.identity_key = BASE64.encode(&keys.identity_key);
+        assert!(parse_device_keys(&bare).is_err());
+
+        let mut truncated = upload;
+        truncated.identity_key = BASE64.encode([0u8; 16]);
+        assert!(parse_device_keys(&truncated).is_err());
+    }
+}
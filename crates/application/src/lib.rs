@@ -1,6 +1,18 @@
+pub mod account;
+pub mod admin;
+pub mod audit;
+pub mod blocks;
 pub mod auth;
 pub mod chat;
+pub mod contacts;
 pub mod error;
 pub mod keys;
+pub mod media;
+pub mod profiles;
+pub mod rate_limit;
+pub mod reports;
+pub mod sessions;
+pub mod transparency;
+pub mod users;
 
 pub use error::{AppError, AppResult};
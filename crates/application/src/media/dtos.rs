@@ -0,0 +1,16 @@
+use serde::{Deserialize, Serialize};
+
+// ============ Profile Images ============
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UploadProfileImageResponse {
+    pub url: String,
+    pub thumbnail_url: String,
+    pub width: u32,
+    pub height: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RemoveProfileImageResponse {
+    pub removed: bool,
+}
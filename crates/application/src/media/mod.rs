@@ -0,0 +1,5 @@
+pub mod dtos;
+pub mod processing;
+pub mod use_cases;
+
+pub use use_cases::{GetMediaUseCase, RemoveProfileImageUseCase, UploadProfileImageUseCase};
@@ -0,0 +1,192 @@
+use crate::{AppError, AppResult};
+use image::codecs::jpeg::JpegEncoder;
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageDecoder, ImageFormat, ImageReader, Limits, RgbImage};
+use std::io::Cursor;
+
+// ============ Constants ============
+
+const MIN_DIMENSION: u32 = 64;
+const MAX_DIMENSION: u32 = 8192;
+const MAX_DECODE_ALLOC_BYTES: u64 = 256 * 1024 * 1024;
+const JPEG_QUALITY: u8 = 85;
+
+const AVATAR_SIZE: u32 = 640;
+const AVATAR_THUMBNAIL_SIZE: u32 = 160;
+const BACKGROUND_MAX_SIZE: u32 = 1920;
+const BACKGROUND_THUMBNAIL_SIZE: u32 = 480;
+
+/// Which profile image is being uploaded; decides crop and output sizes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProfileImageKind {
+    Avatar,
+    Background,
+}
+
+impl ProfileImageKind {
+    /// Parse the path segment used by the upload endpoints
+    pub fn parse(value: &str) -> AppResult<Self> {
+        match value {
+            "avatar" => Ok(ProfileImageKind::Avatar),
+            "background" => Ok(ProfileImageKind::Background),
+            _ => Err(AppError::NotFound(format!("Unknown profile image kind: {}", value))),
+        }
+    }
+
+    /// Top-level directory in the blob store
+    pub fn directory(self) -> &'static str {
+        match self {
+            ProfileImageKind::Avatar => "avatars",
+            ProfileImageKind::Background => "backgrounds",
+        }
+    }
+}
+
+pub struct ProcessedImage {
+    pub full: Vec<u8>,
+    pub thumbnail: Vec<u8>,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Validate and re-encode an uploaded image.
+///
+/// Only JPEG, PNG and WebP are accepted, sniffed from the bytes rather than
+/// trusted from the client. The EXIF orientation is applied and the image is
+/// re-encoded as baseline JPEG, which drops EXIF, ICC and any other metadata
+/// (GPS positions included) along with anything appended to the file.
+pub fn process_profile_image(data: &[u8], kind: ProfileImageKind) -> AppResult<ProcessedImage> {
+    let mut reader = ImageReader::new(Cursor::new(data))
+        .with_guessed_format()
+        .map_err(|e| AppError::Validation(format!("Unreadable image: {}", e)))?;
+
+    match reader.format() {
+        Some(ImageFormat::Jpeg | ImageFormat::Png | ImageFormat::WebP) => {}
+        _ => {
+            return Err(AppError::Validation(
+                "Unsupported image type. Use JPEG, PNG or WebP".to_string(),
+            ))
+        }
+    }
+
+    // Reject decompression bombs before any pixels are allocated
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(MAX_DIMENSION);
+    limits.max_image_height = Some(MAX_DIMENSION);
+    limits.max_alloc = Some(MAX_DECODE_ALLOC_BYTES);
+    reader.limits(limits);
+
+    let mut decoder = reader
+        .into_decoder()
+        .map_err(|e| AppError::Validation(format!("Invalid image: {}", e)))?;
+
+    let (width, height) = decoder.dimensions();
+    if width < MIN_DIMENSION || height < MIN_DIMENSION {
+        return Err(AppError::Validation(format!(
+            "Image must be at least {}x{} pixels",
+            MIN_DIMENSION, MIN_DIMENSION
+        )));
+    }
+
+    let orientation = decoder
+        .orientation()
+        .map_err(|e| AppError::Validation(format!("Invalid image: {}", e)))?;
+    let mut img = DynamicImage::from_decoder(decoder)
+        .map_err(|e| AppError::Validation(format!("Invalid image: {}", e)))?;
+    img.apply_orientation(orientation);
+
+    let (full, thumbnail) = match kind {
+        ProfileImageKind::Avatar => {
+            // Square, centre-cropped; small sources are not upscaled
+            let side = img.width().min(img.height()).min(AVATAR_SIZE);
+            (
+                img.resize_to_fill(side, side, FilterType::Lanczos3),
+                img.resize_to_fill(AVATAR_THUMBNAIL_SIZE, AVATAR_THUMBNAIL_SIZE, FilterType::Lanczos3),
+            )
+        }
+        ProfileImageKind::Background => {
+            let full = if img.width() > BACKGROUND_MAX_SIZE || img.height() > BACKGROUND_MAX_SIZE {
+                img.resize(BACKGROUND_MAX_SIZE, BACKGROUND_MAX_SIZE, FilterType::Lanczos3)
+            } else {
+                img.clone()
+            };
+            (
+                full,
+                img.resize(BACKGROUND_THUMBNAIL_SIZE, BACKGROUND_THUMBNAIL_SIZE, FilterType::Lanczos3),
+            )
+        }
+    };
+
+    Ok(ProcessedImage {
+        width: full.width(),
+        height: full.height(),
+        full: encode_jpeg(&full)?,
+        thumbnail: encode_jpeg(&thumbnail)?,
+    })
+}
+
+fn encode_jpeg(img: &DynamicImage) -> AppResult<Vec<u8>> {
+    let mut buf = Vec::new();
+    JpegEncoder::new_with_quality(&mut buf, JPEG_QUALITY)
+        .encode_image(&flatten(img))
+        .map_err(|e| AppError::Internal(format!("Failed to encode image: {}", e)))?;
+    Ok(buf)
+}
+
+/// JPEG has no alpha channel: composite transparent pixels onto white
+fn flatten(img: &DynamicImage) -> RgbImage {
+    if !img.color().has_alpha() {
+        return img.to_rgb8();
+    }
+
+    let rgba = img.to_rgba8();
+    RgbImage::from_fn(rgba.width(), rgba.height(), |x, y| {
+        let [r, g, b, a] = rgba.get_pixel(x, y).0;
+        let blend = |c: u8| ((c as u32 * a as u32 + 255 * (255 - a as u32)) / 255) as u8;
+        image::Rgb([blend(r), blend(g), blend(b)])
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgba, RgbaImage};
+
+    fn png(width: u32, height: u32) -> Vec<u8> {
+        let img = RgbaImage::from_pixel(width, height, Rgba([10, 20, 30, 0]));
+        let mut buf = Vec::new();
+        DynamicImage::ImageRgba8(img)
+            .write_to(&mut Cursor::new(&mut buf), ImageFormat::Png)
+            .unwrap();
+        buf
+    }
+
+    #[test]
+    fn test_avatar_is_square_jpeg_with_thumbnail() {
+        let processed = process_profile_image(&png(1200, 800), ProfileImageKind::Avatar).unwrap();
+        assert_eq!((processed.width, processed.height), (AVATAR_SIZE, AVATAR_SIZE));
+        assert_eq!(image::guess_format(&processed.full).unwrap(), ImageFormat::Jpeg);
+
+        let thumb = image::load_from_memory(&processed.thumbnail).unwrap();
+        assert_eq!((thumb.width(), thumb.height()), (AVATAR_THUMBNAIL_SIZE, AVATAR_THUMBNAIL_SIZE));
+
+        // Fully transparent pixels are flattened onto white
+        let full = image::load_from_memory(&processed.full).unwrap().to_rgb8();
+        assert!(full.get_pixel(0, 0).0.iter().all(|&c| c > 240));
+    }
+
+    #[test]
+    fn test_background_keeps_aspect_ratio() {
+        let processed =
+            process_profile_image(&png(2400, 600), ProfileImageKind::Background).unwrap();
+        assert_eq!((processed.width, processed.height), (1920, 480));
+    }
+
+    #[test]
+    fn test_rejects_invalid_images() {
+        assert!(process_profile_image(b"GIF89a not really", ProfileImageKind::Avatar).is_err());
+        assert!(process_profile_image(b"plain text", ProfileImageKind::Avatar).is_err());
+        assert!(process_profile_image(&png(32, 32), ProfileImageKind::Avatar).is_err());
+        assert!(process_profile_image(&png(MAX_DIMENSION + 1, 64), ProfileImageKind::Avatar).is_err());
+    }
+}
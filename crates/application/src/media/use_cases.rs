@@ -0,0 +1,240 @@
+use crate::media::dtos::*;
+use crate::media::processing::{process_profile_image, ProfileImageKind};
+use crate::blocks::use_cases::blockers_of;
+use crate::users::use_cases::{contacts_among, project_profile};
+use crate::{AppError, AppResult};
+use chrono::Utc;
+use core::entities::users;
+use infrastructure::storage::BlobStore;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set};
+use tracing::{info, instrument, warn};
+use uuid::Uuid;
+
+// ============ Constants ============
+
+pub const MAX_UPLOAD_BYTES: usize = 10 * 1024 * 1024;
+/// Path under which the API serves stored media
+pub const MEDIA_PATH: &str = "/api/v1/media/";
+
+/// "<dir>/<user_id>/<image_id>[_thumb].jpg"; anything else is never served
+static MEDIA_KEY_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(avatars|backgrounds)/[0-9a-f-]{36}/[0-9a-f-]{36}(_thumb)?\.jpg$").unwrap()
+});
+
+// ============ Upload Profile Image Use Case ============
+
+pub struct UploadProfileImageUseCase;
+
+impl UploadProfileImageUseCase {
+    #[instrument(skip(db, blob_store, media_base_url, data), fields(user_id = %user_id, bytes = data.len()))]
+    pub async fn execute(
+        db: &DatabaseConnection,
+        blob_store: &BlobStore,
+        media_base_url: &str,
+        user_id: Uuid,
+        kind: ProfileImageKind,
+        data: Vec<u8>,
+    ) -> AppResult<UploadProfileImageResponse> {
+        if data.len() > MAX_UPLOAD_BYTES {
+            return Err(AppError::Validation(format!(
+                "Image too large (max {} MB)",
+                MAX_UPLOAD_BYTES / 1024 / 1024
+            )));
+        }
+
+        let user = users::Entity::find_by_id(user_id)
+            .one(db)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("User {} not found", user_id)))?;
+
+        // Decoding and resizing is CPU bound, keep it off the async workers
+        let processed = tokio::task::spawn_blocking(move || process_profile_image(&data, kind))
+            .await
+            .map_err(|e| AppError::Internal(format!("Image processing failed: {}", e)))??;
+
+        let image_id = Uuid::new_v4();
+        let key = format!("{}/{}/{}.jpg", kind.directory(), user_id, image_id);
+        let thumbnail_key = thumbnail_key(&key);
+
+        blob_store
+            .put(&key, processed.full)
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to store image: {}", e)))?;
+        blob_store
+            .put(&thumbnail_key, processed.thumbnail)
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to store image: {}", e)))?;
+
+        let url = media_url(media_base_url, &key);
+        let previous = set_profile_image(db, user, kind, Some(url.clone())).await?;
+        delete_stored_image(blob_store, previous.as_deref()).await;
+
+        info!("Stored {:?} {} for user: {}", kind, key, user_id);
+
+        Ok(UploadProfileImageResponse {
+            url,
+            thumbnail_url: media_url(media_base_url, &thumbnail_key),
+            width: processed.width,
+            height: processed.height,
+        })
+    }
+}
+
+// ============ Remove Profile Image Use Case ============
+
+pub struct RemoveProfileImageUseCase;
+
+impl RemoveProfileImageUseCase {
+    #[instrument(skip(db, blob_store), fields(user_id = %user_id))]
+    pub async fn execute(
+        db: &DatabaseConnection,
+        blob_store: &BlobStore,
+        user_id: Uuid,
+        kind: ProfileImageKind,
+    ) -> AppResult<RemoveProfileImageResponse> {
+        let user = users::Entity::find_by_id(user_id)
+            .one(db)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("User {} not found", user_id)))?;
+
+        let previous = set_profile_image(db, user, kind, None).await?;
+        let removed = previous.is_some();
+        delete_stored_image(blob_store, previous.as_deref()).await;
+
+        Ok(RemoveProfileImageResponse { removed })
+    }
+}
+
+// ============ Get Media Use Case ============
+
+pub struct GetMediaUseCase;
+
+impl GetMediaUseCase {
+    /// Fetch a stored image for `viewer_id`. Only the owner's current profile
+    /// images are served, and only when the owner's field visibility lets the
+    /// viewer see them; replaced or hidden images are reported as missing.
+    #[instrument(skip(db, blob_store), fields(viewer_id = %viewer_id))]
+    pub async fn execute(
+        db: &DatabaseConnection,
+        blob_store: &BlobStore,
+        viewer_id: Uuid,
+        key: &str,
+    ) -> AppResult<Vec<u8>> {
+        let not_found = || AppError::NotFound("Media not found".to_string());
+        if !MEDIA_KEY_REGEX.is_match(key) {
+            return Err(not_found());
+        }
+
+        // "<dir>/<owner_id>/<image_id>[_thumb].jpg"
+        let owner_id = key
+            .split('/')
+            .nth(1)
+            .and_then(|id| Uuid::parse_str(id).ok())
+            .ok_or_else(not_found)?;
+        let image_key = key.replace("_thumb.jpg", ".jpg");
+
+        let owner = users::Entity::find_by_id(owner_id)
+            .filter(users::Column::IsDeleted.eq(false))
+            .filter(users::Column::UserId.not_in_subquery(blockers_of(viewer_id)))
+            .one(db)
+            .await?
+            .ok_or_else(not_found)?;
+
+        let contacts = contacts_among(db, viewer_id, vec![owner_id]).await?;
+        let profile = project_profile(owner, viewer_id, contacts.contains(&owner_id));
+        let visible = [profile.profile_picture_url, profile.background_image_url]
+            .iter()
+            .flatten()
+            .any(|url| media_key(url) == Some(image_key.as_str()));
+        if !visible {
+            return Err(not_found());
+        }
+
+        blob_store
+            .get(key)
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to read media: {}", e)))?
+            .map(|bytes| bytes.to_vec())
+            .ok_or_else(not_found)
+    }
+}
+
+// ============ Helpers ============
+
+pub fn media_url(media_base_url: &str, key: &str) -> String {
+    format!("{}{}{}", media_base_url.trim_end_matches('/'), MEDIA_PATH, key)
+}
+
+/// Blob key of an internal media URL, `None` for anything else
+pub fn media_key(url: &str) -> Option<&str> {
+    url.split_once(MEDIA_PATH)
+        .map(|(_, key)| key)
+        .filter(|key| MEDIA_KEY_REGEX.is_match(key))
+}
+
+/// Keep a stored profile image URL only if it points at our own media.
+/// External URLs saved before uploads existed stay in the row but are never
+/// handed to clients, so they cannot hotlink or track viewers.
+pub fn served_image_url(url: Option<String>) -> Option<String> {
+    url.filter(|url| media_key(url).is_some())
+}
+
+fn thumbnail_key(key: &str) -> String {
+    format!("{}_thumb.jpg", key.trim_end_matches(".jpg"))
+}
+
+/// Write the new URL and return the one it replaced
+async fn set_profile_image(
+    db: &DatabaseConnection,
+    user: users::Model,
+    kind: ProfileImageKind,
+    url: Option<String>,
+) -> AppResult<Option<String>> {
+    let previous = match kind {
+        ProfileImageKind::Avatar => user.profile_picture.clone(),
+        ProfileImageKind::Background => user.background_image.clone(),
+    };
+
+    let mut active_user: users::ActiveModel = user.into();
+    match kind {
+        ProfileImageKind::Avatar => active_user.profile_picture = Set(url),
+        ProfileImageKind::Background => active_user.background_image = Set(url),
+    }
+    active_user.updated_at = Set(Utc::now().into());
+    active_user.update(db).await?;
+
+    Ok(previous)
+}
+
+/// Delete an internal media URL and its thumbnail. Best effort: a failed
+/// delete only leaves an orphaned blob behind.
+pub async fn delete_stored_image(blob_store: &BlobStore, url: Option<&str>) {
+    let Some(key) = url.and_then(media_key) else {
+        return;
+    };
+
+    for key in [key.to_string(), thumbnail_key(key)] {
+        if let Err(e) = blob_store.delete(&key).await {
+            warn!("Failed to delete media {}: {}", key, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_media_urls_round_trip() {
+        let key = format!("avatars/{}/{}.jpg", Uuid::new_v4(), Uuid::new_v4());
+        let url = media_url("https://api.example.com/", &key);
+        assert_eq!(url, format!("https://api.example.com/api/v1/media/{}", key));
+        assert_eq!(media_key(&url), Some(key.as_str()));
+        assert!(MEDIA_KEY_REGEX.is_match(&thumbnail_key(&key)));
+
+        assert_eq!(media_key("https://evil.example.com/pixel.gif"), None);
+        assert_eq!(media_key("https://api.example.com/api/v1/media/../../etc/passwd"), None);
+    }
+}
@@ -0,0 +1,44 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+// ============ Encrypted Profile ============
+
+/// Profile fields encrypted client-side with the user's profile key.
+/// All binary fields are base64 encoded.
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct SetEncryptedProfileRequest {
+    /// Hex-encoded 32-byte version derived from the profile key
+    #[validate(length(min = 64, max = 64, message = "Version must be 64 hex characters"))]
+    pub version: String,
+    /// Commitment to the profile key, lets peers check the key they hold
+    pub commitment: String,
+    pub name: String,
+    #[serde(default)]
+    pub about: Option<String>,
+    #[serde(default)]
+    pub about_emoji: Option<String>,
+    #[serde(default)]
+    pub avatar: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EncryptedProfileResponse {
+    pub user_id: Uuid,
+    pub version: String,
+    pub commitment: String,
+    pub name: String,
+    pub about: Option<String>,
+    pub about_emoji: Option<String>,
+    pub avatar: Option<String>,
+}
+
+/// The owner's view: which version is live and whether the key must be rotated
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EncryptedProfileStatusResponse {
+    pub enabled: bool,
+    pub version: Option<String>,
+    pub rotation_required: bool,
+    pub updated_at: Option<DateTime<Utc>>,
+}
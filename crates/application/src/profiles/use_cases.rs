@@ -0,0 +1,240 @@
+use crate::blocks::use_cases::blockers_of;
+use crate::profiles::dtos::*;
+use crate::{AppError, AppResult};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use chrono::Utc;
+use core::entities::{encrypted_profiles, users};
+use sea_orm::sea_query::{Expr, OnConflict};
+use sea_orm::{
+    ActiveValue::NotSet, ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait,
+    QueryFilter, Set,
+};
+use tracing::{info, instrument};
+use uuid::Uuid;
+use validator::Validate;
+
+// ============ Constants ============
+
+// Ciphertext limits: padded plaintext plus a 12-byte nonce and 16-byte tag
+const MAX_COMMITMENT_BYTES: usize = 256;
+const MAX_NAME_BYTES: usize = 512;
+const MAX_ABOUT_BYTES: usize = 1024;
+const MAX_ABOUT_EMOJI_BYTES: usize = 64;
+const MAX_AVATAR_BYTES: usize = 512;
+
+// ============ Set Encrypted Profile Use Case ============
+
+pub struct SetEncryptedProfileUseCase;
+
+impl SetEncryptedProfileUseCase {
+    #[instrument(skip(db, req), fields(user_id = %user_id))]
+    pub async fn execute(
+        db: &DatabaseConnection,
+        user_id: Uuid,
+        req: SetEncryptedProfileRequest,
+    ) -> AppResult<EncryptedProfileStatusResponse> {
+        req.validate()
+            .map_err(|e| AppError::Validation(e.to_string()))?;
+
+        let version = req.version.to_lowercase();
+        if !version.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(AppError::Validation("Version must be 64 hex characters".to_string()));
+        }
+
+        let commitment = decode_field("commitment", &req.commitment, MAX_COMMITMENT_BYTES)?;
+        let name = decode_field("name", &req.name, MAX_NAME_BYTES)?;
+        let about = decode_optional("about", req.about.as_deref(), MAX_ABOUT_BYTES)?;
+        let about_emoji =
+            decode_optional("about_emoji", req.about_emoji.as_deref(), MAX_ABOUT_EMOJI_BYTES)?;
+        let avatar = decode_optional("avatar", req.avatar.as_deref(), MAX_AVATAR_BYTES)?;
+
+        // After a block the old key may be held by the blocked user, so the
+        // next upload must come with a new key
+        if let Some(existing) = encrypted_profiles::Entity::find_by_id(user_id).one(db).await? {
+            if existing.rotation_required && existing.version == version {
+                return Err(AppError::Validation(
+                    "Profile key must be rotated before updating the profile".to_string(),
+                ));
+            }
+        }
+
+        let now = Utc::now();
+        let profile = encrypted_profiles::ActiveModel {
+            user_id: Set(user_id),
+            version: Set(version.clone()),
+            commitment: Set(commitment),
+            name: Set(name),
+            about: Set(about),
+            about_emoji: Set(about_emoji),
+            avatar: Set(avatar),
+            rotation_required: Set(false),
+            created_at: NotSet,
+            updated_at: Set(now.into()),
+        };
+
+        encrypted_profiles::Entity::insert(profile)
+            .on_conflict(
+                OnConflict::column(encrypted_profiles::Column::UserId)
+                    .update_columns([
+                        encrypted_profiles::Column::Version,
+                        encrypted_profiles::Column::Commitment,
+                        encrypted_profiles::Column::Name,
+                        encrypted_profiles::Column::About,
+                        encrypted_profiles::Column::AboutEmoji,
+                        encrypted_profiles::Column::Avatar,
+                        encrypted_profiles::Column::RotationRequired,
+                        encrypted_profiles::Column::UpdatedAt,
+                    ])
+                    .to_owned(),
+            )
+            .exec(db)
+            .await?;
+
+        info!("Encrypted profile stored for user: {}", user_id);
+
+        Ok(EncryptedProfileStatusResponse {
+            enabled: true,
+            version: Some(version),
+            rotation_required: false,
+            updated_at: Some(now),
+        })
+    }
+}
+
+// ============ Get Encrypted Profile Use Case ============
+
+pub struct GetEncryptedProfileUseCase;
+
+impl GetEncryptedProfileUseCase {
+    /// Peers prove they hold the profile key by presenting the version derived from it
+    #[instrument(skip(db, version), fields(viewer_id = %viewer_id, user_id = %user_id))]
+    pub async fn execute(
+        db: &DatabaseConnection,
+        viewer_id: Uuid,
+        user_id: Uuid,
+        version: &str,
+    ) -> AppResult<EncryptedProfileResponse> {
+        // Wrong versions and blocked viewers are indistinguishable from users
+        // without an encrypted profile
+        let profile = encrypted_profiles::Entity::find_by_id(user_id)
+            .filter(encrypted_profiles::Column::Version.eq(version.to_lowercase()))
+            .inner_join(users::Entity)
+            .filter(users::Column::IsDeleted.eq(false))
+            .filter(users::Column::UserId.not_in_subquery(blockers_of(viewer_id)))
+            .one(db)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Profile not found".to_string()))?;
+
+        Ok(EncryptedProfileResponse {
+            user_id: profile.user_id,
+            version: profile.version,
+            commitment: BASE64.encode(&profile.commitment),
+            name: BASE64.encode(&profile.name),
+            about: profile.about.map(|v| BASE64.encode(v)),
+            about_emoji: profile.about_emoji.map(|v| BASE64.encode(v)),
+            avatar: profile.avatar.map(|v| BASE64.encode(v)),
+        })
+    }
+}
+
+// ============ Get Encrypted Profile Status Use Case ============
+
+pub struct GetEncryptedProfileStatusUseCase;
+
+impl GetEncryptedProfileStatusUseCase {
+    #[instrument(skip(db), fields(user_id = %user_id))]
+    pub async fn execute(
+        db: &DatabaseConnection,
+        user_id: Uuid,
+    ) -> AppResult<EncryptedProfileStatusResponse> {
+        let profile = encrypted_profiles::Entity::find_by_id(user_id).one(db).await?;
+
+        Ok(match profile {
+            Some(p) => EncryptedProfileStatusResponse {
+                enabled: true,
+                version: Some(p.version),
+                rotation_required: p.rotation_required,
+                updated_at: Some(p.updated_at.into()),
+            },
+            None => EncryptedProfileStatusResponse {
+                enabled: false,
+                version: None,
+                rotation_required: false,
+                updated_at: None,
+            },
+        })
+    }
+}
+
+// ============ Delete Encrypted Profile Use Case ============
+
+pub struct DeleteEncryptedProfileUseCase;
+
+impl DeleteEncryptedProfileUseCase {
+    #[instrument(skip(db), fields(user_id = %user_id))]
+    pub async fn execute(
+        db: &DatabaseConnection,
+        user_id: Uuid,
+    ) -> AppResult<EncryptedProfileStatusResponse> {
+        encrypted_profiles::Entity::delete_by_id(user_id)
+            .exec(db)
+            .await?;
+
+        Ok(EncryptedProfileStatusResponse {
+            enabled: false,
+            version: None,
+            rotation_required: false,
+            updated_at: None,
+        })
+    }
+}
+
+// ============ Helpers ============
+
+/// Flag the user's profile key for rotation. Returns whether the user has an
+/// encrypted profile, i.e. whether their devices need to be told.
+pub async fn require_profile_key_rotation<C: ConnectionTrait>(
+    db: &C,
+    user_id: Uuid,
+) -> AppResult<bool> {
+    let result = encrypted_profiles::Entity::update_many()
+        .col_expr(encrypted_profiles::Column::RotationRequired, Expr::value(true))
+        .filter(encrypted_profiles::Column::UserId.eq(user_id))
+        .exec(db)
+        .await?;
+
+    Ok(result.rows_affected > 0)
+}
+
+fn decode_field(field: &str, value: &str, max_bytes: usize) -> AppResult<Vec<u8>> {
+    let bytes = BASE64
+        .decode(value)
+        .map_err(|_| AppError::Validation(format!("{} must be base64 encoded", field)))?;
+
+    if bytes.is_empty() || bytes.len() > max_bytes {
+        return Err(AppError::Validation(format!(
+            "{} must be between 1-{} bytes",
+            field, max_bytes
+        )));
+    }
+
+    Ok(bytes)
+}
+
+fn decode_optional(field: &str, value: Option<&str>, max_bytes: usize) -> AppResult<Option<Vec<u8>>> {
+    value.map(|v| decode_field(field, v, max_bytes)).transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_field_limits() {
+        assert_eq!(decode_field("name", &BASE64.encode([1u8; 4]), 8).unwrap(), vec![1u8; 4]);
+        assert!(decode_field("name", "not base64!", 8).is_err());
+        assert!(decode_field("name", "", 8).is_err());
+        assert!(decode_field("name", &BASE64.encode([1u8; 9]), 8).is_err());
+        assert_eq!(decode_optional("about", None, 8).unwrap(), None);
+    }
+}
@@ -0,0 +1,55 @@
+use crate::{AppError, AppResult};
+use redis::aio::MultiplexedConnection;
+use redis::AsyncCommands;
+
+/// Fixed-window counter stored in Redis.
+///
+/// Increments `key` and fails with `RateLimitExceeded` once more than
+/// `max_requests` hits land inside the `window_seconds` window. The window
+/// starts on the first hit.
+pub async fn check_rate_limit(
+    redis_conn: &mut MultiplexedConnection,
+    key: &str,
+    max_requests: u32,
+    window_seconds: i64,
+    message: &str,
+) -> AppResult<()> {
+    let count: u32 = redis_conn.incr(key, 1).await?;
+    if count == 1 {
+        redis_conn.expire::<_, ()>(key, window_seconds).await?;
+    }
+
+    if count > max_requests {
+        return Err(AppError::RateLimitExceeded(message.to_string()));
+    }
+
+    Ok(())
+}
+
+/// Limit the number of distinct values seen under `key` per window.
+///
+/// Adds `members` to a HyperLogLog and fails with `RateLimitExceeded` once its
+/// cardinality exceeds `max_distinct`. Resubmitting values already counted is
+/// free, so a client re-syncing the same data does not burn its allowance.
+/// Returns the remaining allowance. The window starts on the first hit.
+pub async fn check_cardinality_limit(
+    redis_conn: &mut MultiplexedConnection,
+    key: &str,
+    members: &[String],
+    max_distinct: u32,
+    window_seconds: i64,
+    message: &str,
+) -> AppResult<u32> {
+    redis_conn.pfadd::<_, _, ()>(key, members).await?;
+    let ttl: i64 = redis_conn.ttl(key).await?;
+    if ttl < 0 {
+        redis_conn.expire::<_, ()>(key, window_seconds).await?;
+    }
+
+    let count: u32 = redis_conn.pfcount(key).await?;
+    if count > max_distinct {
+        return Err(AppError::RateLimitExceeded(message.to_string()));
+    }
+
+    Ok(max_distinct - count)
+}
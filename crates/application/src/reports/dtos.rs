@@ -0,0 +1,119 @@
+use chrono::{DateTime, Utc};
+use core::entities::moderation_actions::ModerationActionType;
+use core::entities::reports::{ReportReason, ReportStatus};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+// ============ Reports ============
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct CreateReportRequest {
+    /// The account being reported. For a personal conversation it defaults to the other member.
+    pub reported_user_id: Option<Uuid>,
+    pub conversation_id: Option<Uuid>,
+    pub reason: ReportReason,
+    #[validate(length(max = 1000, message = "Comment must be at most 1000 characters"))]
+    pub comment: Option<String>,
+    /// Decrypted messages the reporter chooses to forward; the server cannot read them otherwise
+    #[serde(default)]
+    #[validate(length(max = 50, message = "At most 50 messages can be attached"))]
+    pub messages: Vec<ReportedMessage>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReportedMessage {
+    pub message_id: Option<i64>,
+    pub sender_id: Uuid,
+    pub sent_at: DateTime<Utc>,
+    pub body: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateReportResponse {
+    pub report_id: Uuid,
+    pub created_at: DateTime<Utc>,
+}
+
+// ============ Moderation Queue ============
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct ListReportsQuery {
+    #[serde(default)]
+    pub status: Option<ReportStatus>,
+    #[serde(default)]
+    #[validate(range(min = 1, max = 200, message = "Limit must be between 1-200"))]
+    pub limit: Option<u64>,
+    #[serde(default)]
+    pub offset: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReportSummary {
+    pub report_id: Uuid,
+    pub reporter_id: Option<Uuid>,
+    pub reported_user_id: Option<Uuid>,
+    pub conversation_id: Option<Uuid>,
+    pub reason: ReportReason,
+    pub status: ReportStatus,
+    pub message_count: usize,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListReportsResponse {
+    pub reports: Vec<ReportSummary>,
+    pub total: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReportDetail {
+    #[serde(flatten)]
+    pub summary: ReportSummary,
+    pub comment: Option<String>,
+    pub messages: Vec<ReportedMessage>,
+    pub resolved_by: Option<Uuid>,
+    pub resolved_at: Option<DateTime<Utc>>,
+    pub resolution_note: Option<String>,
+    /// Open reports against the same account, this one included
+    pub open_reports_against_user: u64,
+    pub moderation_history: Vec<ModerationAction>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ModerationAction {
+    pub action_id: Uuid,
+    pub user_id: Uuid,
+    pub action: ModerationActionType,
+    pub reason: Option<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub admin_id: Option<Uuid>,
+    pub report_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportResolution {
+    Warn,
+    Suspend,
+    Ban,
+    Dismiss,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct ResolveReportRequest {
+    pub action: ReportResolution,
+    #[validate(length(max = 1000, message = "Note must be at most 1000 characters"))]
+    pub note: Option<String>,
+    /// Required for `suspend`
+    pub suspend_seconds: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ResolveReportResponse {
+    pub report_id: Uuid,
+    pub status: ReportStatus,
+    pub action: Option<ModerationAction>,
+    pub revoked_devices: u64,
+}
@@ -0,0 +1,418 @@
+use crate::audit::{self, AuditEvent};
+use crate::rate_limit::check_rate_limit;
+use crate::reports::dtos::*;
+use crate::{AppError, AppResult};
+use chrono::{Duration, Utc};
+use core::entities::moderation_actions::{self, ModerationActionType};
+use core::entities::reports::{self, ReportStatus};
+use core::entities::{conv_members, devices, users};
+use redis::aio::MultiplexedConnection;
+use sea_orm::sea_query::Expr;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait,
+    PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, Set, TransactionTrait,
+};
+use tracing::{info, instrument};
+use uuid::Uuid;
+use validator::Validate;
+
+// ============ Constants ============
+
+const REPORT_MAX_MESSAGE_CHARS: usize = 4096;
+const REPORTS_PER_DAY: u32 = 20;
+const REPORTS_WINDOW_SECONDS: i64 = 86400;
+const LIST_REPORTS_DEFAULT_LIMIT: u64 = 50;
+const MAX_SUSPENSION_SECONDS: i64 = 365 * 86400;
+
+// ============ Create Report Use Case ============
+
+pub struct CreateReportUseCase;
+
+impl CreateReportUseCase {
+    #[instrument(skip(db, redis_conn, req), fields(reporter_id = %reporter_id))]
+    pub async fn execute(
+        db: &DatabaseConnection,
+        redis_conn: &mut MultiplexedConnection,
+        reporter_id: Uuid,
+        req: CreateReportRequest,
+    ) -> AppResult<CreateReportResponse> {
+        req.validate()
+            .map_err(|e| AppError::Validation(e.to_string()))?;
+
+        if req.reported_user_id.is_none() && req.conversation_id.is_none() {
+            return Err(AppError::Validation(
+                "A report must name an account or a conversation".to_string(),
+            ));
+        }
+        if req.reported_user_id == Some(reporter_id) {
+            return Err(AppError::Validation("You cannot report yourself".to_string()));
+        }
+        if req.messages.iter().any(|m| m.body.chars().count() > REPORT_MAX_MESSAGE_CHARS) {
+            return Err(AppError::Validation(format!(
+                "Each attached message must be at most {} characters",
+                REPORT_MAX_MESSAGE_CHARS
+            )));
+        }
+
+        check_rate_limit(
+            redis_conn,
+            &format!("reports:{}", reporter_id),
+            REPORTS_PER_DAY,
+            REPORTS_WINDOW_SECONDS,
+            "Too many reports. Please try again later.",
+        )
+        .await?;
+
+        let mut reported_user_id = req.reported_user_id;
+        if let Some(conv_id) = req.conversation_id {
+            let members: Vec<Uuid> = conv_members::Entity::find()
+                .select_only()
+                .column(conv_members::Column::UserId)
+                .filter(conv_members::Column::ConvId.eq(conv_id))
+                .into_tuple()
+                .all(db)
+                .await?;
+
+            // Former members can still report what they were sent
+            if !members.contains(&reporter_id) {
+                return Err(AppError::NotFound("Conversation not found".to_string()));
+            }
+
+            if reported_user_id.is_none() {
+                let others: Vec<Uuid> = members.into_iter().filter(|id| *id != reporter_id).collect();
+                if let [other] = others[..] {
+                    reported_user_id = Some(other);
+                }
+            }
+        }
+
+        if let Some(user_id) = reported_user_id {
+            users::Entity::find_by_id(user_id)
+                .filter(users::Column::PurgedAt.is_null())
+                .one(db)
+                .await?
+                .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+        }
+
+        let evidence = serde_json::to_value(&req.messages)
+            .map_err(|e| AppError::Internal(format!("Failed to encode report: {}", e)))?;
+
+        let report = reports::ActiveModel {
+            report_id: Set(Uuid::new_v4()),
+            reporter_id: Set(Some(reporter_id)),
+            reported_user_id: Set(reported_user_id),
+            conversation_id: Set(req.conversation_id),
+            reason: Set(req.reason.into()),
+            comment: Set(req.comment),
+            evidence: Set(evidence),
+            status: Set(ReportStatus::Open.into()),
+            resolved_by: Set(None),
+            resolved_at: Set(None),
+            resolution_note: Set(None),
+            created_at: Set(Utc::now().into()),
+        }
+        .insert(db)
+        .await?;
+
+        info!("Report {} filed by {}", report.report_id, reporter_id);
+
+        Ok(CreateReportResponse {
+            report_id: report.report_id,
+            created_at: report.created_at.into(),
+        })
+    }
+}
+
+// ============ List Reports Use Case ============
+
+pub struct ListReportsUseCase;
+
+impl ListReportsUseCase {
+    /// The moderation queue, oldest first
+    #[instrument(skip(db))]
+    pub async fn execute(
+        db: &DatabaseConnection,
+        query: ListReportsQuery,
+    ) -> AppResult<ListReportsResponse> {
+        query
+            .validate()
+            .map_err(|e| AppError::Validation(e.to_string()))?;
+
+        let mut select = reports::Entity::find();
+        if let Some(status) = query.status {
+            select = select.filter(reports::Column::Status.eq(i16::from(status)));
+        }
+
+        let total = select.clone().count(db).await?;
+        let reports = select
+            .order_by_asc(reports::Column::CreatedAt)
+            .offset(query.offset.unwrap_or(0))
+            .limit(query.limit.unwrap_or(LIST_REPORTS_DEFAULT_LIMIT))
+            .all(db)
+            .await?
+            .into_iter()
+            .map(|report| summarize(&report))
+            .collect();
+
+        Ok(ListReportsResponse { reports, total })
+    }
+}
+
+// ============ Get Report Use Case ============
+
+pub struct GetReportUseCase;
+
+impl GetReportUseCase {
+    /// Reports can carry forwarded message text, so every view is audited
+    #[instrument(skip(db), fields(admin_id = %admin_id, report_id = %report_id))]
+    pub async fn execute(
+        db: &DatabaseConnection,
+        admin_id: Uuid,
+        report_id: Uuid,
+    ) -> AppResult<ReportDetail> {
+        let report = find_report(db, report_id).await?;
+
+        audit::record(
+            db,
+            AuditEvent::by_admin(admin_id, report.reported_user_id, audit::REPORT_VIEWED)
+                .with_details(serde_json::json!({ "report_id": report_id })),
+        )
+        .await?;
+
+        let (open_reports_against_user, moderation_history) = match report.reported_user_id {
+            Some(user_id) => {
+                let open = reports::Entity::find()
+                    .filter(reports::Column::ReportedUserId.eq(user_id))
+                    .filter(reports::Column::Status.eq(i16::from(ReportStatus::Open)))
+                    .count(db)
+                    .await?;
+                let history = moderation_actions::Entity::find()
+                    .filter(moderation_actions::Column::UserId.eq(user_id))
+                    .order_by_desc(moderation_actions::Column::CreatedAt)
+                    .all(db)
+                    .await?
+                    .into_iter()
+                    .map(ModerationAction::from)
+                    .collect();
+                (open, history)
+            }
+            None => (0, Vec::new()),
+        };
+
+        Ok(ReportDetail {
+            summary: summarize(&report),
+            comment: report.comment,
+            messages: serde_json::from_value(report.evidence).unwrap_or_default(),
+            resolved_by: report.resolved_by,
+            resolved_at: report.resolved_at.map(Into::into),
+            resolution_note: report.resolution_note,
+            open_reports_against_user,
+            moderation_history,
+        })
+    }
+}
+
+// ============ Resolve Report Use Case ============
+
+pub struct ResolveReportUseCase;
+
+impl ResolveReportUseCase {
+    /// Close an open report, acting against the reported account unless it is dismissed
+    #[instrument(skip(db, req), fields(admin_id = %admin_id, report_id = %report_id))]
+    pub async fn execute(
+        db: &DatabaseConnection,
+        admin_id: Uuid,
+        report_id: Uuid,
+        req: ResolveReportRequest,
+    ) -> AppResult<ResolveReportResponse> {
+        req.validate()
+            .map_err(|e| AppError::Validation(e.to_string()))?;
+
+        let report = find_report(db, report_id).await?;
+        if report.report_status() != ReportStatus::Open {
+            return Err(AppError::Validation("Report has already been resolved".to_string()));
+        }
+
+        let action_type = match req.action {
+            ReportResolution::Warn => Some(ModerationActionType::Warn),
+            ReportResolution::Suspend => Some(ModerationActionType::Suspend),
+            ReportResolution::Ban => Some(ModerationActionType::Ban),
+            ReportResolution::Dismiss => None,
+        };
+
+        let txn = db.begin().await?;
+
+        let (status, action, revoked_devices) = match action_type {
+            Some(action_type) => {
+                let user_id = report.reported_user_id.ok_or_else(|| {
+                    AppError::Validation("Report does not name an account to act on".to_string())
+                })?;
+                let (action, revoked) = apply_moderation_action(
+                    &txn,
+                    admin_id,
+                    user_id,
+                    Some(report_id),
+                    action_type,
+                    req.note.clone(),
+                    req.suspend_seconds,
+                )
+                .await?;
+                (ReportStatus::Actioned, Some(action), revoked)
+            }
+            None => {
+                audit::record(
+                    &txn,
+                    AuditEvent::by_admin(admin_id, report.reported_user_id, audit::REPORT_DISMISSED)
+                        .with_details(serde_json::json!({ "report_id": report_id })),
+                )
+                .await?;
+                (ReportStatus::Dismissed, None, 0)
+            }
+        };
+
+        let mut active_report: reports::ActiveModel = report.into();
+        active_report.status = Set(status.into());
+        active_report.resolved_by = Set(Some(admin_id));
+        active_report.resolved_at = Set(Some(Utc::now().into()));
+        active_report.resolution_note = Set(req.note);
+        active_report.update(&txn).await?;
+
+        txn.commit().await?;
+
+        info!("Admin {} resolved report {} as {:?}", admin_id, report_id, req.action);
+
+        Ok(ResolveReportResponse {
+            report_id,
+            status,
+            action,
+            revoked_devices,
+        })
+    }
+}
+
+// ============ Helpers ============
+
+/// Record a moderation action and apply it to the account. Suspensions and
+/// bans revoke every device, so refresh tokens stop working at once and
+/// logging back in is refused until the action lapses. Returns the action
+/// and the number of devices revoked.
+pub async fn apply_moderation_action<C: ConnectionTrait>(
+    db: &C,
+    admin_id: Uuid,
+    user_id: Uuid,
+    report_id: Option<Uuid>,
+    action_type: ModerationActionType,
+    reason: Option<String>,
+    suspend_seconds: Option<i64>,
+) -> AppResult<(ModerationAction, u64)> {
+    let user = users::Entity::find_by_id(user_id)
+        .filter(users::Column::PurgedAt.is_null())
+        .one(db)
+        .await?
+        .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+
+    let now = Utc::now();
+    let expires_at = match action_type {
+        ModerationActionType::Suspend => {
+            let seconds = suspend_seconds
+                .filter(|s| (1..=MAX_SUSPENSION_SECONDS).contains(s))
+                .ok_or_else(|| {
+                    AppError::Validation(format!(
+                        "suspend_seconds must be between 1-{}",
+                        MAX_SUSPENSION_SECONDS
+                    ))
+                })?;
+            Some(now + Duration::seconds(seconds))
+        }
+        _ => None,
+    };
+
+    let audit_action = match action_type {
+        ModerationActionType::Warn => audit::ACCOUNT_WARNED,
+        ModerationActionType::Suspend => audit::ACCOUNT_SUSPENDED,
+        ModerationActionType::Ban => audit::ACCOUNT_BANNED,
+    };
+
+    if action_type != ModerationActionType::Warn {
+        let mut active_user: users::ActiveModel = user.into();
+        match action_type {
+            ModerationActionType::Suspend => active_user.suspended_until = Set(expires_at.map(Into::into)),
+            _ => active_user.banned_at = Set(Some(now.into())),
+        }
+        active_user.updated_at = Set(now.into());
+        active_user.update(db).await?;
+    }
+
+    let revoked_devices = if action_type == ModerationActionType::Warn {
+        0
+    } else {
+        devices::Entity::update_many()
+            .col_expr(devices::Column::IsActive, Expr::value(false))
+            .filter(devices::Column::UserId.eq(user_id))
+            .filter(devices::Column::IsActive.eq(true))
+            .exec(db)
+            .await?
+            .rows_affected
+    };
+
+    let action = moderation_actions::ActiveModel {
+        action_id: Set(Uuid::new_v4()),
+        user_id: Set(user_id),
+        admin_id: Set(Some(admin_id)),
+        report_id: Set(report_id),
+        action: Set(action_type.into()),
+        reason: Set(reason),
+        expires_at: Set(expires_at.map(Into::into)),
+        created_at: Set(now.into()),
+    }
+    .insert(db)
+    .await?;
+
+    audit::record(
+        db,
+        AuditEvent::by_admin(admin_id, Some(user_id), audit_action).with_details(serde_json::json!({
+            "action_id": action.action_id,
+            "report_id": report_id,
+            "expires_at": expires_at,
+            "revoked_devices": revoked_devices,
+        })),
+    )
+    .await?;
+
+    Ok((ModerationAction::from(action), revoked_devices))
+}
+
+async fn find_report(db: &DatabaseConnection, report_id: Uuid) -> AppResult<reports::Model> {
+    reports::Entity::find_by_id(report_id)
+        .one(db)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Report not found".to_string()))
+}
+
+fn summarize(report: &reports::Model) -> ReportSummary {
+    ReportSummary {
+        report_id: report.report_id,
+        reporter_id: report.reporter_id,
+        reported_user_id: report.reported_user_id,
+        conversation_id: report.conversation_id,
+        reason: report.reason(),
+        status: report.report_status(),
+        message_count: report.evidence.as_array().map_or(0, |m| m.len()),
+        created_at: report.created_at.into(),
+    }
+}
+
+impl From<moderation_actions::Model> for ModerationAction {
+    fn from(action: moderation_actions::Model) -> Self {
+        Self {
+            action_id: action.action_id,
+            user_id: action.user_id,
+            action: action.action_type(),
+            reason: action.reason,
+            expires_at: action.expires_at.map(Into::into),
+            admin_id: action.admin_id,
+            report_id: action.report_id,
+            created_at: action.created_at.into(),
+        }
+    }
+}
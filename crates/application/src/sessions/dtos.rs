@@ -0,0 +1,100 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+// ============ Session Records ============
+
+/// A stored session without its record, for listing
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SessionSummary {
+    pub address: String,
+    pub version: i64,
+    pub size_bytes: usize,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListSessionsResponse {
+    pub sessions: Vec<SessionSummary>,
+    pub total: usize,
+    pub stored_bytes: usize,
+    pub max_sessions: u64,
+    pub max_stored_bytes: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SessionRecordResponse {
+    pub address: String,
+    /// Base64 encoded, opaque to the server
+    pub record: String,
+    pub version: i64,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct PutSessionRequest {
+    /// Base64 encoded session record
+    pub record: String,
+    /// The version last read. Omit it to create a record that must not exist yet.
+    #[serde(default)]
+    #[validate(range(min = 1, message = "Expected version must be positive"))]
+    pub expected_version: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PutSessionResponse {
+    pub address: String,
+    pub version: i64,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DeleteSessionQuery {
+    /// Only delete if the stored record is still at this version
+    pub version: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DeleteSessionResponse {
+    pub deleted: bool,
+}
+
+// ============ Export / Import ============
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct ExportSessionsQuery {
+    #[serde(default)]
+    #[validate(range(min = 1, max = 50, message = "Limit must be between 1-50"))]
+    pub limit: Option<u64>,
+    /// Only records with a greater address, for paging forwards
+    pub after: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExportSessionsResponse {
+    pub sessions: Vec<SessionRecordResponse>,
+    /// Pass as `after` to fetch the next page; absent on the last page
+    pub next_after: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ImportedSession {
+    pub address: String,
+    /// Base64 encoded session record
+    pub record: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct ImportSessionsRequest {
+    #[validate(length(min = 1, max = 50, message = "Import between 1-50 sessions at a time"))]
+    pub sessions: Vec<ImportedSession>,
+    /// Replace records that already exist instead of rejecting the batch
+    #[serde(default)]
+    pub overwrite: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ImportSessionsResponse {
+    pub created: usize,
+    pub replaced: usize,
+}
@@ -0,0 +1,477 @@
+use crate::sessions::dtos::*;
+use crate::{AppError, AppResult};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use chrono::Utc;
+use core::entities::{devices, signal_sessions};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use sea_orm::sea_query::Expr;
+use sea_orm::{
+    ColumnTrait, ConnectionTrait, DatabaseConnection, DatabaseTransaction, DbBackend, EntityTrait,
+    QueryFilter, QueryOrder, QuerySelect, Select, Set, Statement, TransactionTrait,
+};
+use std::collections::HashSet;
+use tracing::{info, instrument};
+use uuid::Uuid;
+use validator::Validate;
+
+// ============ Constants ============
+
+/// Most session records a device may store at once
+pub const MAX_SESSIONS_PER_DEVICE: u64 = 2000;
+pub const MAX_SESSION_RECORD_BYTES: usize = 64 * 1024;
+/// Total size of all of a device's session records
+pub const MAX_STORED_SESSION_BYTES: usize = 16 * 1024 * 1024;
+const EXPORT_DEFAULT_LIMIT: u64 = 50;
+
+/// A Signal protocol address, "<name>.<device_id>"
+static SESSION_ADDRESS_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^[A-Za-z0-9_:+-]{1,100}\.[0-9]{1,10}$").unwrap());
+
+// ============ List Sessions Use Case ============
+
+pub struct ListSessionsUseCase;
+
+impl ListSessionsUseCase {
+    /// Every session record stored for the caller's device, without the records
+    #[instrument(skip(db), fields(user_id = %user_id, device_id = device_id))]
+    pub async fn execute(
+        db: &DatabaseConnection,
+        user_id: Uuid,
+        device_id: i64,
+    ) -> AppResult<ListSessionsResponse> {
+        ensure_device(db, user_id, device_id).await?;
+
+        let stmt = Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            r#"SELECT address, version, octet_length(session_record)::bigint AS size_bytes, updated_at
+               FROM signal_sessions
+               WHERE device_id = $1
+               ORDER BY address"#,
+            [device_id.into()],
+        );
+
+        let mut stored_bytes = 0;
+        let mut sessions = Vec::new();
+        for row in db.query_all(stmt).await? {
+            let size_bytes = row.try_get::<i64>("", "size_bytes")? as usize;
+            stored_bytes += size_bytes;
+            sessions.push(SessionSummary {
+                address: row.try_get("", "address")?,
+                version: row.try_get("", "version")?,
+                size_bytes,
+                updated_at: row
+                    .try_get::<chrono::DateTime<chrono::FixedOffset>>("", "updated_at")?
+                    .into(),
+            });
+        }
+
+        Ok(ListSessionsResponse {
+            total: sessions.len(),
+            sessions,
+            stored_bytes,
+            max_sessions: MAX_SESSIONS_PER_DEVICE,
+            max_stored_bytes: MAX_STORED_SESSION_BYTES,
+        })
+    }
+}
+
+// ============ Get Session Use Case ============
+
+pub struct GetSessionUseCase;
+
+impl GetSessionUseCase {
+    #[instrument(skip(db), fields(user_id = %user_id, device_id = device_id))]
+    pub async fn execute(
+        db: &DatabaseConnection,
+        user_id: Uuid,
+        device_id: i64,
+        address: String,
+    ) -> AppResult<SessionRecordResponse> {
+        validate_address(&address)?;
+        ensure_device(db, user_id, device_id).await?;
+
+        signal_sessions::Entity::find_by_id((device_id, address))
+            .one(db)
+            .await?
+            .map(to_record_response)
+            .ok_or_else(|| AppError::NotFound("Session not found".to_string()))
+    }
+}
+
+// ============ Put Session Use Case ============
+
+pub struct PutSessionUseCase;
+
+impl PutSessionUseCase {
+    /// Create or update one session record. Without an expected version the
+    /// record must not exist yet; with one, it must still be at that version.
+    #[instrument(skip(db, req), fields(user_id = %user_id, device_id = device_id))]
+    pub async fn execute(
+        db: &DatabaseConnection,
+        user_id: Uuid,
+        device_id: i64,
+        address: String,
+        req: PutSessionRequest,
+    ) -> AppResult<PutSessionResponse> {
+        req.validate()
+            .map_err(|e| AppError::Validation(e.to_string()))?;
+        validate_address(&address)?;
+        let record = decode_record(&req.record)?;
+
+        let txn = db.begin().await?;
+        lock_device(&txn, user_id, device_id).await?;
+
+        let existing = signal_sessions::Entity::find_by_id((device_id, address.clone()))
+            .one(&txn)
+            .await?;
+        let (count, stored_bytes) = session_usage(&txn, device_id).await?;
+        let now = Utc::now();
+
+        let session = match (existing, req.expected_version) {
+            (Some(existing), None) => {
+                return Err(AppError::Conflict(format!(
+                    "Session already exists at version {}",
+                    existing.version
+                )));
+            }
+            (None, Some(_)) => {
+                return Err(AppError::Conflict("Session no longer exists".to_string()));
+            }
+            (Some(existing), Some(expected)) if existing.version != expected => {
+                return Err(AppError::Conflict(format!(
+                    "Session is at version {}, not {}",
+                    existing.version, expected
+                )));
+            }
+            (Some(existing), Some(_)) => {
+                check_quota(count, stored_bytes - existing.session_record.len() + record.len())?;
+
+                let mut updated = signal_sessions::Entity::update_many()
+                    .col_expr(signal_sessions::Column::SessionRecord, Expr::value(record))
+                    .col_expr(signal_sessions::Column::Version, Expr::value(existing.version + 1))
+                    .col_expr(signal_sessions::Column::UpdatedAt, Expr::value(now))
+                    .filter(signal_sessions::Column::DeviceId.eq(device_id))
+                    .filter(signal_sessions::Column::Address.eq(address.as_str()))
+                    .exec_with_returning(&txn)
+                    .await?;
+                updated
+                    .pop()
+                    .ok_or_else(|| AppError::Internal("Session was not stored".to_string()))?
+            }
+            (None, None) => {
+                check_quota(count + 1, stored_bytes + record.len())?;
+
+                signal_sessions::Entity::insert(signal_sessions::ActiveModel {
+                    device_id: Set(device_id),
+                    address: Set(address.clone()),
+                    session_record: Set(record),
+                    version: Set(1),
+                    updated_at: Set(now.into()),
+                })
+                .exec_with_returning(&txn)
+                .await?
+            }
+        };
+
+        txn.commit().await?;
+
+        Ok(PutSessionResponse {
+            address: session.address,
+            version: session.version,
+            updated_at: session.updated_at.into(),
+        })
+    }
+}
+
+// ============ Delete Session Use Case ============
+
+pub struct DeleteSessionUseCase;
+
+impl DeleteSessionUseCase {
+    #[instrument(skip(db), fields(user_id = %user_id, device_id = device_id))]
+    pub async fn execute(
+        db: &DatabaseConnection,
+        user_id: Uuid,
+        device_id: i64,
+        address: String,
+        query: DeleteSessionQuery,
+    ) -> AppResult<DeleteSessionResponse> {
+        validate_address(&address)?;
+        ensure_device(db, user_id, device_id).await?;
+
+        let mut delete = signal_sessions::Entity::delete_many()
+            .filter(signal_sessions::Column::DeviceId.eq(device_id))
+            .filter(signal_sessions::Column::Address.eq(address.as_str()));
+        if let Some(version) = query.version {
+            delete = delete.filter(signal_sessions::Column::Version.eq(version));
+        }
+
+        if delete.exec(db).await?.rows_affected > 0 {
+            return Ok(DeleteSessionResponse { deleted: true });
+        }
+
+        // Tell a stale version apart from a record that is already gone
+        match signal_sessions::Entity::find_by_id((device_id, address)).one(db).await? {
+            Some(existing) => Err(AppError::Conflict(format!(
+                "Session is at version {}, not {}",
+                existing.version,
+                query.version.unwrap_or_default()
+            ))),
+            None => Err(AppError::NotFound("Session not found".to_string())),
+        }
+    }
+}
+
+// ============ Export Sessions Use Case ============
+
+pub struct ExportSessionsUseCase;
+
+impl ExportSessionsUseCase {
+    /// One page of the caller's session records, ordered by address
+    #[instrument(skip(db, query), fields(user_id = %user_id, device_id = device_id))]
+    pub async fn execute(
+        db: &DatabaseConnection,
+        user_id: Uuid,
+        device_id: i64,
+        query: ExportSessionsQuery,
+    ) -> AppResult<ExportSessionsResponse> {
+        query
+            .validate()
+            .map_err(|e| AppError::Validation(e.to_string()))?;
+        ensure_device(db, user_id, device_id).await?;
+        let limit = query.limit.unwrap_or(EXPORT_DEFAULT_LIMIT);
+
+        let mut select =
+            signal_sessions::Entity::find().filter(signal_sessions::Column::DeviceId.eq(device_id));
+        if let Some(after) = query.after {
+            select = select.filter(signal_sessions::Column::Address.gt(after));
+        }
+
+        // One extra row tells whether another page follows
+        let mut sessions = select
+            .order_by_asc(signal_sessions::Column::Address)
+            .limit(limit + 1)
+            .all(db)
+            .await?;
+        let next_after = if sessions.len() as u64 > limit {
+            sessions.truncate(limit as usize);
+            sessions.last().map(|s| s.address.clone())
+        } else {
+            None
+        };
+
+        Ok(ExportSessionsResponse {
+            sessions: sessions.into_iter().map(to_record_response).collect(),
+            next_after,
+        })
+    }
+}
+
+// ============ Import Sessions Use Case ============
+
+pub struct ImportSessionsUseCase;
+
+impl ImportSessionsUseCase {
+    /// Store a batch of session records exported from another device. The
+    /// batch is applied in full or not at all.
+    #[instrument(skip(db, req), fields(user_id = %user_id, device_id = device_id, batch = req.sessions.len()))]
+    pub async fn execute(
+        db: &DatabaseConnection,
+        user_id: Uuid,
+        device_id: i64,
+        req: ImportSessionsRequest,
+    ) -> AppResult<ImportSessionsResponse> {
+        req.validate()
+            .map_err(|e| AppError::Validation(e.to_string()))?;
+
+        let mut addresses = HashSet::new();
+        let mut sessions = Vec::with_capacity(req.sessions.len());
+        for session in req.sessions {
+            validate_address(&session.address)?;
+            if !addresses.insert(session.address.clone()) {
+                return Err(AppError::Validation(format!(
+                    "Session {} appears more than once",
+                    session.address
+                )));
+            }
+            sessions.push((session.address, decode_record(&session.record)?));
+        }
+
+        let txn = db.begin().await?;
+        lock_device(&txn, user_id, device_id).await?;
+
+        let existing: Vec<(String, i32)> = signal_sessions::Entity::find()
+            .select_only()
+            .column(signal_sessions::Column::Address)
+            .column_as(
+                Expr::cust("octet_length(session_record)"),
+                "size_bytes",
+            )
+            .filter(signal_sessions::Column::DeviceId.eq(device_id))
+            .filter(signal_sessions::Column::Address.is_in(addresses))
+            .into_tuple()
+            .all(&txn)
+            .await?;
+        if !req.overwrite {
+            if let Some((address, _)) = existing.first() {
+                return Err(AppError::Conflict(format!("Session {} already exists", address)));
+            }
+        }
+
+        let (count, stored_bytes) = session_usage(&txn, device_id).await?;
+        let replaced_bytes: usize = existing.iter().map(|(_, size)| *size as usize).sum();
+        let imported_bytes: usize = sessions.iter().map(|(_, record)| record.len()).sum();
+        let created = sessions.len() - existing.len();
+        check_quota(count + created as u64, stored_bytes - replaced_bytes + imported_bytes)?;
+
+        let now = Utc::now();
+        for (address, record) in sessions {
+            let stmt = Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                r#"INSERT INTO signal_sessions (device_id, address, session_record, version, updated_at)
+                   VALUES ($1, $2, $3, 1, $4)
+                   ON CONFLICT (device_id, address) DO UPDATE
+                   SET session_record = EXCLUDED.session_record,
+                       version = signal_sessions.version + 1,
+                       updated_at = EXCLUDED.updated_at"#,
+                [device_id.into(), address.into(), record.into(), now.into()],
+            );
+            txn.execute(stmt).await?;
+        }
+
+        txn.commit().await?;
+
+        info!(
+            "Device {} imported {} sessions ({} replaced)",
+            device_id,
+            created + existing.len(),
+            existing.len()
+        );
+
+        Ok(ImportSessionsResponse {
+            created,
+            replaced: existing.len(),
+        })
+    }
+}
+
+// ============ Helpers ============
+
+fn validate_address(address: &str) -> AppResult<()> {
+    if !SESSION_ADDRESS_REGEX.is_match(address) {
+        return Err(AppError::Validation(
+            "Session address must be \"<name>.<device_id>\"".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn decode_record(record: &str) -> AppResult<Vec<u8>> {
+    let bytes = BASE64
+        .decode(record)
+        .map_err(|_| AppError::Validation("Session record must be base64 encoded".to_string()))?;
+
+    if bytes.is_empty() || bytes.len() > MAX_SESSION_RECORD_BYTES {
+        return Err(AppError::Validation(format!(
+            "Session record must be between 1-{} bytes",
+            MAX_SESSION_RECORD_BYTES
+        )));
+    }
+
+    Ok(bytes)
+}
+
+/// Records are only reachable through an active device of the caller's own
+fn active_device(user_id: Uuid, device_id: i64) -> Select<devices::Entity> {
+    devices::Entity::find_by_id(device_id)
+        .filter(devices::Column::UserId.eq(user_id))
+        .filter(devices::Column::IsActive.eq(true))
+}
+
+async fn ensure_device(db: &DatabaseConnection, user_id: Uuid, device_id: i64) -> AppResult<()> {
+    active_device(user_id, device_id)
+        .one(db)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Device not found".to_string()))?;
+    Ok(())
+}
+
+/// Locking the device row serialises concurrent writes, so the quota holds
+async fn lock_device(txn: &DatabaseTransaction, user_id: Uuid, device_id: i64) -> AppResult<()> {
+    active_device(user_id, device_id)
+        .lock_exclusive()
+        .one(txn)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Device not found".to_string()))?;
+    Ok(())
+}
+
+/// Number of records the device stores and their total size
+async fn session_usage<C: ConnectionTrait>(db: &C, device_id: i64) -> AppResult<(u64, usize)> {
+    let stmt = Statement::from_sql_and_values(
+        DbBackend::Postgres,
+        r#"SELECT count(*) AS count, coalesce(sum(octet_length(session_record)), 0)::bigint AS bytes
+           FROM signal_sessions
+           WHERE device_id = $1"#,
+        [device_id.into()],
+    );
+
+    let row = db
+        .query_one(stmt)
+        .await?
+        .ok_or_else(|| AppError::Internal("Session usage query returned no row".to_string()))?;
+    let count: i64 = row.try_get("", "count")?;
+    let bytes: i64 = row.try_get("", "bytes")?;
+
+    Ok((count as u64, bytes as usize))
+}
+
+fn check_quota(sessions: u64, bytes: usize) -> AppResult<()> {
+    if sessions > MAX_SESSIONS_PER_DEVICE {
+        return Err(AppError::Validation(format!(
+            "A device may store at most {} sessions",
+            MAX_SESSIONS_PER_DEVICE
+        )));
+    }
+    if bytes > MAX_STORED_SESSION_BYTES {
+        return Err(AppError::Validation(format!(
+            "A device may store at most {} bytes of session records",
+            MAX_STORED_SESSION_BYTES
+        )));
+    }
+    Ok(())
+}
+
+fn to_record_response(session: signal_sessions::Model) -> SessionRecordResponse {
+    SessionRecordResponse {
+        address: session.address,
+        record: BASE64.encode(&session.session_record),
+        version: session.version,
+        updated_at: session.updated_at.into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_address_format() {
+        assert!(validate_address("3f2a9c1e-0000-4000-8000-000000000001.1").is_ok());
+        assert!(validate_address("+15551234567.42").is_ok());
+        assert!(validate_address("alice").is_err());
+        assert!(validate_address("alice.").is_err());
+        assert!(validate_address(".1").is_err());
+        assert!(validate_address("../etc.1").is_err());
+        assert!(validate_address("export").is_err());
+    }
+
+    #[test]
+    fn test_session_quota() {
+        assert!(check_quota(MAX_SESSIONS_PER_DEVICE, MAX_STORED_SESSION_BYTES).is_ok());
+        assert!(check_quota(MAX_SESSIONS_PER_DEVICE + 1, 0).is_err());
+        assert!(check_quota(1, MAX_STORED_SESSION_BYTES + 1).is_err());
+        assert!(decode_record(&BASE64.encode(vec![0u8; MAX_SESSION_RECORD_BYTES + 1])).is_err());
+    }
+}
@@ -0,0 +1,47 @@
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+// ============ Tree Heads ============
+
+/// The log's commitment to its first `tree_size` leaves. The signature covers
+/// [`tree_head_message`](super::tree_head_message).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignedTreeHead {
+    pub tree_size: u64,
+    pub root_hash: Vec<u8>,
+    pub timestamp_ms: i64,
+    pub signature: Vec<u8>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TreeHeadResponse {
+    pub tree_head: SignedTreeHead,
+    /// Serialized Curve25519 key that signs tree heads
+    pub log_public_key: Vec<u8>,
+}
+
+// ============ Proofs ============
+
+/// Proof that a device's identity key binding is in the log. The leaf is
+/// [`leaf_data`](super::leaf_data) for the bundle's user, device and identity key.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct InclusionProof {
+    pub leaf_index: u64,
+    /// Sibling hashes from the leaf up to the root
+    pub audit_path: Vec<Vec<u8>>,
+    pub tree_head: SignedTreeHead,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct ConsistencyProofQuery {
+    #[validate(range(min = 1, message = "First tree size must be positive"))]
+    pub first: u64,
+    pub second: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ConsistencyProofResponse {
+    pub first: u64,
+    pub second: u64,
+    pub proof: Vec<Vec<u8>>,
+}
@@ -0,0 +1,116 @@
+//! Key transparency: an append-only Merkle log of every (user, device,
+//! identity key) binding the server has accepted. Signed tree heads commit the
+//! server to one history, so a key substituted for some clients but not others
+//! shows up as inconsistent heads.
+
+pub mod dtos;
+pub mod use_cases;
+
+pub use use_cases::{GetConsistencyProofUseCase, GetTreeHeadUseCase};
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use crypto::{PrivateKey, PublicKey, DJB_TYPE};
+use uuid::Uuid;
+
+/// Prefix of the signed tree head message, so the signature means nothing elsewhere
+const TREE_HEAD_CONTEXT: &[u8] = b"vyry key transparency tree head v1";
+
+/// Signs tree heads with the log's Curve25519 key (XEdDSA)
+#[derive(Clone)]
+pub struct LogSigner {
+    key: PrivateKey,
+    ephemeral: bool,
+}
+
+impl LogSigner {
+    /// Load a base64 encoded 32-byte private key. Without one a key is
+    /// generated, which only suits development: heads signed before a restart
+    /// can no longer be checked against the new key.
+    pub fn new(key: Option<&str>) -> Result<Self, String> {
+        match key.filter(|k| !k.is_empty()) {
+            Some(key) => {
+                let bytes = BASE64
+                    .decode(key)
+                    .map_err(|_| "KEY_TRANSPARENCY_SIGNING_KEY must be base64 encoded".to_string())?;
+                let key = PrivateKey::deserialize(&bytes)
+                    .map_err(|e| format!("KEY_TRANSPARENCY_SIGNING_KEY: {}", e))?;
+                Ok(Self {
+                    key,
+                    ephemeral: false,
+                })
+            }
+            None => Ok(Self {
+                key: PrivateKey::generate(&mut rand::rngs::OsRng),
+                ephemeral: true,
+            }),
+        }
+    }
+
+    pub fn is_ephemeral(&self) -> bool {
+        self.ephemeral
+    }
+
+    pub fn public_key(&self) -> PublicKey {
+        self.key.public_key()
+    }
+
+    pub fn sign_tree_head(&self, tree_size: u64, timestamp_ms: i64, root_hash: &[u8]) -> Vec<u8> {
+        self.key
+            .calculate_signature(
+                &tree_head_message(tree_size, timestamp_ms, root_hash),
+                &mut rand::rngs::OsRng,
+            )
+            .to_vec()
+    }
+}
+
+/// What a tree head signature covers: the context string, the tree size and
+/// timestamp as big-endian 64-bit integers, then the root hash
+pub fn tree_head_message(tree_size: u64, timestamp_ms: i64, root_hash: &[u8]) -> Vec<u8> {
+    [
+        TREE_HEAD_CONTEXT,
+        &tree_size.to_be_bytes(),
+        &timestamp_ms.to_be_bytes(),
+        root_hash,
+    ]
+    .concat()
+}
+
+/// Leaf contents: user ID (16 bytes), device ID (big-endian 64-bit) and the
+/// serialized identity key
+pub fn leaf_data(user_id: Uuid, device_id: i64, identity_key: &[u8]) -> Vec<u8> {
+    [
+        user_id.as_bytes(),
+        &device_id.to_be_bytes()[..],
+        &[DJB_TYPE],
+        identity_key,
+    ]
+    .concat()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tree_head_signature() {
+        let signer = LogSigner::new(None).unwrap();
+        assert!(signer.is_ephemeral());
+
+        let root = [7u8; 32];
+        let signature = signer.sign_tree_head(5, 1_700_000_000_000, &root);
+        let message = tree_head_message(5, 1_700_000_000_000, &root);
+        assert!(signer.public_key().verify_signature(&message, &signature).is_ok());
+        assert!(signer
+            .public_key()
+            .verify_signature(&tree_head_message(6, 1_700_000_000_000, &root), &signature)
+            .is_err());
+
+        let key = BASE64.encode([9u8; 32]);
+        let loaded = LogSigner::new(Some(&key)).unwrap();
+        assert!(!loaded.is_ephemeral());
+        assert_eq!(loaded.public_key(), LogSigner::new(Some(&key)).unwrap().public_key());
+        assert!(LogSigner::new(Some("not base64!")).is_err());
+        assert!(LogSigner::new(Some(&BASE64.encode([9u8; 31]))).is_err());
+    }
+}
@@ -0,0 +1,297 @@
+use super::dtos::*;
+use super::{leaf_data, LogSigner};
+use crate::{AppError, AppResult};
+use chrono::Utc;
+use core::entities::{key_transparency_leaves, key_transparency_nodes, key_transparency_tree_heads};
+use crypto::merkle::{self, Hash, Subtree};
+use sea_orm::sea_query::OnConflict;
+use sea_orm::{
+    ColumnTrait, Condition, ConnectionTrait, DatabaseConnection, DbBackend, EntityTrait,
+    QueryFilter, QueryOrder, Set, Statement,
+};
+use std::collections::HashMap;
+use std::ops::Range;
+use tracing::{info, instrument};
+use uuid::Uuid;
+use validator::Validate;
+
+// ============ Constants ============
+
+/// Advisory lock held while appending, so leaf indexes are handed out in
+/// commit order and the committed leaves are always a prefix of the log
+const LOG_APPEND_LOCK: i64 = 0x006b_746c_6f67;
+
+// ============ Get Tree Head Use Case ============
+
+pub struct GetTreeHeadUseCase;
+
+impl GetTreeHeadUseCase {
+    /// The newest signed tree head, signing one first if leaves were added
+    #[instrument(skip(db, signer))]
+    pub async fn execute(db: &DatabaseConnection, signer: &LogSigner) -> AppResult<TreeHeadResponse> {
+        Ok(TreeHeadResponse {
+            tree_head: latest_tree_head(db, signer).await?,
+            log_public_key: signer.public_key().serialize(),
+        })
+    }
+}
+
+// ============ Get Consistency Proof Use Case ============
+
+pub struct GetConsistencyProofUseCase;
+
+impl GetConsistencyProofUseCase {
+    /// Proof that the tree of `second` leaves extends the tree of `first`
+    #[instrument(skip(db))]
+    pub async fn execute(
+        db: &DatabaseConnection,
+        query: ConsistencyProofQuery,
+    ) -> AppResult<ConsistencyProofResponse> {
+        query
+            .validate()
+            .map_err(|e| AppError::Validation(e.to_string()))?;
+        if query.first > query.second {
+            return Err(AppError::Validation(
+                "First tree size must not exceed the second".to_string(),
+            ));
+        }
+
+        // Only sizes the log has committed to with a signature
+        let signed_size = key_transparency_tree_heads::Entity::find()
+            .order_by_desc(key_transparency_tree_heads::Column::TreeSize)
+            .one(db)
+            .await?
+            .map_or(0, |head| head.tree_size as u64);
+        if query.second > signed_size {
+            return Err(AppError::NotFound(format!(
+                "No signed tree head covers {} leaves",
+                query.second
+            )));
+        }
+
+        let proof = range_hashes(db, merkle::consistency_path(query.first, query.second)).await?;
+
+        Ok(ConsistencyProofResponse {
+            first: query.first,
+            second: query.second,
+            proof: proof.into_iter().map(|hash| hash.to_vec()).collect(),
+        })
+    }
+}
+
+// ============ Helpers ============
+
+/// Append a device's identity key binding to the log. Must run in the
+/// transaction that writes the key, which holds the append lock until commit.
+pub(crate) async fn append_binding<C: ConnectionTrait>(
+    db: &C,
+    user_id: Uuid,
+    device_id: i64,
+    identity_key: &[u8],
+) -> AppResult<u64> {
+    db.execute(Statement::from_sql_and_values(
+        DbBackend::Postgres,
+        "SELECT pg_advisory_xact_lock($1)",
+        [LOG_APPEND_LOCK.into()],
+    ))
+    .await?;
+
+    let index = log_size(db).await?;
+    let leaf = merkle::leaf_hash(&leaf_data(user_id, device_id, identity_key));
+
+    key_transparency_leaves::Entity::insert(key_transparency_leaves::ActiveModel {
+        leaf_index: Set(index as i64),
+        user_id: Set(user_id),
+        device_id: Set(device_id),
+        identity_key: Set(identity_key.to_vec()),
+        created_at: Set(Utc::now().into()),
+    })
+    .exec_without_returning(db)
+    .await?;
+
+    // The new leaf completes one perfect subtree per trailing one bit of its
+    // index; each combines the stored left sibling with the subtree just built
+    let completed = (index + 1).trailing_zeros();
+    let siblings: Vec<Subtree> = (1..=completed)
+        .map(|level| Subtree {
+            level: level - 1,
+            index: ((index + 1) >> (level - 1)) - 2,
+        })
+        .collect();
+    let sibling_hashes = node_hashes(db, &siblings).await?;
+
+    let mut nodes = vec![new_node(0, index, leaf)];
+    let mut hash = leaf;
+    for sibling in siblings {
+        let left = sibling_hashes
+            .get(&sibling)
+            .ok_or_else(|| AppError::Internal("Key transparency node is missing".to_string()))?;
+        hash = merkle::node_hash(left, &hash);
+        nodes.push(new_node(sibling.level + 1, sibling.index / 2, hash));
+    }
+    key_transparency_nodes::Entity::insert_many(nodes)
+        .exec_without_returning(db)
+        .await?;
+
+    info!("Appended key transparency leaf {} for device {}", index, device_id);
+
+    Ok(index)
+}
+
+/// The newest signed tree head. Leaves appended since it was signed get a new
+/// head first, so every committed leaf is covered.
+pub(crate) async fn latest_tree_head<C: ConnectionTrait>(
+    db: &C,
+    signer: &LogSigner,
+) -> AppResult<SignedTreeHead> {
+    let size = log_size(db).await?;
+    let latest = key_transparency_tree_heads::Entity::find()
+        .order_by_desc(key_transparency_tree_heads::Column::TreeSize)
+        .one(db)
+        .await?;
+    if let Some(head) = latest.filter(|head| head.tree_size as u64 >= size) {
+        return Ok(to_signed_tree_head(head));
+    }
+
+    let root = range_hashes(db, std::iter::once(0..size)).await?.remove(0);
+    let timestamp_ms = Utc::now().timestamp_millis();
+
+    // A concurrent request may sign the same size first; its head stands
+    key_transparency_tree_heads::Entity::insert(key_transparency_tree_heads::ActiveModel {
+        tree_size: Set(size as i64),
+        root_hash: Set(root.to_vec()),
+        timestamp_ms: Set(timestamp_ms),
+        signature: Set(signer.sign_tree_head(size, timestamp_ms, &root)),
+    })
+    .on_conflict(
+        OnConflict::column(key_transparency_tree_heads::Column::TreeSize)
+            .do_nothing()
+            .to_owned(),
+    )
+    .exec_without_returning(db)
+    .await?;
+
+    key_transparency_tree_heads::Entity::find_by_id(size as i64)
+        .one(db)
+        .await?
+        .map(to_signed_tree_head)
+        .ok_or_else(|| AppError::Internal("Tree head was not stored".to_string()))
+}
+
+/// Inclusion proof for the device's binding under `tree_head`, if it has one
+pub(crate) async fn inclusion_proof<C: ConnectionTrait>(
+    db: &C,
+    tree_head: &SignedTreeHead,
+    device_id: i64,
+) -> AppResult<Option<InclusionProof>> {
+    let leaf = key_transparency_leaves::Entity::find()
+        .filter(key_transparency_leaves::Column::DeviceId.eq(device_id))
+        .filter(key_transparency_leaves::Column::LeafIndex.lt(tree_head.tree_size as i64))
+        .order_by_desc(key_transparency_leaves::Column::LeafIndex)
+        .one(db)
+        .await?;
+    let Some(leaf) = leaf else {
+        return Ok(None);
+    };
+
+    let leaf_index = leaf.leaf_index as u64;
+    let audit_path =
+        range_hashes(db, merkle::inclusion_path(leaf_index, tree_head.tree_size)).await?;
+
+    Ok(Some(InclusionProof {
+        leaf_index,
+        audit_path: audit_path.into_iter().map(|hash| hash.to_vec()).collect(),
+        tree_head: tree_head.clone(),
+    }))
+}
+
+/// Number of committed leaves
+async fn log_size<C: ConnectionTrait>(db: &C) -> AppResult<u64> {
+    let row = db
+        .query_one(Statement::from_string(
+            DbBackend::Postgres,
+            "SELECT coalesce(max(leaf_index) + 1, 0) AS size FROM key_transparency_leaves",
+        ))
+        .await?
+        .ok_or_else(|| AppError::Internal("Log size query returned no row".to_string()))?;
+    let size: i64 = row.try_get("", "size")?;
+    Ok(size as u64)
+}
+
+/// Hash of each range, from the stored perfect subtrees covering it
+async fn range_hashes<C: ConnectionTrait>(
+    db: &C,
+    ranges: impl IntoIterator<Item = Range<u64>>,
+) -> AppResult<Vec<Hash>> {
+    let covers: Vec<Vec<Subtree>> = ranges.into_iter().map(merkle::perfect_subtrees).collect();
+    let wanted: Vec<Subtree> = covers.iter().flatten().copied().collect();
+    let stored = node_hashes(db, &wanted).await?;
+
+    covers
+        .iter()
+        .map(|cover| {
+            let hashes = cover
+                .iter()
+                .map(|subtree| {
+                    stored.get(subtree).copied().ok_or_else(|| {
+                        AppError::Internal("Key transparency node is missing".to_string())
+                    })
+                })
+                .collect::<AppResult<Vec<Hash>>>()?;
+            Ok(merkle::combine(&hashes))
+        })
+        .collect()
+}
+
+async fn node_hashes<C: ConnectionTrait>(
+    db: &C,
+    subtrees: &[Subtree],
+) -> AppResult<HashMap<Subtree, Hash>> {
+    if subtrees.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let condition = subtrees.iter().fold(Condition::any(), |any, subtree| {
+        any.add(
+            Condition::all()
+                .add(key_transparency_nodes::Column::Level.eq(subtree.level as i16))
+                .add(key_transparency_nodes::Column::NodeIndex.eq(subtree.index as i64)),
+        )
+    });
+
+    key_transparency_nodes::Entity::find()
+        .filter(condition)
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|node| {
+            let hash: Hash = node.hash.as_slice().try_into().map_err(|_| {
+                AppError::Internal("Key transparency node hash is malformed".to_string())
+            })?;
+            Ok((
+                Subtree {
+                    level: node.level as u32,
+                    index: node.node_index as u64,
+                },
+                hash,
+            ))
+        })
+        .collect()
+}
+
+fn new_node(level: u32, index: u64, hash: Hash) -> key_transparency_nodes::ActiveModel {
+    key_transparency_nodes::ActiveModel {
+        level: Set(level as i16),
+        node_index: Set(index as i64),
+        hash: Set(hash.to_vec()),
+    }
+}
+
+fn to_signed_tree_head(head: key_transparency_tree_heads::Model) -> SignedTreeHead {
+    SignedTreeHead {
+        tree_size: head.tree_size as u64,
+        root_hash: head.root_hash,
+        timestamp_ms: head.timestamp_ms,
+        signature: head.signature,
+    }
+}
@@ -0,0 +1,148 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+// ============ Visibility ============
+
+/// Audience for a discoverability or profile-field setting
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Visibility {
+    Everyone,
+    /// Only users who share a conversation with them
+    Contacts,
+    Nobody,
+}
+
+impl Visibility {
+    pub fn as_i16(self) -> i16 {
+        match self {
+            Visibility::Everyone => 0,
+            Visibility::Contacts => 1,
+            Visibility::Nobody => 2,
+        }
+    }
+
+    /// Unknown values are treated as the most restrictive setting
+    pub fn from_i16(value: i16) -> Self {
+        match value {
+            0 => Visibility::Everyone,
+            1 => Visibility::Contacts,
+            _ => Visibility::Nobody,
+        }
+    }
+
+    /// Whether a viewer other than the owner may see the field
+    pub fn permits(self, is_contact: bool) -> bool {
+        match self {
+            Visibility::Everyone => true,
+            Visibility::Contacts => is_contact,
+            Visibility::Nobody => false,
+        }
+    }
+}
+
+// ============ Discoverability ============
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateDiscoverabilityRequest {
+    pub discoverability: Visibility,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateDiscoverabilityResponse {
+    pub discoverability: Visibility,
+    pub updated_at: DateTime<Utc>,
+}
+
+// ============ Profile Visibility ============
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProfileVisibility {
+    pub display_name: Visibility,
+    pub bio: Visibility,
+    pub profile_picture: Visibility,
+    pub background_image: Visibility,
+}
+
+/// Omitted fields keep their current setting
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct UpdateProfileVisibilityRequest {
+    #[serde(default)]
+    pub display_name: Option<Visibility>,
+    #[serde(default)]
+    pub bio: Option<Visibility>,
+    #[serde(default)]
+    pub profile_picture: Option<Visibility>,
+    #[serde(default)]
+    pub background_image: Option<Visibility>,
+}
+
+// ============ Public Profile ============
+
+/// Profile of another user as the viewer is allowed to see it. Fields hidden
+/// from the viewer are `None`; the phone number is never included.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PublicProfileResponse {
+    pub user_id: Uuid,
+    pub username: Option<String>,
+    pub display_name: Option<String>,
+    pub bio: Option<String>,
+    pub profile_picture_url: Option<String>,
+    pub background_image_url: Option<String>,
+}
+
+// ============ Username Lookup ============
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct SearchUsernamesQuery {
+    #[validate(length(min = 3, max = 50, message = "Search query must be between 3-50 characters"))]
+    pub q: String,
+    #[serde(default)]
+    #[validate(range(min = 1, max = 20, message = "Limit must be between 1-20"))]
+    pub limit: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SearchUsernamesResponse {
+    pub results: Vec<PublicProfileResponse>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_visibility_round_trip() {
+        for value in [
+            Visibility::Everyone,
+            Visibility::Contacts,
+            Visibility::Nobody,
+        ] {
+            assert_eq!(Visibility::from_i16(value.as_i16()), value);
+        }
+        assert_eq!(Visibility::from_i16(42), Visibility::Nobody);
+
+        assert!(Visibility::Everyone.permits(false));
+        assert!(Visibility::Contacts.permits(true));
+        assert!(!Visibility::Contacts.permits(false));
+        assert!(!Visibility::Nobody.permits(true));
+        assert_eq!(
+            serde_json::to_string(&Visibility::Contacts).unwrap(),
+            "\"contacts\""
+        );
+    }
+
+    #[test]
+    fn test_search_usernames_query_validation() {
+        let valid = SearchUsernamesQuery { q: "joh".to_string(), limit: Some(20) };
+        assert!(valid.validate().is_ok());
+
+        let too_short = SearchUsernamesQuery { q: "jo".to_string(), limit: None };
+        assert!(too_short.validate().is_err());
+
+        let too_many = SearchUsernamesQuery { q: "john".to_string(), limit: Some(100) };
+        assert!(too_many.validate().is_err());
+    }
+}
@@ -0,0 +1,7 @@
+pub mod dtos;
+pub mod use_cases;
+
+pub use use_cases::{
+    GetPublicProfileUseCase, GetUserByUsernameUseCase, SearchUsernamesUseCase,
+    UpdateDiscoverabilityUseCase, UpdateProfileVisibilityUseCase,
+};
@@ -0,0 +1,425 @@
+use crate::blocks::use_cases::{blocked_by, blockers_of};
+use crate::media::use_cases::served_image_url;
+use crate::rate_limit::check_rate_limit;
+use crate::users::dtos::*;
+use crate::{AppError, AppResult};
+use chrono::Utc;
+use core::entities::conv_members::{self, RequestStatus};
+use core::entities::users;
+use redis::aio::MultiplexedConnection;
+use sea_orm::sea_query::{Alias, Expr, Func, JoinType, LikeExpr, Query, SelectStatement};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, Condition, DatabaseConnection, EntityTrait, Order, QueryFilter,
+    QueryOrder, QuerySelect, Set,
+};
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use tracing::instrument;
+use uuid::Uuid;
+use validator::Validate;
+
+// ============ Constants ============
+
+const USERNAME_LOOKUP_MAX_REQUESTS: u32 = 30;
+const USERNAME_LOOKUP_WINDOW_SECONDS: i64 = 60;
+const USERNAME_SEARCH_DEFAULT_LIMIT: u64 = 10;
+
+// ============ Get User By Username Use Case ============
+
+pub struct GetUserByUsernameUseCase;
+
+impl GetUserByUsernameUseCase {
+    #[instrument(skip(db, redis_conn), fields(viewer_id = %viewer_id))]
+    pub async fn execute(
+        db: &DatabaseConnection,
+        redis_conn: &mut MultiplexedConnection,
+        viewer_id: Uuid,
+        username: &str,
+    ) -> AppResult<PublicProfileResponse> {
+        let username = username.trim();
+        if !crate::auth::USERNAME_REGEX.is_match(username) {
+            return Err(AppError::Validation("Invalid username".to_string()));
+        }
+
+        check_lookup_rate_limit(redis_conn, viewer_id).await?;
+
+        // Hidden users are reported exactly like missing ones
+        let user = users::Entity::find()
+            .filter(Expr::expr(lower_username()).eq(username.to_lowercase()))
+            .filter(users::Column::IsDeleted.eq(false))
+            .filter(visible_to(viewer_id))
+            .one(db)
+            .await?
+            .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+
+        let contacts = contacts_among(db, viewer_id, vec![user.user_id]).await?;
+        let is_contact = contacts.contains(&user.user_id);
+        Ok(project_profile(user, viewer_id, is_contact))
+    }
+}
+
+// ============ Search Usernames Use Case ============
+
+pub struct SearchUsernamesUseCase;
+
+impl SearchUsernamesUseCase {
+    #[instrument(skip(db, redis_conn), fields(viewer_id = %viewer_id))]
+    pub async fn execute(
+        db: &DatabaseConnection,
+        redis_conn: &mut MultiplexedConnection,
+        viewer_id: Uuid,
+        query: SearchUsernamesQuery,
+    ) -> AppResult<SearchUsernamesResponse> {
+        query
+            .validate()
+            .map_err(|e| AppError::Validation(e.to_string()))?;
+
+        let prefix = query.q.trim().to_lowercase();
+        if !crate::auth::USERNAME_REGEX.is_match(&prefix) {
+            return Err(AppError::Validation(
+                "Search query can only contain letters, numbers, underscores, and hyphens".to_string(),
+            ));
+        }
+
+        check_lookup_rate_limit(redis_conn, viewer_id).await?;
+
+        // Usernames may contain '_', which LIKE treats as a wildcard
+        let pattern = format!("{}%", prefix.replace('_', "\\_"));
+
+        let found = users::Entity::find()
+            .filter(Expr::expr(lower_username()).like(LikeExpr::new(pattern)))
+            .filter(users::Column::IsDeleted.eq(false))
+            .filter(visible_to(viewer_id))
+            .order_by(Expr::expr(lower_username()), Order::Asc)
+            .limit(query.limit.unwrap_or(USERNAME_SEARCH_DEFAULT_LIMIT))
+            .all(db)
+            .await?;
+
+        let contacts =
+            contacts_among(db, viewer_id, found.iter().map(|u| u.user_id).collect()).await?;
+        let results = found
+            .into_iter()
+            .map(|user| {
+                let is_contact = contacts.contains(&user.user_id);
+                project_profile(user, viewer_id, is_contact)
+            })
+            .collect();
+
+        Ok(SearchUsernamesResponse { results })
+    }
+}
+
+// ============ Update Discoverability Use Case ============
+
+pub struct UpdateDiscoverabilityUseCase;
+
+impl UpdateDiscoverabilityUseCase {
+    #[instrument(skip(db), fields(user_id = %user_id))]
+    pub async fn execute(
+        db: &DatabaseConnection,
+        user_id: Uuid,
+        req: UpdateDiscoverabilityRequest,
+    ) -> AppResult<UpdateDiscoverabilityResponse> {
+        let user = users::Entity::find_by_id(user_id)
+            .one(db)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("User {} not found", user_id)))?;
+
+        let now = Utc::now();
+        let mut active_user: users::ActiveModel = user.into();
+        active_user.discoverability = Set(req.discoverability.as_i16());
+        active_user.updated_at = Set(now.into());
+        active_user.update(db).await?;
+
+        Ok(UpdateDiscoverabilityResponse {
+            discoverability: req.discoverability,
+            updated_at: now,
+        })
+    }
+}
+
+// ============ Get Public Profile Use Case ============
+
+pub struct GetPublicProfileUseCase;
+
+impl GetPublicProfileUseCase {
+    #[instrument(skip(db), fields(viewer_id = %viewer_id, user_id = %user_id))]
+    pub async fn execute(
+        db: &DatabaseConnection,
+        viewer_id: Uuid,
+        user_id: Uuid,
+    ) -> AppResult<PublicProfileResponse> {
+        // Blocking hides the blocker's profile as if the account did not exist
+        let user = users::Entity::find_by_id(user_id)
+            .filter(users::Column::IsDeleted.eq(false))
+            .filter(users::Column::UserId.not_in_subquery(blockers_of(viewer_id)))
+            .one(db)
+            .await?
+            .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+
+        let contacts = contacts_among(db, viewer_id, vec![user_id]).await?;
+        Ok(project_profile(user, viewer_id, contacts.contains(&user_id)))
+    }
+}
+
+// ============ Update Profile Visibility Use Case ============
+
+pub struct UpdateProfileVisibilityUseCase;
+
+impl UpdateProfileVisibilityUseCase {
+    #[instrument(skip(db), fields(user_id = %user_id))]
+    pub async fn execute(
+        db: &DatabaseConnection,
+        user_id: Uuid,
+        req: UpdateProfileVisibilityRequest,
+    ) -> AppResult<ProfileVisibility> {
+        let user = users::Entity::find_by_id(user_id)
+            .one(db)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("User {} not found", user_id)))?;
+
+        let mut active_user: users::ActiveModel = user.into();
+        if let Some(v) = req.display_name {
+            active_user.display_name_visibility = Set(v.as_i16());
+        }
+        if let Some(v) = req.bio {
+            active_user.bio_visibility = Set(v.as_i16());
+        }
+        if let Some(v) = req.profile_picture {
+            active_user.profile_picture_visibility = Set(v.as_i16());
+        }
+        if let Some(v) = req.background_image {
+            active_user.background_image_visibility = Set(v.as_i16());
+        }
+        active_user.updated_at = Set(Utc::now().into());
+
+        let updated_user = active_user.update(db).await?;
+        Ok(ProfileVisibility::from(&updated_user))
+    }
+}
+
+// ============ Helpers ============
+
+impl From<&users::Model> for ProfileVisibility {
+    fn from(user: &users::Model) -> Self {
+        Self {
+            display_name: Visibility::from_i16(user.display_name_visibility),
+            bio: Visibility::from_i16(user.bio_visibility),
+            profile_picture: Visibility::from_i16(user.profile_picture_visibility),
+            background_image: Visibility::from_i16(user.background_image_visibility),
+        }
+    }
+}
+
+impl PublicProfileResponse {
+    /// Strong validator over the projected profile, so it also changes when
+    /// the viewer gains or loses access to a field
+    pub fn etag(&self) -> String {
+        let body = serde_json::to_vec(self).unwrap_or_default();
+        let digest = Sha256::digest(&body);
+        let hex: String = digest[..16].iter().map(|b| format!("{:02x}", b)).collect();
+        format!("\"{}\"", hex)
+    }
+}
+
+/// Project a user's profile for `viewer_id`, dropping fields they may not see
+pub(crate) fn project_profile(
+    user: users::Model,
+    viewer_id: Uuid,
+    is_contact: bool,
+) -> PublicProfileResponse {
+    let visibility = ProfileVisibility::from(&user);
+    let is_self = user.user_id == viewer_id;
+    let show = |v: Visibility| is_self || v.permits(is_contact);
+
+    PublicProfileResponse {
+        user_id: user.user_id,
+        username: user.username,
+        display_name: user.display_name.filter(|_| show(visibility.display_name)),
+        bio: user.bio.filter(|_| show(visibility.bio)),
+        profile_picture_url: served_image_url(user.profile_picture)
+            .filter(|_| show(visibility.profile_picture)),
+        background_image_url: served_image_url(user.background_image)
+            .filter(|_| show(visibility.background_image)),
+    }
+}
+
+/// Everyone sharing an active conversation with `user_id`, minus blocks in either direction
+pub async fn list_contact_ids(db: &DatabaseConnection, user_id: Uuid) -> AppResult<Vec<Uuid>> {
+    let ids = users::Entity::find()
+        .select_only()
+        .column(users::Column::UserId)
+        .filter(users::Column::UserId.in_subquery(contact_ids(user_id)))
+        .filter(users::Column::UserId.ne(user_id))
+        .filter(users::Column::IsDeleted.eq(false))
+        .filter(users::Column::UserId.not_in_subquery(blockers_of(user_id)))
+        .filter(users::Column::UserId.not_in_subquery(blocked_by(user_id)))
+        .into_tuple::<Uuid>()
+        .all(db)
+        .await?;
+    Ok(ids)
+}
+
+/// The subset of `user_ids` sharing an active conversation with `user_id`
+pub async fn contacts_among(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    user_ids: Vec<Uuid>,
+) -> AppResult<HashSet<Uuid>> {
+    if user_ids.is_empty() {
+        return Ok(HashSet::new());
+    }
+
+    let ids = users::Entity::find()
+        .select_only()
+        .column(users::Column::UserId)
+        .filter(users::Column::UserId.is_in(user_ids))
+        .filter(users::Column::UserId.in_subquery(contact_ids(user_id)))
+        .into_tuple::<Uuid>()
+        .all(db)
+        .await?;
+    Ok(ids.into_iter().collect())
+}
+
+async fn check_lookup_rate_limit(
+    redis_conn: &mut MultiplexedConnection,
+    viewer_id: Uuid,
+) -> AppResult<()> {
+    check_rate_limit(
+        redis_conn,
+        &format!("username_lookup:{}", viewer_id),
+        USERNAME_LOOKUP_MAX_REQUESTS,
+        USERNAME_LOOKUP_WINDOW_SECONDS,
+        "Too many username lookups. Please try again later.",
+    )
+    .await
+}
+
+fn lower_username() -> sea_orm::sea_query::SimpleExpr {
+    Func::lower(Expr::col((users::Entity, users::Column::Username))).into()
+}
+
+/// Users the viewer may find: themselves, anyone discoverable by everyone, and
+/// contacts-only users who share an active conversation with the viewer.
+/// Users who blocked the viewer are never found.
+fn visible_to(viewer_id: Uuid) -> Condition {
+    Condition::all()
+        .add(users::Column::UserId.not_in_subquery(blockers_of(viewer_id)))
+        .add(
+            Condition::any()
+                .add(users::Column::UserId.eq(viewer_id))
+                .add(users::Column::Discoverability.eq(Visibility::Everyone.as_i16()))
+                .add(
+                    Condition::all()
+                        .add(users::Column::Discoverability.eq(Visibility::Contacts.as_i16()))
+                        .add(users::Column::UserId.in_subquery(contact_ids(viewer_id))),
+                ),
+        )
+}
+
+/// `SELECT user_id` of everyone sharing an active conversation with `user_id`.
+/// Conversations still waiting on a message request do not count.
+pub fn contact_ids(user_id: Uuid) -> SelectStatement {
+    let accepted = i16::from(RequestStatus::Accepted);
+    let mine = Alias::new("mine");
+    let theirs = Alias::new("theirs");
+
+    Query::select()
+        .column((theirs.clone(), conv_members::Column::UserId))
+        .from_as(conv_members::Entity, mine.clone())
+        .join_as(
+            JoinType::InnerJoin,
+            conv_members::Entity,
+            theirs.clone(),
+            Expr::col((mine.clone(), conv_members::Column::ConvId))
+                .equals((theirs.clone(), conv_members::Column::ConvId)),
+        )
+        .and_where(Expr::col((mine.clone(), conv_members::Column::UserId)).eq(user_id))
+        .and_where(Expr::col((mine.clone(), conv_members::Column::LeftAt)).is_null())
+        .and_where(Expr::col((theirs.clone(), conv_members::Column::LeftAt)).is_null())
+        .and_where(Expr::col((mine, conv_members::Column::RequestStatus)).eq(accepted))
+        .and_where(Expr::col((theirs, conv_members::Column::RequestStatus)).eq(accepted))
+        .to_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user(visibility: Visibility) -> users::Model {
+        let now = Utc::now().into();
+        users::Model {
+            user_id: Uuid::new_v4(),
+            phone_number: "+66812345678".to_string(),
+            phone_number_hash: vec![0; 32],
+            username: Some("alice".to_string()),
+            display_name: Some("Alice".to_string()),
+            bio: Some("Hello".to_string()),
+            profile_picture: Some(format!(
+                "https://api.example.com/api/v1/media/avatars/{}/{}.jpg",
+                Uuid::new_v4(),
+                Uuid::new_v4()
+            )),
+            background_image: None,
+            last_seen_at: None,
+            is_online: false,
+            is_deleted: false,
+            deleted_at: None,
+            purged_at: None,
+            suspended_until: None,
+            banned_at: None,
+            created_at: now,
+            updated_at: now,
+            pin_hash: None,
+            registration_lock: false,
+            registration_lock_expires_at: None,
+            pin_set_at: None,
+            pin_reset_at: None,
+            discoverability: Visibility::Everyone.as_i16(),
+            display_name_visibility: Visibility::Everyone.as_i16(),
+            bio_visibility: visibility.as_i16(),
+            profile_picture_visibility: visibility.as_i16(),
+            background_image_visibility: visibility.as_i16(),
+        }
+    }
+
+    #[test]
+    fn test_project_profile_applies_field_visibility() {
+        let viewer = Uuid::new_v4();
+
+        let contacts_only = user(Visibility::Contacts);
+        let stranger_view = project_profile(contacts_only.clone(), viewer, false);
+        assert_eq!(stranger_view.display_name.as_deref(), Some("Alice"));
+        assert!(stranger_view.bio.is_none());
+        assert!(stranger_view.profile_picture_url.is_none());
+
+        let contact_view = project_profile(contacts_only.clone(), viewer, true);
+        assert_eq!(contact_view.bio.as_deref(), Some("Hello"));
+        assert!(contact_view.profile_picture_url.is_some());
+
+        let hidden = user(Visibility::Nobody);
+        assert!(project_profile(hidden.clone(), viewer, true).bio.is_none());
+        let own_view = project_profile(hidden.clone(), hidden.user_id, false);
+        assert_eq!(own_view.bio.as_deref(), Some("Hello"));
+    }
+
+    #[test]
+    fn test_project_profile_drops_external_images() {
+        let mut external = user(Visibility::Everyone);
+        external.profile_picture = Some("https://tracker.example.com/pixel.gif".to_string());
+        let own_view = project_profile(external.clone(), external.user_id, false);
+        assert!(own_view.profile_picture_url.is_none());
+    }
+
+    #[test]
+    fn test_etag_tracks_visible_fields() {
+        let viewer = Uuid::new_v4();
+        let model = user(Visibility::Contacts);
+
+        let stranger = project_profile(model.clone(), viewer, false).etag();
+        let contact = project_profile(model.clone(), viewer, true).etag();
+        assert_ne!(stranger, contact);
+        assert_eq!(stranger, project_profile(model, viewer, false).etag());
+        assert!(stranger.starts_with('"') && stranger.ends_with('"'));
+    }
+}
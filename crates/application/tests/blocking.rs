@@ -0,0 +1,153 @@
+//! Blocking across messaging and discovery. Needs a migrated Postgres and Redis:
+//!
+//!     TEST_DATABASE_URL=postgres://postgres@localhost:5432/vyry_test \
+//!     TEST_REDIS_URL=redis://localhost:6379 \
+//!     cargo test -p application --test blocking -- --ignored
+
+mod common;
+
+use application::auth::phone::{phone_number_hash, PhoneHasher};
+use application::blocks::BlockUserUseCase;
+use application::chat::use_cases::SendMessageUseCase;
+use application::contacts::dtos::DiscoverContactsRequest;
+use application::contacts::DiscoverContactsUseCase;
+use application::users::{GetPublicProfileUseCase, GetUserByUsernameUseCase};
+use application::AppError;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use common::{connect_db, connect_redis, count, create_user, delete_users, message, run};
+use uuid::Uuid;
+
+#[test]
+#[ignore = "needs TEST_DATABASE_URL"]
+fn test_blocked_send_is_dropped() {
+    run(async {
+        let db = connect_db().await;
+        let alice = create_user(&db).await;
+        let bob = create_user(&db).await;
+        let conversation_id = Uuid::new_v4();
+
+        assert!(
+            SendMessageUseCase::execute(&db, message(&alice, &bob, conversation_id))
+                .await
+                .unwrap()
+        );
+
+        BlockUserUseCase::execute(&db, bob.user_id, alice.user_id)
+            .await
+            .unwrap();
+
+        // Reported like a delivery, but nothing is stored for bob
+        assert!(
+            !SendMessageUseCase::execute(&db, message(&alice, &bob, conversation_id))
+                .await
+                .unwrap()
+        );
+        let stored = count(
+            &db,
+            "SELECT count(*) AS count FROM messages WHERE conv_id = $1",
+            conversation_id,
+        )
+        .await;
+        assert_eq!(stored, 1);
+
+        delete_users(&db, &[&alice, &bob]).await;
+    });
+}
+
+#[test]
+#[ignore = "needs TEST_DATABASE_URL"]
+fn test_blocked_send_cannot_reach_blocker_through_another_recipient() {
+    run(async {
+        let db = connect_db().await;
+        let alice = create_user(&db).await;
+        let bob = create_user(&db).await;
+        let carol = create_user(&db).await;
+        let conversation_id = Uuid::new_v4();
+
+        assert!(
+            SendMessageUseCase::execute(&db, message(&alice, &carol, conversation_id))
+                .await
+                .unwrap()
+        );
+        BlockUserUseCase::execute(&db, bob.user_id, alice.user_id)
+            .await
+            .unwrap();
+
+        // Addressed to carol, who has not blocked alice, but aimed at bob's device
+        let mut req = message(&alice, &carol, conversation_id);
+        req.recipient_device_id = bob.device_id;
+        assert!(matches!(
+            SendMessageUseCase::execute(&db, req).await,
+            Err(AppError::NotFound(_))
+        ));
+        let stored = count(
+            &db,
+            "SELECT count(*) AS count FROM messages WHERE conv_id = $1",
+            conversation_id,
+        )
+        .await;
+        assert_eq!(stored, 1);
+
+        delete_users(&db, &[&alice, &bob, &carol]).await;
+    });
+}
+
+#[test]
+#[ignore = "needs TEST_DATABASE_URL and TEST_REDIS_URL"]
+fn test_blocked_user_is_hidden_from_discovery_and_lookup() {
+    run(async {
+        let db = connect_db().await;
+        let mut redis_conn = connect_redis().await;
+        let alice = create_user(&db).await;
+        let bob = create_user(&db).await;
+        let hash = BASE64.encode(phone_number_hash(&bob.phone_number));
+        let discover = || DiscoverContactsRequest {
+            hashes: vec![hash.clone()],
+        };
+
+        let found = DiscoverContactsUseCase::execute(
+            &db,
+            &mut redis_conn,
+            &PhoneHasher::default(),
+            alice.user_id,
+            discover(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(found.contacts.len(), 1);
+        assert!(GetUserByUsernameUseCase::execute(
+            &db,
+            &mut redis_conn,
+            alice.user_id,
+            &bob.username
+        )
+        .await
+        .is_ok());
+
+        BlockUserUseCase::execute(&db, bob.user_id, alice.user_id)
+            .await
+            .unwrap();
+
+        let found = DiscoverContactsUseCase::execute(
+            &db,
+            &mut redis_conn,
+            &PhoneHasher::default(),
+            alice.user_id,
+            discover(),
+        )
+        .await
+        .unwrap();
+        assert!(found.contacts.is_empty());
+        assert!(matches!(
+            GetUserByUsernameUseCase::execute(&db, &mut redis_conn, alice.user_id, &bob.username)
+                .await,
+            Err(AppError::NotFound(_))
+        ));
+        assert!(matches!(
+            GetPublicProfileUseCase::execute(&db, alice.user_id, bob.user_id).await,
+            Err(AppError::NotFound(_))
+        ));
+
+        delete_users(&db, &[&alice, &bob]).await;
+    });
+}
@@ -0,0 +1,127 @@
+//! Shared fixtures for the database-backed integration tests. Each test
+//! creates its own users and removes them when done.
+
+#![allow(dead_code)]
+
+use application::auth::phone::phone_number_hash;
+use application::chat::dtos::SendMessageRequest;
+use redis::aio::MultiplexedConnection;
+use sea_orm::{ConnectionTrait, Database, DatabaseConnection, DbBackend, Statement};
+use uuid::Uuid;
+
+pub struct TestUser {
+    pub user_id: Uuid,
+    pub device_id: i64,
+    pub phone_number: String,
+    pub username: String,
+}
+
+pub async fn connect_db() -> DatabaseConnection {
+    let url = std::env::var("TEST_DATABASE_URL").expect("TEST_DATABASE_URL must be set");
+    Database::connect(url).await.unwrap()
+}
+
+pub async fn connect_redis() -> MultiplexedConnection {
+    let url = std::env::var("TEST_REDIS_URL").expect("TEST_REDIS_URL must be set");
+    redis::Client::open(url)
+        .unwrap()
+        .get_multiplexed_tokio_connection()
+        .await
+        .unwrap()
+}
+
+pub async fn create_user(db: &DatabaseConnection) -> TestUser {
+    let user_id = Uuid::new_v4();
+    let digits = user_id.as_u128() % 10_000_000_000;
+    let phone_number = format!("+1555{:010}", digits);
+    let username = format!("u{}", &user_id.simple().to_string()[..12]);
+
+    db.execute(Statement::from_sql_and_values(
+        DbBackend::Postgres,
+        "INSERT INTO users (user_id, phone_number, phone_number_hash, username, display_name)
+         VALUES ($1, $2, $3, $4, 'Test')",
+        [
+            user_id.into(),
+            phone_number.clone().into(),
+            phone_number_hash(&phone_number).into(),
+            username.clone().into(),
+        ],
+    ))
+    .await
+    .unwrap();
+
+    let row = db
+        .query_one(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            "INSERT INTO devices (user_id, device_uuid, platform, identity_key_public, registration_id,
+                                  signed_prekey_id, signed_prekey_public, signed_prekey_signature)
+             VALUES ($1, $2, 1, $3, 1, 1, $3, $4)
+             RETURNING device_id",
+            [
+                user_id.into(),
+                Uuid::new_v4().into(),
+                vec![1u8; 32].into(),
+                vec![2u8; 64].into(),
+            ],
+        ))
+        .await
+        .unwrap()
+        .unwrap();
+
+    TestUser {
+        user_id,
+        device_id: row.try_get("", "device_id").unwrap(),
+        phone_number,
+        username,
+    }
+}
+
+pub async fn delete_users(db: &DatabaseConnection, users: &[&TestUser]) {
+    for user in users {
+        for sql in [
+            "DELETE FROM message_deliveries WHERE message_id IN
+                 (SELECT message_id FROM messages WHERE sender_user_id = $1)",
+            "DELETE FROM messages WHERE sender_user_id = $1",
+            "DELETE FROM conversations WHERE creator_id = $1",
+            "DELETE FROM users WHERE user_id = $1",
+        ] {
+            db.execute(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                sql,
+                [user.user_id.into()],
+            ))
+            .await
+            .unwrap();
+        }
+    }
+}
+
+/// Run a `SELECT count(*) AS count ... $1` query
+pub async fn count(db: &DatabaseConnection, sql: &str, conv_id: Uuid) -> i64 {
+    db.query_one(Statement::from_sql_and_values(
+        DbBackend::Postgres,
+        sql,
+        [conv_id.into()],
+    ))
+    .await
+    .unwrap()
+    .unwrap()
+    .try_get("", "count")
+    .unwrap()
+}
+
+pub fn message(from: &TestUser, to: &TestUser, conversation_id: Uuid) -> SendMessageRequest {
+    SendMessageRequest {
+        sender_id: from.user_id,
+        sender_device_id: from.device_id,
+        recipient_id: to.user_id,
+        recipient_device_id: to.device_id,
+        conversation_id,
+        client_message_id: Uuid::new_v4(),
+        content: vec![0xAB; 16],
+    }
+}
+
+pub fn run(test: impl std::future::Future<Output = ()>) {
+    tokio::runtime::Runtime::new().unwrap().block_on(test);
+}
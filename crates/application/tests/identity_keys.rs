@@ -0,0 +1,199 @@
+//! Identity key history and the change feed for offline devices. Needs a
+//! migrated Postgres:
+//!
+//!     TEST_DATABASE_URL=postgres://postgres@localhost:5432/vyry_test \
+//!     cargo test -p application --test identity_keys -- --ignored
+
+mod common;
+
+use application::chat::use_cases::SendMessageUseCase;
+use application::keys::dtos::ListIdentityChangesQuery;
+use application::keys::use_cases::{
+    record_identity_key, GetIdentityKeysUseCase, ListIdentityChangesUseCase,
+};
+use common::{connect_db, create_user, delete_users, message, run, TestUser};
+use sea_orm::{ConnectionTrait, DatabaseConnection, DbBackend, Statement};
+use uuid::Uuid;
+
+/// Make `key` the device's current identity key
+async fn set_identity_key(db: &DatabaseConnection, user: &TestUser, key: &[u8]) {
+    db.execute(Statement::from_sql_and_values(
+        DbBackend::Postgres,
+        "UPDATE devices SET identity_key_public = $2 WHERE device_id = $1",
+        [user.device_id.into(), key.to_vec().into()],
+    ))
+    .await
+    .unwrap();
+}
+
+fn changes_after(after: Option<i64>) -> ListIdentityChangesQuery {
+    ListIdentityChangesQuery { after, limit: None }
+}
+
+#[test]
+#[ignore = "needs TEST_DATABASE_URL"]
+fn test_record_identity_key_detects_change() {
+    run(async {
+        let db = connect_db().await;
+        let user = create_user(&db).await;
+        let (first, second) = (vec![3u8; 32], vec![4u8; 32]);
+
+        // A first registration replaces nothing
+        assert!(
+            !record_identity_key(&db, user.user_id, user.device_id, &first, None)
+                .await
+                .unwrap()
+        );
+        // Re-registering with the same key is not a change
+        assert!(!record_identity_key(
+            &db,
+            user.user_id,
+            user.device_id,
+            &first,
+            Some(first.clone())
+        )
+        .await
+        .unwrap());
+        assert!(record_identity_key(
+            &db,
+            user.user_id,
+            user.device_id,
+            &second,
+            Some(first.clone())
+        )
+        .await
+        .unwrap());
+
+        let previous: Vec<Option<Vec<u8>>> = db
+            .query_all(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                "SELECT previous_identity_key FROM identity_key_history WHERE user_id = $1 ORDER BY id",
+                [user.user_id.into()],
+            ))
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|row| row.try_get("", "previous_identity_key").unwrap())
+            .collect();
+        assert_eq!(previous, vec![None, None, Some(first)]);
+
+        delete_users(&db, &[&user]).await;
+    });
+}
+
+#[test]
+#[ignore = "needs TEST_DATABASE_URL"]
+fn test_identity_key_since_and_changes_feed() {
+    run(async {
+        let db = connect_db().await;
+        let alice = create_user(&db).await;
+        let bob = create_user(&db).await;
+        let stranger = create_user(&db).await;
+
+        // Contacts share an accepted conversation
+        let conversation_id = Uuid::new_v4();
+        SendMessageUseCase::execute(&db, message(&alice, &bob, conversation_id))
+            .await
+            .unwrap();
+        db.execute(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            "UPDATE conv_members SET request_status = 0 WHERE conv_id = $1",
+            [conversation_id.into()],
+        ))
+        .await
+        .unwrap();
+
+        let (first, second) = (vec![5u8; 32], vec![6u8; 32]);
+        record_identity_key(&db, bob.user_id, bob.device_id, &first, None)
+            .await
+            .unwrap();
+        set_identity_key(&db, &bob, &first).await;
+        let since = GetIdentityKeysUseCase::execute(&db, bob.user_id)
+            .await
+            .unwrap()
+            .devices[0]
+            .since;
+
+        // The same key registered again keeps its original date
+        record_identity_key(&db, bob.user_id, bob.device_id, &first, Some(first.clone()))
+            .await
+            .unwrap();
+        let keys = GetIdentityKeysUseCase::execute(&db, bob.user_id)
+            .await
+            .unwrap();
+        assert_eq!(keys.devices[0].since, since);
+
+        // A new key starts over
+        record_identity_key(
+            &db,
+            bob.user_id,
+            bob.device_id,
+            &second,
+            Some(first.clone()),
+        )
+        .await
+        .unwrap();
+        set_identity_key(&db, &bob, &second).await;
+        let keys = GetIdentityKeysUseCase::execute(&db, bob.user_id)
+            .await
+            .unwrap();
+        assert!(keys.devices[0].since > since);
+
+        // Alice missed all three while offline
+        let feed = ListIdentityChangesUseCase::execute(
+            &db,
+            alice.user_id,
+            alice.device_id,
+            changes_after(None),
+        )
+        .await
+        .unwrap();
+        let bobs: Vec<_> = feed
+            .changes
+            .iter()
+            .filter(|change| change.user_id == bob.user_id)
+            .collect();
+        assert_eq!(bobs.len(), 3);
+        assert_eq!(bobs[2].identity_key[1..], second[..]);
+        assert_eq!(
+            bobs[2]
+                .previous_identity_key
+                .as_deref()
+                .map(|key| &key[1..]),
+            Some(&first[..])
+        );
+
+        let last = feed.changes.last().unwrap().id;
+        let feed = ListIdentityChangesUseCase::execute(
+            &db,
+            alice.user_id,
+            alice.device_id,
+            changes_after(Some(last)),
+        )
+        .await
+        .unwrap();
+        assert!(feed.changes.is_empty());
+
+        // Neither the device's own keys nor non-contacts show up
+        let feed = ListIdentityChangesUseCase::execute(
+            &db,
+            bob.user_id,
+            bob.device_id,
+            changes_after(None),
+        )
+        .await
+        .unwrap();
+        assert!(feed.changes.is_empty());
+        let feed = ListIdentityChangesUseCase::execute(
+            &db,
+            stranger.user_id,
+            stranger.device_id,
+            changes_after(None),
+        )
+        .await
+        .unwrap();
+        assert!(feed.changes.is_empty());
+
+        delete_users(&db, &[&alice, &bob, &stranger]).await;
+    });
+}
@@ -0,0 +1,251 @@
+//! Enforcement of suspensions and bans. Needs a migrated Postgres:
+//!
+//!     TEST_DATABASE_URL=postgres://postgres@localhost:5432/vyry_test \
+//!     cargo test -p application --test moderation -- --ignored
+
+mod common;
+
+use application::account::PurgeDeletedAccountsUseCase;
+use application::auth::dtos::Claims;
+use application::auth::phone::phone_number_hash;
+use application::auth::AuthenticateUseCase;
+use application::reports::dtos::{ReportResolution, ResolveReportRequest};
+use application::reports::ResolveReportUseCase;
+use application::AppError;
+use chrono::Utc;
+use common::{connect_db, count, create_user, delete_users, run, TestUser};
+use infrastructure::storage::{BlobStore, BlobStoreConfig};
+use jsonwebtoken::{encode, EncodingKey, Header};
+use sea_orm::{ConnectionTrait, DatabaseConnection, DbBackend, Statement};
+use uuid::Uuid;
+
+const JWT_SECRET: &str = "moderation-test-secret";
+
+fn access_token(user: &TestUser) -> String {
+    token_for(user.user_id, user.device_id)
+}
+
+fn token_for(user_id: Uuid, device_id: i64) -> String {
+    let now = Utc::now().timestamp();
+    let claims = Claims {
+        sub: user_id.to_string(),
+        device_id,
+        exp: now + 900,
+        iat: now,
+        token_type: "access".to_string(),
+    };
+    encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(JWT_SECRET.as_bytes()),
+    )
+    .unwrap()
+}
+
+async fn execute(db: &DatabaseConnection, sql: &str, values: Vec<sea_orm::Value>) {
+    db.execute(Statement::from_sql_and_values(
+        DbBackend::Postgres,
+        sql,
+        values,
+    ))
+    .await
+    .unwrap();
+}
+
+async fn create_admin(db: &DatabaseConnection) -> Uuid {
+    let admin_id = Uuid::new_v4();
+    execute(
+        db,
+        "INSERT INTO admins (admin_id, username, password_hash) VALUES ($1, $2, 'x')",
+        vec![
+            admin_id.into(),
+            format!("test-{}", admin_id.simple()).into(),
+        ],
+    )
+    .await;
+    admin_id
+}
+
+/// An open report by `reporter` against `reported`
+async fn create_report(db: &DatabaseConnection, reporter: &TestUser, reported: &TestUser) -> Uuid {
+    let report_id = Uuid::new_v4();
+    execute(
+        db,
+        "INSERT INTO reports (report_id, reporter_id, reported_user_id, reason) VALUES ($1, $2, $3, 1)",
+        vec![report_id.into(), reporter.user_id.into(), reported.user_id.into()],
+    )
+    .await;
+    report_id
+}
+
+async fn resolve(
+    db: &DatabaseConnection,
+    admin_id: Uuid,
+    report_id: Uuid,
+    action: ReportResolution,
+    suspend_seconds: Option<i64>,
+) {
+    let req = ResolveReportRequest {
+        action,
+        note: None,
+        suspend_seconds,
+    };
+    ResolveReportUseCase::execute(db, admin_id, report_id, req)
+        .await
+        .unwrap();
+}
+
+#[test]
+#[ignore = "needs TEST_DATABASE_URL"]
+fn test_moderation_revokes_issued_tokens() {
+    run(async {
+        let db = connect_db().await;
+        let admin_id = create_admin(&db).await;
+        let reporter = create_user(&db).await;
+        let suspended = create_user(&db).await;
+        let banned = create_user(&db).await;
+
+        for user in [&suspended, &banned] {
+            let claims = AuthenticateUseCase::execute(&db, JWT_SECRET, &access_token(user))
+                .await
+                .unwrap();
+            assert_eq!(claims.device_id, user.device_id);
+        }
+
+        let report_id = create_report(&db, &reporter, &suspended).await;
+        resolve(
+            &db,
+            admin_id,
+            report_id,
+            ReportResolution::Suspend,
+            Some(3600),
+        )
+        .await;
+        let report_id = create_report(&db, &reporter, &banned).await;
+        resolve(&db, admin_id, report_id, ReportResolution::Ban, None).await;
+
+        // Tokens issued before the action stop working without waiting to expire
+        for user in [&suspended, &banned] {
+            let result = AuthenticateUseCase::execute(&db, JWT_SECRET, &access_token(user)).await;
+            assert!(
+                matches!(result, Err(AppError::Authorization(_))),
+                "{:?}",
+                result
+            );
+        }
+
+        // Even with the device reactivated the account state still applies
+        execute(
+            &db,
+            "UPDATE devices SET is_active = true WHERE user_id = $1",
+            vec![banned.user_id.into()],
+        )
+        .await;
+        let result = AuthenticateUseCase::execute(&db, JWT_SECRET, &access_token(&banned)).await;
+        match result {
+            Err(AppError::Authorization(msg)) => assert!(msg.contains("banned"), "{}", msg),
+            other => panic!("expected a ban, got {:?}", other),
+        }
+
+        // A token naming another user's device is rejected like a revoked one
+        let result = AuthenticateUseCase::execute(
+            &db,
+            JWT_SECRET,
+            &token_for(reporter.user_id, banned.device_id),
+        )
+        .await;
+        assert!(
+            matches!(result, Err(AppError::Authorization(_))),
+            "{:?}",
+            result
+        );
+
+        delete_users(&db, &[&reporter, &suspended, &banned]).await;
+        execute(
+            &db,
+            "DELETE FROM admins WHERE admin_id = $1",
+            vec![admin_id.into()],
+        )
+        .await;
+    });
+}
+
+#[test]
+#[ignore = "needs TEST_DATABASE_URL"]
+fn test_purge_keeps_moderation_history() {
+    run(async {
+        let db = connect_db().await;
+        let admin_id = create_admin(&db).await;
+        let reporter = create_user(&db).await;
+        let banned = create_user(&db).await;
+
+        let report_id = create_report(&db, &reporter, &banned).await;
+        resolve(&db, admin_id, report_id, ReportResolution::Ban, None).await;
+
+        for user in [&reporter, &banned] {
+            execute(
+                &db,
+                "UPDATE users SET is_deleted = true, deleted_at = now() - interval '1 day'
+                 WHERE user_id = $1",
+                vec![user.user_id.into()],
+            )
+            .await;
+        }
+        let root = std::env::temp_dir().join(format!("vyry-test-{}", Uuid::new_v4()));
+        let blob_store = BlobStore::new(&BlobStoreConfig::Local {
+            root: root.to_string_lossy().into_owned(),
+        })
+        .unwrap();
+        PurgeDeletedAccountsUseCase::execute(&db, &blob_store, 0)
+            .await
+            .unwrap();
+
+        // The report survives with its reporter detached
+        let anonymous = count(
+            &db,
+            "SELECT count(*) AS count FROM reports WHERE report_id = $1 AND reporter_id IS NULL",
+            report_id,
+        )
+        .await;
+        assert_eq!(anonymous, 1);
+        let actions = count(
+            &db,
+            "SELECT count(*) AS count FROM moderation_actions WHERE user_id = $1",
+            banned.user_id,
+        )
+        .await;
+        assert_eq!(actions, 1);
+
+        // The freed number stays banned; the reporter's does not
+        let banned_numbers = db
+            .query_all(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                "SELECT phone_hash FROM banned_phone_numbers WHERE user_id = ANY($1)",
+                [vec![reporter.user_id, banned.user_id].into()],
+            ))
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|row| row.try_get::<Vec<u8>>("", "phone_hash").unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            banned_numbers,
+            vec![phone_number_hash(&banned.phone_number)]
+        );
+
+        execute(
+            &db,
+            "DELETE FROM banned_phone_numbers WHERE user_id = $1",
+            vec![banned.user_id.into()],
+        )
+        .await;
+        delete_users(&db, &[&reporter, &banned]).await;
+        execute(
+            &db,
+            "DELETE FROM admins WHERE admin_id = $1",
+            vec![admin_id.into()],
+        )
+        .await;
+        let _ = std::fs::remove_dir_all(root);
+    });
+}
@@ -0,0 +1,110 @@
+//! Concurrency test for one-time prekey claiming. Needs a migrated Postgres:
+//!
+//!     TEST_DATABASE_URL=postgres://postgres@localhost:5432/vyry_test \
+//!     cargo test -p application --test prekey_claims -- --ignored
+
+use application::keys::use_cases::{claim_one_time_prekey, GetPreKeyBundleUseCase};
+use application::transparency::LogSigner;
+use sea_orm::{ConnectOptions, ConnectionTrait, Database, DatabaseConnection, DbBackend, Statement};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+const PREKEYS: i32 = 200;
+const FETCHERS: usize = 400;
+
+async fn create_device(db: &DatabaseConnection, user_id: Uuid) -> i64 {
+    db.execute(Statement::from_sql_and_values(
+        DbBackend::Postgres,
+        "INSERT INTO users (user_id, phone_number, phone_number_hash) VALUES ($1, $2, $3)",
+        [
+            user_id.into(),
+            format!("test:{}", user_id).into(),
+            user_id.as_bytes().to_vec().into(),
+        ],
+    ))
+    .await
+    .unwrap();
+
+    let row = db
+        .query_one(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            "INSERT INTO devices (user_id, device_uuid, platform, identity_key_public, registration_id,
+                                  signed_prekey_id, signed_prekey_public, signed_prekey_signature)
+             VALUES ($1, $2, 1, $3, 1, 1, $3, $4)
+             RETURNING device_id",
+            [
+                user_id.into(),
+                Uuid::new_v4().into(),
+                vec![1u8; 32].into(),
+                vec![2u8; 64].into(),
+            ],
+        ))
+        .await
+        .unwrap()
+        .unwrap();
+    let device_id: i64 = row.try_get("", "device_id").unwrap();
+
+    db.execute(Statement::from_sql_and_values(
+        DbBackend::Postgres,
+        "INSERT INTO one_time_prekeys (device_id, prekey_id, public_key)
+         SELECT $1, id, decode(lpad(to_hex(id), 64, '0'), 'hex') FROM generate_series(1, $2) AS id",
+        [device_id.into(), PREKEYS.into()],
+    ))
+    .await
+    .unwrap();
+
+    device_id
+}
+
+#[test]
+#[ignore = "needs TEST_DATABASE_URL"]
+fn test_concurrent_fetchers_never_share_a_prekey() {
+    let url = std::env::var("TEST_DATABASE_URL").expect("TEST_DATABASE_URL must be set");
+
+    tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .unwrap()
+        .block_on(async {
+            let mut options = ConnectOptions::new(url);
+            options.max_connections(32).sqlx_logging(false);
+            let db = Database::connect(options).await.unwrap();
+
+            let user_id = Uuid::new_v4();
+            let device_id = create_device(&db, user_id).await;
+            let signer = LogSigner::new(None).unwrap();
+
+            let fetchers: Vec<_> = (0..FETCHERS)
+                .map(|_| {
+                    let db = db.clone();
+                    let signer = signer.clone();
+                    tokio::spawn(async move {
+                        GetPreKeyBundleUseCase::execute(&db, &signer, user_id, device_id)
+                            .await
+                            .unwrap()
+                            .one_time_prekey
+                            .map(|prekey| prekey.id)
+                    })
+                })
+                .collect();
+
+            let mut claimed = HashSet::new();
+            for fetcher in fetchers {
+                if let Some(id) = fetcher.await.unwrap() {
+                    assert!(claimed.insert(id), "prekey {} was handed out twice", id);
+                }
+            }
+
+            // Every key was handed out exactly once and none are left
+            assert_eq!(claimed.len(), PREKEYS as usize);
+            assert!(claim_one_time_prekey(&db, device_id).await.unwrap().is_none());
+
+            db.execute(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                "DELETE FROM users WHERE user_id = $1",
+                [user_id.into()],
+            ))
+            .await
+            .unwrap();
+        });
+}
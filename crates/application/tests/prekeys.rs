@@ -0,0 +1,186 @@
+//! One-time prekey replenishment. Needs a migrated Postgres:
+//!
+//!     TEST_DATABASE_URL=postgres://postgres@localhost:5432/vyry_test \
+//!     cargo test -p application --test prekeys -- --ignored
+
+mod common;
+
+use application::keys::dtos::{PreKeyCountResponse, PreKeyUpload, UploadPreKeysRequest};
+use application::keys::use_cases::{
+    GetPreKeyBundleUseCase, GetPreKeyCountUseCase, UploadPreKeysUseCase, PREKEY_LOW_THRESHOLD,
+};
+use application::transparency::LogSigner;
+use application::AppError;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use common::{connect_db, create_user, delete_users, run, TestUser};
+use crypto::generate::generate_prekeys;
+use sea_orm::{ConnectionTrait, DatabaseConnection, DbBackend, Statement};
+use std::ops::Range;
+
+fn upload(ids: Range<u32>) -> UploadPreKeysRequest {
+    UploadPreKeysRequest {
+        one_time_prekeys: generate_prekeys(ids.start, ids.end - ids.start)
+            .into_iter()
+            .map(|prekey| PreKeyUpload {
+                id: prekey.id as i32,
+                public_key: BASE64.encode(prekey.key_pair.public_key.serialize()),
+            })
+            .collect(),
+        pq_prekeys: Vec::new(),
+        pq_last_resort_prekey: None,
+    }
+}
+
+/// Store prekeys `ids` directly, bypassing the per-upload batch limit
+async fn store_prekeys(db: &DatabaseConnection, user: &TestUser, ids: Range<i32>) {
+    db.execute(Statement::from_sql_and_values(
+        DbBackend::Postgres,
+        "INSERT INTO one_time_prekeys (device_id, prekey_id, public_key)
+         SELECT $1, id, decode(lpad(to_hex(id), 64, '0'), 'hex') FROM generate_series($2, $3) AS id",
+        [user.device_id.into(), ids.start.into(), (ids.end - 1).into()],
+    ))
+    .await
+    .unwrap();
+}
+
+#[test]
+fn test_prekey_count_is_low() {
+    let status = |count, pq_count, pq_last_resort| PreKeyCountResponse {
+        count,
+        pq_count,
+        pq_last_resort,
+        low_threshold: PREKEY_LOW_THRESHOLD,
+    };
+
+    assert!(!status(PREKEY_LOW_THRESHOLD, 0, false).is_low());
+    assert!(status(PREKEY_LOW_THRESHOLD - 1, 0, false).is_low());
+    // Kyber keys only count once the device has uploaded a last-resort key
+    assert!(status(PREKEY_LOW_THRESHOLD, 0, true).is_low());
+    assert!(!status(PREKEY_LOW_THRESHOLD, PREKEY_LOW_THRESHOLD, true).is_low());
+}
+
+#[test]
+#[ignore = "needs TEST_DATABASE_URL"]
+fn test_upload_rejects_stored_ids() {
+    run(async {
+        let db = connect_db().await;
+        let user = create_user(&db).await;
+
+        let response =
+            UploadPreKeysUseCase::execute(&db, user.user_id, user.device_id, upload(1..21))
+                .await
+                .unwrap();
+        assert_eq!((response.uploaded, response.count), (20, 20));
+
+        // One overlapping ID rejects the whole batch
+        let result =
+            UploadPreKeysUseCase::execute(&db, user.user_id, user.device_id, upload(20..30)).await;
+        match result {
+            Err(AppError::Validation(msg)) => assert!(msg.contains("Prekey ID 20"), "{}", msg),
+            other => panic!("expected a collision, got {:?}", other),
+        }
+        let status = GetPreKeyCountUseCase::execute(&db, user.device_id)
+            .await
+            .unwrap();
+        assert_eq!(status.count, 20);
+
+        delete_users(&db, &[&user]).await;
+    });
+}
+
+#[test]
+#[ignore = "needs TEST_DATABASE_URL"]
+fn test_upload_is_capped_per_device() {
+    run(async {
+        let db = connect_db().await;
+        let user = create_user(&db).await;
+        store_prekeys(&db, &user, 1..496).await;
+
+        let result =
+            UploadPreKeysUseCase::execute(&db, user.user_id, user.device_id, upload(1000..1010))
+                .await;
+        assert!(
+            matches!(result, Err(AppError::Validation(_))),
+            "{:?}",
+            result
+        );
+
+        // Filling up to the cap exactly is allowed
+        let response =
+            UploadPreKeysUseCase::execute(&db, user.user_id, user.device_id, upload(1000..1005))
+                .await
+                .unwrap();
+        assert_eq!(response.count, 500);
+
+        delete_users(&db, &[&user]).await;
+    });
+}
+
+#[test]
+#[ignore = "needs TEST_DATABASE_URL"]
+fn test_prekey_count_reports_low_devices() {
+    run(async {
+        let db = connect_db().await;
+        let user = create_user(&db).await;
+        let threshold = PREKEY_LOW_THRESHOLD as i32;
+
+        store_prekeys(&db, &user, 1..threshold).await;
+        let status = GetPreKeyCountUseCase::execute(&db, user.device_id)
+            .await
+            .unwrap();
+        assert_eq!(status.count, PREKEY_LOW_THRESHOLD - 1);
+        assert!(status.is_low());
+
+        store_prekeys(&db, &user, threshold..threshold + 1).await;
+        let status = GetPreKeyCountUseCase::execute(&db, user.device_id)
+            .await
+            .unwrap();
+        assert_eq!(status.count, PREKEY_LOW_THRESHOLD);
+        assert!(!status.is_low());
+
+        delete_users(&db, &[&user]).await;
+    });
+}
+
+#[test]
+#[ignore = "needs TEST_DATABASE_URL"]
+fn test_bundle_is_not_served_for_unlinked_devices_or_deleted_users() {
+    run(async {
+        let db = connect_db().await;
+        let signer = LogSigner::new(None).unwrap();
+        let user = create_user(&db).await;
+        store_prekeys(&db, &user, 1..3).await;
+        let fetch = || GetPreKeyBundleUseCase::execute(&db, &signer, user.user_id, user.device_id);
+
+        // An unlinked device, then an active device of a deleted user
+        for (is_active, is_deleted) in [(false, false), (true, true)] {
+            for (sql, value) in [
+                (
+                    "UPDATE devices SET is_active = $2 WHERE user_id = $1",
+                    is_active,
+                ),
+                (
+                    "UPDATE users SET is_deleted = $2 WHERE user_id = $1",
+                    is_deleted,
+                ),
+            ] {
+                db.execute(Statement::from_sql_and_values(
+                    DbBackend::Postgres,
+                    sql,
+                    [user.user_id.into(), value.into()],
+                ))
+                .await
+                .unwrap();
+            }
+            assert!(matches!(fetch().await, Err(AppError::NotFound(_))));
+        }
+
+        // Nothing was claimed while the device was unreachable
+        let status = GetPreKeyCountUseCase::execute(&db, user.device_id)
+            .await
+            .unwrap();
+        assert_eq!(status.count, 2);
+
+        delete_users(&db, &[&user]).await;
+    });
+}
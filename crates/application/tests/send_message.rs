@@ -0,0 +1,110 @@
+//! Who may send into a conversation. Needs a migrated Postgres:
+//!
+//!     TEST_DATABASE_URL=postgres://postgres@localhost:5432/vyry_test \
+//!     cargo test -p application --test send_message -- --ignored
+
+mod common;
+
+use application::chat::use_cases::SendMessageUseCase;
+use application::chat::DeclineMessageRequestUseCase;
+use application::AppError;
+use common::{connect_db, count, create_user, delete_users, message, run};
+use uuid::Uuid;
+
+const STORED_MESSAGES: &str = "SELECT count(*) AS count FROM messages WHERE conv_id = $1";
+
+#[test]
+#[ignore = "needs TEST_DATABASE_URL"]
+fn test_non_member_cannot_send() {
+    run(async {
+        let db = connect_db().await;
+        let alice = create_user(&db).await;
+        let bob = create_user(&db).await;
+        let mallory = create_user(&db).await;
+        let conversation_id = Uuid::new_v4();
+
+        assert!(
+            SendMessageUseCase::execute(&db, message(&alice, &bob, conversation_id))
+                .await
+                .unwrap()
+        );
+
+        // A known conversation id does not let an outsider write into it
+        for recipient in [&alice, &bob] {
+            assert!(matches!(
+                SendMessageUseCase::execute(&db, message(&mallory, recipient, conversation_id))
+                    .await,
+                Err(AppError::NotFound(_))
+            ));
+        }
+        // Nor address someone outside it
+        assert!(matches!(
+            SendMessageUseCase::execute(&db, message(&alice, &mallory, conversation_id)).await,
+            Err(AppError::NotFound(_))
+        ));
+        assert_eq!(count(&db, STORED_MESSAGES, conversation_id).await, 1);
+
+        delete_users(&db, &[&alice, &bob, &mallory]).await;
+    });
+}
+
+#[test]
+#[ignore = "needs TEST_DATABASE_URL"]
+fn test_declined_request_drops_messages() {
+    run(async {
+        let db = connect_db().await;
+        let alice = create_user(&db).await;
+        let bob = create_user(&db).await;
+        let conversation_id = Uuid::new_v4();
+
+        // Strangers: the first message opens a request in bob's inbox
+        assert!(
+            SendMessageUseCase::execute(&db, message(&alice, &bob, conversation_id))
+                .await
+                .unwrap()
+        );
+        DeclineMessageRequestUseCase::execute(&db, bob.user_id, conversation_id, false)
+            .await
+            .unwrap();
+
+        // Reported like a delivery, but nothing more reaches bob
+        assert!(
+            !SendMessageUseCase::execute(&db, message(&alice, &bob, conversation_id))
+                .await
+                .unwrap()
+        );
+        assert_eq!(count(&db, STORED_MESSAGES, conversation_id).await, 1);
+
+        delete_users(&db, &[&alice, &bob]).await;
+    });
+}
+
+#[test]
+#[ignore = "needs TEST_DATABASE_URL"]
+fn test_stranger_cannot_bypass_request_inbox_through_shared_conversation() {
+    run(async {
+        let db = connect_db().await;
+        let mallory = create_user(&db).await;
+        let carol = create_user(&db).await;
+        let bob = create_user(&db).await;
+        let conversation_id = Uuid::new_v4();
+
+        // mallory shares a conversation with carol; bob has never heard of mallory
+        assert!(
+            SendMessageUseCase::execute(&db, message(&mallory, &carol, conversation_id))
+                .await
+                .unwrap()
+        );
+
+        // Aiming at bob's device through carol skips bob's message requests
+        let mut req = message(&mallory, &carol, conversation_id);
+        req.recipient_device_id = bob.device_id;
+        assert!(matches!(
+            SendMessageUseCase::execute(&db, req).await,
+            Err(AppError::NotFound(_))
+        ));
+        assert_eq!(count(&db, STORED_MESSAGES, conversation_id).await, 1);
+
+        delete_users(&db, &[&mallory, &carol, &bob]).await;
+    });
+}
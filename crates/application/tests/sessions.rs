@@ -0,0 +1,295 @@
+//! Session record storage for linked devices. Needs a migrated Postgres:
+//!
+//!     TEST_DATABASE_URL=postgres://postgres@localhost:5432/vyry_test \
+//!     cargo test -p application --test sessions -- --ignored
+
+mod common;
+
+use application::sessions::dtos::{
+    DeleteSessionQuery, ExportSessionsQuery, ImportSessionsRequest, ImportedSession,
+    PutSessionRequest,
+};
+use application::sessions::use_cases::{MAX_SESSIONS_PER_DEVICE, MAX_SESSION_RECORD_BYTES};
+use application::sessions::{
+    DeleteSessionUseCase, ExportSessionsUseCase, GetSessionUseCase, ImportSessionsUseCase,
+    ListSessionsUseCase, PutSessionUseCase,
+};
+use application::AppError;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use common::{connect_db, create_user, delete_users, run, TestUser};
+use sea_orm::{ConnectionTrait, DatabaseConnection, DbBackend, Statement};
+
+fn put(record: &[u8], expected_version: Option<i64>) -> PutSessionRequest {
+    PutSessionRequest {
+        record: BASE64.encode(record),
+        expected_version,
+    }
+}
+
+async fn put_session(
+    db: &DatabaseConnection,
+    user: &TestUser,
+    address: &str,
+    req: PutSessionRequest,
+) -> Result<i64, AppError> {
+    PutSessionUseCase::execute(db, user.user_id, user.device_id, address.to_string(), req)
+        .await
+        .map(|response| response.version)
+}
+
+/// Store `count` records of `size` bytes directly, bypassing the quota
+async fn fill_sessions(db: &DatabaseConnection, user: &TestUser, count: i32, size: i32) {
+    db.execute(Statement::from_sql_and_values(
+        DbBackend::Postgres,
+        "INSERT INTO signal_sessions (device_id, address, session_record, version, updated_at)
+         SELECT $1, 'filler' || n || '.1', decode(repeat('ab', $3), 'hex'), 1, now()
+         FROM generate_series(1, $2) AS n",
+        [user.device_id.into(), count.into(), size.into()],
+    ))
+    .await
+    .unwrap();
+}
+
+#[test]
+#[ignore = "needs TEST_DATABASE_URL"]
+fn test_put_and_delete_check_versions() {
+    run(async {
+        let db = connect_db().await;
+        let user = create_user(&db).await;
+        let address = "alice.1";
+
+        assert_eq!(
+            put_session(&db, &user, address, put(b"v1", None))
+                .await
+                .unwrap(),
+            1
+        );
+
+        // Creating it again, or updating from a stale version, conflicts
+        let result = put_session(&db, &user, address, put(b"v1 again", None)).await;
+        assert!(matches!(result, Err(AppError::Conflict(_))), "{:?}", result);
+        assert_eq!(
+            put_session(&db, &user, address, put(b"v2", Some(1)))
+                .await
+                .unwrap(),
+            2
+        );
+        let result = put_session(&db, &user, address, put(b"lost update", Some(1))).await;
+        assert!(matches!(result, Err(AppError::Conflict(_))), "{:?}", result);
+
+        let session =
+            GetSessionUseCase::execute(&db, user.user_id, user.device_id, address.to_string())
+                .await
+                .unwrap();
+        assert_eq!(
+            (session.version, BASE64.decode(session.record).unwrap()),
+            (2, b"v2".to_vec())
+        );
+
+        let result = DeleteSessionUseCase::execute(
+            &db,
+            user.user_id,
+            user.device_id,
+            address.to_string(),
+            DeleteSessionQuery { version: Some(1) },
+        )
+        .await;
+        assert!(matches!(result, Err(AppError::Conflict(_))), "{:?}", result);
+        let deleted = DeleteSessionUseCase::execute(
+            &db,
+            user.user_id,
+            user.device_id,
+            address.to_string(),
+            DeleteSessionQuery { version: Some(2) },
+        )
+        .await
+        .unwrap();
+        assert!(deleted.deleted);
+
+        // Updating a record that was deleted meanwhile conflicts too
+        let result = put_session(&db, &user, address, put(b"v3", Some(2))).await;
+        assert!(matches!(result, Err(AppError::Conflict(_))), "{:?}", result);
+
+        delete_users(&db, &[&user]).await;
+    });
+}
+
+#[test]
+#[ignore = "needs TEST_DATABASE_URL"]
+fn test_session_quota_per_device() {
+    run(async {
+        let db = connect_db().await;
+        let full = create_user(&db).await;
+        let large = create_user(&db).await;
+
+        fill_sessions(&db, &full, MAX_SESSIONS_PER_DEVICE as i32, 1).await;
+        let result = put_session(&db, &full, "alice.1", put(b"one too many", None)).await;
+        assert!(
+            matches!(result, Err(AppError::Validation(_))),
+            "{:?}",
+            result
+        );
+
+        // Replacing an existing record does not add to the count
+        let result = put_session(&db, &full, "filler1.1", put(b"replaced", Some(1))).await;
+        assert_eq!(result.unwrap(), 2);
+
+        // 256 records of 64 KiB fill the byte quota exactly
+        fill_sessions(&db, &large, 256, MAX_SESSION_RECORD_BYTES as i32).await;
+        let usage = ListSessionsUseCase::execute(&db, large.user_id, large.device_id)
+            .await
+            .unwrap();
+        assert_eq!(usage.stored_bytes, usage.max_stored_bytes);
+        let result = put_session(&db, &large, "alice.1", put(b"x", None)).await;
+        assert!(
+            matches!(result, Err(AppError::Validation(_))),
+            "{:?}",
+            result
+        );
+
+        delete_users(&db, &[&full, &large]).await;
+    });
+}
+
+#[test]
+#[ignore = "needs TEST_DATABASE_URL"]
+fn test_export_import_round_trip() {
+    run(async {
+        let db = connect_db().await;
+        let user = create_user(&db).await;
+        let old_device = create_user(&db).await;
+        let donor = create_user(&db).await;
+        // Move the donor's device over to the same account as a second device
+        db.execute(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            "UPDATE devices SET user_id = $1 WHERE device_id = $2",
+            [old_device.user_id.into(), donor.device_id.into()],
+        ))
+        .await
+        .unwrap();
+        let new_device = TestUser {
+            user_id: old_device.user_id,
+            device_id: donor.device_id,
+            phone_number: old_device.phone_number.clone(),
+            username: old_device.username.clone(),
+        };
+
+        for n in 0..7u8 {
+            let address = format!("peer{}.1", n);
+            put_session(&db, &old_device, &address, put(&[n; 40], None))
+                .await
+                .unwrap();
+        }
+
+        // Page through the export and import each page on the new device
+        let mut after = None;
+        let mut pages = 0;
+        loop {
+            let page = ExportSessionsUseCase::execute(
+                &db,
+                old_device.user_id,
+                old_device.device_id,
+                ExportSessionsQuery {
+                    limit: Some(3),
+                    after,
+                },
+            )
+            .await
+            .unwrap();
+            pages += 1;
+
+            let imported = ImportSessionsUseCase::execute(
+                &db,
+                new_device.user_id,
+                new_device.device_id,
+                ImportSessionsRequest {
+                    sessions: page
+                        .sessions
+                        .into_iter()
+                        .map(|session| ImportedSession {
+                            address: session.address,
+                            record: session.record,
+                        })
+                        .collect(),
+                    overwrite: false,
+                },
+            )
+            .await
+            .unwrap();
+            assert_eq!(imported.replaced, 0);
+
+            match page.next_after {
+                Some(next) => after = Some(next),
+                None => break,
+            }
+        }
+        assert_eq!(pages, 3);
+
+        let list =
+            |device: &TestUser| ListSessionsUseCase::execute(&db, device.user_id, device.device_id);
+        let (old, new) = (
+            list(&old_device).await.unwrap(),
+            list(&new_device).await.unwrap(),
+        );
+        assert_eq!(new.total, 7);
+        assert_eq!(new.stored_bytes, old.stored_bytes);
+        let record = GetSessionUseCase::execute(
+            &db,
+            new_device.user_id,
+            new_device.device_id,
+            "peer4.1".to_string(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(BASE64.decode(record.record).unwrap(), vec![4u8; 40]);
+
+        // Importing the same batch again conflicts unless overwriting
+        let again = |overwrite| ImportSessionsRequest {
+            sessions: vec![ImportedSession {
+                address: "peer4.1".to_string(),
+                record: BASE64.encode([9u8; 40]),
+            }],
+            overwrite,
+        };
+        let result = ImportSessionsUseCase::execute(
+            &db,
+            new_device.user_id,
+            new_device.device_id,
+            again(false),
+        )
+        .await;
+        assert!(matches!(result, Err(AppError::Conflict(_))), "{:?}", result);
+        let replaced = ImportSessionsUseCase::execute(
+            &db,
+            new_device.user_id,
+            new_device.device_id,
+            again(true),
+        )
+        .await
+        .unwrap();
+        assert_eq!((replaced.created, replaced.replaced), (0, 1));
+
+        // Another account cannot read the records through the device id
+        let result = ExportSessionsUseCase::execute(
+            &db,
+            user.user_id,
+            old_device.device_id,
+            ExportSessionsQuery {
+                limit: None,
+                after: None,
+            },
+        )
+        .await;
+        assert!(matches!(result, Err(AppError::NotFound(_))), "{:?}", result);
+        let result = GetSessionUseCase::execute(
+            &db,
+            user.user_id,
+            old_device.device_id,
+            "peer4.1".to_string(),
+        )
+        .await;
+        assert!(matches!(result, Err(AppError::NotFound(_))), "{:?}", result);
+
+        delete_users(&db, &[&user, &old_device, &donor]).await;
+    });
+}
@@ -0,0 +1,183 @@
+//! Signed prekey rotation and its grace period. Needs a migrated Postgres:
+//!
+//!     TEST_DATABASE_URL=postgres://postgres@localhost:5432/vyry_test \
+//!     cargo test -p application --test signed_prekeys -- --ignored
+
+mod common;
+
+use application::keys::dtos::{SignedPreKeyStatusResponse, SignedPreKeyUpload};
+use application::keys::use_cases::{
+    ExpirePreviousSignedPreKeysUseCase, GetSignedPreKeyStatusUseCase, RotateSignedPreKeyUseCase,
+};
+use application::AppError;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use common::{connect_db, create_user, delete_users, run, TestUser};
+use crypto::generate::{generate_identity_keypair, generate_signed_prekey};
+use crypto::KeyPair;
+use sea_orm::{ConnectionTrait, DatabaseConnection, DbBackend, Statement};
+
+const DAY: i64 = 86400;
+
+/// Give the test device a real identity key so rotations can be signed
+async fn set_identity_key(db: &DatabaseConnection, user: &TestUser) -> KeyPair {
+    let identity = generate_identity_keypair();
+    db.execute(Statement::from_sql_and_values(
+        DbBackend::Postgres,
+        "UPDATE devices SET identity_key_public = $2 WHERE device_id = $1",
+        [
+            user.device_id.into(),
+            identity.public_key.public_key_bytes().to_vec().into(),
+        ],
+    ))
+    .await
+    .unwrap();
+    identity
+}
+
+fn signed_prekey(identity: &KeyPair, id: u32) -> SignedPreKeyUpload {
+    let signed_prekey = generate_signed_prekey(identity, id);
+    SignedPreKeyUpload {
+        id: signed_prekey.id as i32,
+        public_key: BASE64.encode(signed_prekey.key_pair.public_key.serialize()),
+        signature: BASE64.encode(&signed_prekey.signature),
+    }
+}
+
+async fn rotate(
+    db: &DatabaseConnection,
+    user: &TestUser,
+    grace_seconds: i64,
+    upload: SignedPreKeyUpload,
+) -> Result<SignedPreKeyStatusResponse, AppError> {
+    RotateSignedPreKeyUseCase::execute(db, user.user_id, user.device_id, grace_seconds, upload)
+        .await
+}
+
+#[test]
+#[ignore = "needs TEST_DATABASE_URL"]
+fn test_rotation_keeps_previous_key_for_grace_period() {
+    run(async {
+        let db = connect_db().await;
+        let user = create_user(&db).await;
+        let identity = set_identity_key(&db, &user).await;
+
+        let status = rotate(&db, &user, DAY, signed_prekey(&identity, 2))
+            .await
+            .unwrap();
+        assert_eq!(status.id, 2);
+        assert_eq!(status.previous_id, Some(1));
+        assert!(status.previous_expires_at.is_some());
+
+        // A key signed by another identity is refused
+        let other = generate_identity_keypair();
+        let result = rotate(&db, &user, DAY, signed_prekey(&other, 3)).await;
+        assert!(
+            matches!(result, Err(AppError::Validation(_))),
+            "{:?}",
+            result
+        );
+
+        // Reusing the ID of the key still in its grace period is refused
+        let result = rotate(&db, &user, DAY, signed_prekey(&identity, 1)).await;
+        assert!(
+            matches!(result, Err(AppError::Validation(_))),
+            "{:?}",
+            result
+        );
+
+        // Still inside the grace window, so the job keeps it
+        ExpirePreviousSignedPreKeysUseCase::execute(&db)
+            .await
+            .unwrap();
+        let status = GetSignedPreKeyStatusUseCase::execute(&db, user.device_id, DAY)
+            .await
+            .unwrap();
+        assert_eq!(status.previous_id, Some(1));
+
+        delete_users(&db, &[&user]).await;
+    });
+}
+
+#[test]
+#[ignore = "needs TEST_DATABASE_URL"]
+fn test_previous_key_expires_after_grace_period() {
+    run(async {
+        let db = connect_db().await;
+        let user = create_user(&db).await;
+        let identity = set_identity_key(&db, &user).await;
+
+        // With no grace the previous key is hidden before the job clears it
+        rotate(&db, &user, 0, signed_prekey(&identity, 2))
+            .await
+            .unwrap();
+        let status = GetSignedPreKeyStatusUseCase::execute(&db, user.device_id, DAY)
+            .await
+            .unwrap();
+        assert_eq!(
+            (status.previous_id, status.previous_expires_at),
+            (None, None)
+        );
+
+        assert!(
+            ExpirePreviousSignedPreKeysUseCase::execute(&db)
+                .await
+                .unwrap()
+                >= 1
+        );
+        let cleared = db
+            .query_one(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                "SELECT previous_signed_prekey_id IS NULL AND previous_signed_prekey_public IS NULL
+                        AND previous_signed_prekey_expires_at IS NULL AS cleared
+                 FROM devices WHERE device_id = $1",
+                [user.device_id.into()],
+            ))
+            .await
+            .unwrap()
+            .unwrap()
+            .try_get::<bool>("", "cleared")
+            .unwrap();
+        assert!(cleared);
+
+        // Once cleared, the old ID may be used again
+        rotate(&db, &user, DAY, signed_prekey(&identity, 1))
+            .await
+            .unwrap();
+
+        delete_users(&db, &[&user]).await;
+    });
+}
+
+#[test]
+#[ignore = "needs TEST_DATABASE_URL"]
+fn test_signed_prekey_status_flags_stale_keys() {
+    run(async {
+        let db = connect_db().await;
+        let user = create_user(&db).await;
+
+        let status = GetSignedPreKeyStatusUseCase::execute(&db, user.device_id, DAY)
+            .await
+            .unwrap();
+        assert_eq!(status.id, 1);
+        assert!(!status.rotation_due);
+
+        db.execute(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            "UPDATE devices SET signed_prekey_created_at = now() - interval '2 days'
+             WHERE device_id = $1",
+            [user.device_id.into()],
+        ))
+        .await
+        .unwrap();
+        let status = GetSignedPreKeyStatusUseCase::execute(&db, user.device_id, DAY)
+            .await
+            .unwrap();
+        assert!(status.rotation_due);
+        let status = GetSignedPreKeyStatusUseCase::execute(&db, user.device_id, 7 * DAY)
+            .await
+            .unwrap();
+        assert!(!status.rotation_due);
+
+        delete_users(&db, &[&user]).await;
+    });
+}
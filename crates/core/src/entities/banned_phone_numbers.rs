@@ -0,0 +1,18 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "banned_phone_numbers")]
+pub struct Model {
+    /// SHA-256 of the E.164 number
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub phone_hash: Vec<u8>,
+    /// Not a foreign key: the purged account it was banned on
+    pub user_id: Uuid,
+    pub banned_at: DateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,39 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "blocks")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub blocker_id: Uuid,
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub blocked_id: Uuid,
+    pub created_at: DateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::users::Entity",
+        from = "Column::BlockerId",
+        to = "super::users::Column::UserId",
+        on_delete = "Cascade"
+    )]
+    Blocker,
+    #[sea_orm(
+        belongs_to = "super::users::Entity",
+        from = "Column::BlockedId",
+        to = "super::users::Column::UserId",
+        on_delete = "Cascade"
+    )]
+    Blocked,
+}
+
+/// A block's related user is the one being blocked
+impl Related<super::users::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Blocked.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
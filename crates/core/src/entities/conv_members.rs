@@ -1,6 +1,33 @@
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
 
+/// Whether a member has agreed to talk in the conversation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RequestStatus {
+    Accepted = 0,
+    Pending = 1,
+    Declined = 2,
+}
+
+impl TryFrom<i16> for RequestStatus {
+    type Error = i16;
+
+    fn try_from(v: i16) -> Result<Self, Self::Error> {
+        match v {
+            0 => Ok(RequestStatus::Accepted),
+            1 => Ok(RequestStatus::Pending),
+            2 => Ok(RequestStatus::Declined),
+            other => Err(other),
+        }
+    }
+}
+
+impl From<RequestStatus> for i16 {
+    fn from(s: RequestStatus) -> Self {
+        s as i16
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
 #[sea_orm(table_name = "conv_members")]
 pub struct Model {
@@ -11,6 +38,15 @@ pub struct Model {
     pub role: i16,
     pub joined_at: DateTimeWithTimeZone,
     pub left_at: Option<DateTimeWithTimeZone>,
+    pub request_status: i16, // 0 = accepted, 1 = pending message request, 2 = declined
+}
+
+impl Model {
+    /// Unknown values are treated as a pending request, which withholds
+    /// receipts and typing indicators rather than granting them
+    pub fn request_status(&self) -> RequestStatus {
+        RequestStatus::try_from(self.request_status).unwrap_or(RequestStatus::Pending)
+    }
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
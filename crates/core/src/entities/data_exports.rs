@@ -0,0 +1,68 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Status of a personal data export
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportStatus {
+    Pending = 1,
+    Ready = 2,
+    Failed = 3,
+    Expired = 4,
+}
+
+impl From<i16> for ExportStatus {
+    fn from(v: i16) -> Self {
+        match v {
+            2 => ExportStatus::Ready,
+            3 => ExportStatus::Failed,
+            4 => ExportStatus::Expired,
+            _ => ExportStatus::Pending,
+        }
+    }
+}
+
+impl From<ExportStatus> for i16 {
+    fn from(s: ExportStatus) -> Self {
+        s as i16
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "data_exports")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub export_id: Uuid,
+    pub user_id: Uuid,
+    pub status: i16, // 1 = pending, 2 = ready, 3 = failed, 4 = expired
+    pub blob_key: Option<String>,
+    pub size_bytes: Option<i64>,
+    pub created_at: DateTimeWithTimeZone,
+    pub completed_at: Option<DateTimeWithTimeZone>,
+    pub expires_at: Option<DateTimeWithTimeZone>,
+}
+
+impl Model {
+    pub fn export_status(&self) -> ExportStatus {
+        ExportStatus::from(self.status)
+    }
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::users::Entity",
+        from = "Column::UserId",
+        to = "super::users::Column::UserId",
+        on_delete = "Cascade"
+    )]
+    Users,
+}
+
+impl Related<super::users::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Users.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -39,6 +39,8 @@ pub struct Model {
     pub status: i16, // 1 = pending, 2 = approved, 3 = expired, 4 = rejected
     pub new_device_uuid: Option<Uuid>,
     pub new_device_name: Option<String>,
+    /// Public keys uploaded by the new device, applied on approval
+    pub new_device_keys: Option<Json>,
     pub expires_at: DateTimeWithTimeZone,
     pub created_at: DateTimeWithTimeZone,
     pub approved_at: Option<DateTimeWithTimeZone>,
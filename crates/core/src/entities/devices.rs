@@ -16,6 +16,12 @@ pub struct Model {
     pub signed_prekey_id: i32,
     pub signed_prekey_public: Vec<u8>,
     pub signed_prekey_signature: Vec<u8>,
+    pub signed_prekey_created_at: DateTimeWithTimeZone,
+    // Signed prekey replaced by the last rotation, kept until the grace period ends
+    pub previous_signed_prekey_id: Option<i32>,
+    pub previous_signed_prekey_public: Option<Vec<u8>>,
+    pub previous_signed_prekey_signature: Option<Vec<u8>>,
+    pub previous_signed_prekey_expires_at: Option<DateTimeWithTimeZone>,
     pub last_seen_at: DateTimeWithTimeZone,
     pub created_at: DateTimeWithTimeZone,
     // Device type and linking fields
@@ -35,6 +41,8 @@ pub enum Relation {
         on_delete = "Cascade"
     )]
     Users,
+    #[sea_orm(has_many = "super::kyber_prekeys::Entity")]
+    KyberPrekeys,
     #[sea_orm(has_many = "super::one_time_prekeys::Entity")]
     OneTimePrekeys,
     #[sea_orm(has_many = "super::signal_sessions::Entity")]
@@ -47,6 +55,12 @@ impl Related<super::users::Entity> for Entity {
     }
 }
 
+impl Related<super::kyber_prekeys::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::KyberPrekeys.def()
+    }
+}
+
 impl Related<super::one_time_prekeys::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::OneTimePrekeys.def()
@@ -0,0 +1,19 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "key_transparency_leaves")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub leaf_index: i64,
+    pub user_id: Uuid,
+    /// Not a foreign key: the log outlives the device
+    pub device_id: i64,
+    pub identity_key: Vec<u8>,
+    pub created_at: DateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
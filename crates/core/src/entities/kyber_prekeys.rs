@@ -0,0 +1,38 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "kyber_prekeys")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub device_id: i64,
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub prekey_id: i32,
+    /// ML-KEM-1024 encapsulation key
+    pub public_key: Vec<u8>,
+    /// Identity key signature over `public_key`
+    pub signature: Vec<u8>,
+    /// Handed out when no one-time key is left, and never deleted by a claim
+    pub is_last_resort: bool,
+    pub created_at: DateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::devices::Entity",
+        from = "Column::DeviceId",
+        to = "super::devices::Column::DeviceId",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    Devices,
+}
+
+impl Related<super::devices::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Devices.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
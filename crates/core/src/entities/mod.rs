@@ -1,12 +1,25 @@
 pub mod prelude;
 
+pub mod admins;
+pub mod audit_logs;
+pub mod banned_phone_numbers;
+pub mod blocks;
 pub mod conv_members;
 pub mod conversations;
+pub mod data_exports;
 pub mod device_linking_sessions;
 pub mod devices;
+pub mod encrypted_profiles;
+pub mod identity_key_history;
+pub mod key_transparency_leaves;
+pub mod key_transparency_nodes;
+pub mod key_transparency_tree_heads;
+pub mod kyber_prekeys;
 pub mod message_deliveries;
 pub mod messages;
+pub mod moderation_actions;
 pub mod one_time_prekeys;
 pub mod push_tokens;
+pub mod reports;
 pub mod signal_sessions;
 pub mod users;
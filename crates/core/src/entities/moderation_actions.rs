@@ -0,0 +1,80 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// What a moderator did to an account
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ModerationActionType {
+    Warn = 1,
+    Suspend = 2,
+    Ban = 3,
+}
+
+impl From<i16> for ModerationActionType {
+    fn from(v: i16) -> Self {
+        match v {
+            2 => ModerationActionType::Suspend,
+            3 => ModerationActionType::Ban,
+            _ => ModerationActionType::Warn,
+        }
+    }
+}
+
+impl From<ModerationActionType> for i16 {
+    fn from(a: ModerationActionType) -> Self {
+        a as i16
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "moderation_actions")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub action_id: Uuid,
+    pub user_id: Uuid,
+    pub admin_id: Option<Uuid>,
+    pub report_id: Option<Uuid>,
+    pub action: i16, // 1 = warn, 2 = suspend, 3 = ban
+    pub reason: Option<String>,
+    pub expires_at: Option<DateTimeWithTimeZone>, // End of a suspension
+    pub created_at: DateTimeWithTimeZone,
+}
+
+impl Model {
+    pub fn action_type(&self) -> ModerationActionType {
+        ModerationActionType::from(self.action)
+    }
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::users::Entity",
+        from = "Column::UserId",
+        to = "super::users::Column::UserId",
+        on_delete = "Cascade"
+    )]
+    Users,
+    #[sea_orm(
+        belongs_to = "super::admins::Entity",
+        from = "Column::AdminId",
+        to = "super::admins::Column::AdminId",
+        on_delete = "SetNull"
+    )]
+    Admins,
+    #[sea_orm(
+        belongs_to = "super::reports::Entity",
+        from = "Column::ReportId",
+        to = "super::reports::Column::ReportId",
+        on_delete = "SetNull"
+    )]
+    Reports,
+}
+
+impl Related<super::users::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Users.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
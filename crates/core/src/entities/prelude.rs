@@ -1,9 +1,22 @@
+pub use super::admins::Entity as Admins;
+pub use super::audit_logs::Entity as AuditLogs;
+pub use super::banned_phone_numbers::Entity as BannedPhoneNumbers;
+pub use super::blocks::Entity as Blocks;
 pub use super::conv_members::Entity as ConvMembers;
 pub use super::conversations::Entity as Conversations;
+pub use super::data_exports::Entity as DataExports;
 pub use super::devices::Entity as Devices;
+pub use super::encrypted_profiles::Entity as EncryptedProfiles;
+pub use super::identity_key_history::Entity as IdentityKeyHistory;
+pub use super::key_transparency_leaves::Entity as KeyTransparencyLeaves;
+pub use super::key_transparency_nodes::Entity as KeyTransparencyNodes;
+pub use super::key_transparency_tree_heads::Entity as KeyTransparencyTreeHeads;
+pub use super::kyber_prekeys::Entity as KyberPrekeys;
 pub use super::message_deliveries::Entity as MessageDeliveries;
 pub use super::messages::Entity as Messages;
+pub use super::moderation_actions::Entity as ModerationActions;
 pub use super::one_time_prekeys::Entity as OneTimePrekeys;
 pub use super::push_tokens::Entity as PushTokens;
+pub use super::reports::Entity as Reports;
 pub use super::signal_sessions::Entity as SignalSessions;
 pub use super::users::Entity as Users;
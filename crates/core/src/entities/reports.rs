@@ -0,0 +1,131 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Why a user filed a report
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportReason {
+    Spam = 1,
+    Harassment = 2,
+    Impersonation = 3,
+    IllegalContent = 4,
+    Other = 5,
+}
+
+impl From<i16> for ReportReason {
+    fn from(v: i16) -> Self {
+        match v {
+            1 => ReportReason::Spam,
+            2 => ReportReason::Harassment,
+            3 => ReportReason::Impersonation,
+            4 => ReportReason::IllegalContent,
+            _ => ReportReason::Other,
+        }
+    }
+}
+
+impl From<ReportReason> for i16 {
+    fn from(r: ReportReason) -> Self {
+        r as i16
+    }
+}
+
+/// Where a report is in the moderation queue
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportStatus {
+    Open = 1,
+    Actioned = 2,
+    Dismissed = 3,
+}
+
+impl From<i16> for ReportStatus {
+    fn from(v: i16) -> Self {
+        match v {
+            2 => ReportStatus::Actioned,
+            3 => ReportStatus::Dismissed,
+            _ => ReportStatus::Open,
+        }
+    }
+}
+
+impl From<ReportStatus> for i16 {
+    fn from(s: ReportStatus) -> Self {
+        s as i16
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "reports")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub report_id: Uuid,
+    /// `None` once the reporter's account has been purged
+    pub reporter_id: Option<Uuid>,
+    pub reported_user_id: Option<Uuid>,
+    pub conversation_id: Option<Uuid>,
+    pub reason: i16, // 1 = spam, 2 = harassment, 3 = impersonation, 4 = illegal content, 5 = other
+    pub comment: Option<String>,
+    pub evidence: Json,
+    pub status: i16, // 1 = open, 2 = actioned, 3 = dismissed
+    pub resolved_by: Option<Uuid>,
+    pub resolved_at: Option<DateTimeWithTimeZone>,
+    pub resolution_note: Option<String>,
+    pub created_at: DateTimeWithTimeZone,
+}
+
+impl Model {
+    pub fn reason(&self) -> ReportReason {
+        ReportReason::from(self.reason)
+    }
+
+    pub fn report_status(&self) -> ReportStatus {
+        ReportStatus::from(self.status)
+    }
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::users::Entity",
+        from = "Column::ReporterId",
+        to = "super::users::Column::UserId",
+        on_delete = "SetNull"
+    )]
+    Reporter,
+    #[sea_orm(
+        belongs_to = "super::users::Entity",
+        from = "Column::ReportedUserId",
+        to = "super::users::Column::UserId",
+        on_delete = "Cascade"
+    )]
+    ReportedUser,
+    #[sea_orm(
+        belongs_to = "super::conversations::Entity",
+        from = "Column::ConversationId",
+        to = "super::conversations::Column::ConvId",
+        on_delete = "SetNull"
+    )]
+    Conversations,
+    #[sea_orm(
+        belongs_to = "super::admins::Entity",
+        from = "Column::ResolvedBy",
+        to = "super::admins::Column::AdminId",
+        on_delete = "SetNull"
+    )]
+    Admins,
+}
+
+impl Related<super::conversations::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Conversations.def()
+    }
+}
+
+impl Related<super::admins::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Admins.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
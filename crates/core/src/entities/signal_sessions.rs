@@ -9,6 +9,7 @@ pub struct Model {
     #[sea_orm(primary_key, auto_increment = false)]
     pub address: String,
     pub session_record: Vec<u8>,
+    pub version: i64,
     pub updated_at: DateTimeWithTimeZone,
 }
 
@@ -20,6 +20,11 @@ pub struct Model {
     pub is_online: bool,
     pub is_deleted: bool,
     pub deleted_at: Option<DateTimeWithTimeZone>,
+    // Set when a deleted account's data has been purged after its grace period
+    pub purged_at: Option<DateTimeWithTimeZone>,
+    // Moderation: login is refused until `suspended_until`, or for good once banned
+    pub suspended_until: Option<DateTimeWithTimeZone>,
+    pub banned_at: Option<DateTimeWithTimeZone>,
     pub created_at: DateTimeWithTimeZone,
     pub updated_at: DateTimeWithTimeZone,
     // PIN/2FA fields
@@ -27,6 +32,38 @@ pub struct Model {
     pub registration_lock: bool,
     pub registration_lock_expires_at: Option<DateTimeWithTimeZone>,
     pub pin_set_at: Option<DateTimeWithTimeZone>,
+    /// Pending forgot-PIN reset completes at this time
+    pub pin_reset_at: Option<DateTimeWithTimeZone>,
+    // Who can find this user by username: 0 = everyone, 1 = contacts, 2 = nobody
+    pub discoverability: i16,
+    // Per-field profile visibility, same encoding as `discoverability`
+    pub display_name_visibility: i16,
+    pub bio_visibility: i16,
+    pub profile_picture_visibility: i16,
+    pub background_image_visibility: i16,
+}
+
+impl Model {
+    /// Registration lock is enforced only while a PIN exists, the lock has not
+    /// expired and no forgot-PIN cooling-off period has elapsed.
+    pub fn is_registration_lock_active(&self) -> bool {
+        if !self.registration_lock || self.pin_hash.is_none() {
+            return false;
+        }
+        let now = chrono::Utc::now();
+        let lock_expired = self.registration_lock_expires_at.is_some_and(|at| now >= at);
+        let reset_due = self.pin_reset_at.is_some_and(|at| now >= at);
+        !lock_expired && !reset_due
+    }
+
+    pub fn is_suspended(&self) -> bool {
+        self.suspended_until
+            .is_some_and(|until| chrono::Utc::now() < until)
+    }
+
+    pub fn is_banned(&self) -> bool {
+        self.banned_at.is_some()
+    }
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
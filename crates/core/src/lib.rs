@@ -1,2 +1 @@
 pub mod entities;
-pub mod signal;
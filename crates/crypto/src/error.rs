@@ -0,0 +1,41 @@
+use std::error::Error;
+use std::fmt;
+
+/// Errors from decoding keys and checking signatures
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CryptoError {
+    /// Serialized key is empty, so it has no type byte
+    NoKeyType,
+
+    /// Type byte does not match the expected key type
+    BadKeyType(u8),
+
+    /// Key material has the wrong length (expected, actual)
+    BadKeyLength(usize, usize),
+
+    /// ML-KEM encapsulation key has a coefficient that is not reduced mod q
+    InvalidKyberKey,
+
+    /// Signature does not verify against the key
+    InvalidSignature,
+}
+
+impl fmt::Display for CryptoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CryptoError::NoKeyType => f.write_str("serialized key has no type byte"),
+            CryptoError::BadKeyType(key_type) => write!(f, "unknown key type {:#04x}", key_type),
+            CryptoError::BadKeyLength(expected, actual) => {
+                write!(f, "key must be {} bytes, got {}", expected, actual)
+            }
+            CryptoError::InvalidKyberKey => {
+                f.write_str("ML-KEM-1024 public key coefficient out of range")
+            }
+            CryptoError::InvalidSignature => f.write_str("invalid signature"),
+        }
+    }
+}
+
+impl Error for CryptoError {}
+
+pub type Result<T> = std::result::Result<T, CryptoError>;
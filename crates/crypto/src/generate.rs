@@ -0,0 +1,119 @@
+//! Key generation mirroring what clients do on the device. The server only
+//! ever receives the public halves; these are for tests and tooling.
+
+use crate::keys::KeyPair;
+use crate::kyber::{KyberPublicKey, KYBER_PUBLIC_KEY_LENGTH, ML_KEM_1024_POLY_BYTES, ML_KEM_Q};
+use rand::rngs::OsRng;
+use rand::Rng;
+
+#[derive(Clone)]
+pub struct PreKey {
+    pub id: u32,
+    pub key_pair: KeyPair,
+}
+
+#[derive(Clone)]
+pub struct SignedPreKey {
+    pub id: u32,
+    pub key_pair: KeyPair,
+    pub signature: Vec<u8>,
+    pub timestamp: u64,
+}
+
+/// ML-KEM-1024 key generation happens on the device; this only carries the
+/// public half and the identity key's signature over it
+#[derive(Clone)]
+pub struct KyberPreKey {
+    pub id: u32,
+    pub public_key: KyberPublicKey,
+    pub signature: Vec<u8>,
+}
+
+pub struct SignalKeys {
+    pub identity_key_pair: KeyPair,
+    pub registration_id: u32,
+    pub signed_prekey: SignedPreKey,
+    pub one_time_prekeys: Vec<PreKey>,
+}
+
+pub fn generate_identity_keypair() -> KeyPair {
+    KeyPair::generate(&mut OsRng)
+}
+
+pub fn generate_registration_id() -> u32 {
+    rand::random::<u32>() & 0x3fff
+}
+
+pub fn generate_signed_prekey(identity_key_pair: &KeyPair, signed_prekey_id: u32) -> SignedPreKey {
+    let key_pair = KeyPair::generate(&mut OsRng);
+    let signature = identity_key_pair
+        .private_key
+        .calculate_signature(&key_pair.public_key.serialize(), &mut OsRng);
+
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis() as u64)
+        .unwrap_or_default();
+
+    SignedPreKey {
+        id: signed_prekey_id,
+        key_pair,
+        signature: signature.to_vec(),
+        timestamp,
+    }
+}
+
+pub fn generate_prekeys(start_id: u32, count: u32) -> Vec<PreKey> {
+    (start_id..start_id + count)
+        .map(|id| PreKey {
+            id,
+            key_pair: KeyPair::generate(&mut OsRng),
+        })
+        .collect()
+}
+
+/// A well-formed ML-KEM-1024 encapsulation key: random reduced coefficients
+/// and seed. There is no private half; the server never decapsulates.
+pub fn generate_kyber_public_key() -> KyberPublicKey {
+    let mut rng = OsRng;
+    let mut public_key = Vec::with_capacity(KYBER_PUBLIC_KEY_LENGTH);
+    for _ in 0..ML_KEM_1024_POLY_BYTES / 3 {
+        let (first, second) = (rng.gen_range(0..ML_KEM_Q), rng.gen_range(0..ML_KEM_Q));
+        public_key.push((first & 0xff) as u8);
+        public_key.push(((first >> 8) | ((second & 0x0f) << 4)) as u8);
+        public_key.push((second >> 4) as u8);
+    }
+    public_key.extend_from_slice(&rng.gen::<[u8; 32]>());
+
+    KyberPublicKey::from_public_key_bytes(&public_key).expect("coefficients are reduced")
+}
+
+pub fn sign_kyber_prekey(
+    identity_key_pair: &KeyPair,
+    kyber_prekey_id: u32,
+    public_key: KyberPublicKey,
+) -> KyberPreKey {
+    let signature = identity_key_pair
+        .private_key
+        .calculate_signature(&public_key.serialize(), &mut OsRng);
+
+    KyberPreKey {
+        id: kyber_prekey_id,
+        public_key,
+        signature: signature.to_vec(),
+    }
+}
+
+pub fn create_signal_keys() -> SignalKeys {
+    let identity_key_pair = generate_identity_keypair();
+    let registration_id = generate_registration_id();
+    let signed_prekey = generate_signed_prekey(&identity_key_pair, 1);
+    let one_time_prekeys = generate_prekeys(1, 100);
+
+    SignalKeys {
+        identity_key_pair,
+        registration_id,
+        signed_prekey,
+        one_time_prekeys,
+    }
+}
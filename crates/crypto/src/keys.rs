@@ -0,0 +1,119 @@
+//! Curve25519 keys in the Signal wire format: a type byte followed by the key
+
+use crate::error::{CryptoError, Result};
+use crate::xeddsa;
+use curve25519_dalek::montgomery::MontgomeryPoint;
+use curve25519_dalek::scalar::clamp_integer;
+use rand::{CryptoRng, RngCore};
+
+/// Type byte of a serialized Curve25519 public key
+pub const DJB_TYPE: u8 = 0x05;
+pub const PUBLIC_KEY_LENGTH: usize = 32;
+pub const PRIVATE_KEY_LENGTH: usize = 32;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PublicKey([u8; PUBLIC_KEY_LENGTH]);
+
+impl PublicKey {
+    /// Parse a type-prefixed key as clients upload it
+    pub fn deserialize(serialized: &[u8]) -> Result<Self> {
+        match serialized.split_first() {
+            None => Err(CryptoError::NoKeyType),
+            Some((&DJB_TYPE, key)) => Self::from_djb_public_key_bytes(key),
+            Some((&key_type, _)) => Err(CryptoError::BadKeyType(key_type)),
+        }
+    }
+
+    /// Wrap a bare 32-byte key, as stored in the database
+    pub fn from_djb_public_key_bytes(bytes: &[u8]) -> Result<Self> {
+        bytes
+            .try_into()
+            .map(Self)
+            .map_err(|_| CryptoError::BadKeyLength(PUBLIC_KEY_LENGTH, bytes.len()))
+    }
+
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut serialized = Vec::with_capacity(1 + PUBLIC_KEY_LENGTH);
+        serialized.push(DJB_TYPE);
+        serialized.extend_from_slice(&self.0);
+        serialized
+    }
+
+    pub fn public_key_bytes(&self) -> &[u8; PUBLIC_KEY_LENGTH] {
+        &self.0
+    }
+
+    pub fn verify_signature(&self, message: &[u8], signature: &[u8]) -> Result<()> {
+        let signature = signature
+            .try_into()
+            .map_err(|_| CryptoError::InvalidSignature)?;
+        if xeddsa::verify(&self.0, message, signature) {
+            Ok(())
+        } else {
+            Err(CryptoError::InvalidSignature)
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct PrivateKey([u8; PRIVATE_KEY_LENGTH]);
+
+impl PrivateKey {
+    pub fn generate<R: RngCore + CryptoRng>(rng: &mut R) -> Self {
+        let mut bytes = [0u8; PRIVATE_KEY_LENGTH];
+        rng.fill_bytes(&mut bytes);
+        Self(clamp_integer(bytes))
+    }
+
+    /// Keys are clamped on import so signing and key agreement use the same scalar
+    pub fn deserialize(bytes: &[u8]) -> Result<Self> {
+        let bytes: [u8; PRIVATE_KEY_LENGTH] = bytes
+            .try_into()
+            .map_err(|_| CryptoError::BadKeyLength(PRIVATE_KEY_LENGTH, bytes.len()))?;
+        Ok(Self(clamp_integer(bytes)))
+    }
+
+    pub fn serialize(&self) -> Vec<u8> {
+        self.0.to_vec()
+    }
+
+    pub fn public_key(&self) -> PublicKey {
+        PublicKey(MontgomeryPoint::mul_base_clamped(self.0).to_bytes())
+    }
+
+    /// X25519 shared secret with another party's public key
+    pub fn calculate_agreement(&self, their_key: &PublicKey) -> [u8; 32] {
+        MontgomeryPoint(their_key.0).mul_clamped(self.0).to_bytes()
+    }
+
+    pub fn calculate_signature<R: RngCore + CryptoRng>(
+        &self,
+        message: &[u8],
+        rng: &mut R,
+    ) -> [u8; xeddsa::SIGNATURE_LENGTH] {
+        let mut random = [0u8; 64];
+        rng.fill_bytes(&mut random);
+        xeddsa::sign(&self.0, message, &random)
+    }
+}
+
+#[derive(Clone)]
+pub struct KeyPair {
+    pub public_key: PublicKey,
+    pub private_key: PrivateKey,
+}
+
+impl KeyPair {
+    pub fn generate<R: RngCore + CryptoRng>(rng: &mut R) -> Self {
+        Self::from(PrivateKey::generate(rng))
+    }
+}
+
+impl From<PrivateKey> for KeyPair {
+    fn from(private_key: PrivateKey) -> Self {
+        Self {
+            public_key: private_key.public_key(),
+            private_key,
+        }
+    }
+}
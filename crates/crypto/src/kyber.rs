@@ -0,0 +1,57 @@
+//! ML-KEM-1024 (Kyber) public keys for PQXDH. The server never encapsulates;
+//! it only checks that uploaded keys are well formed and signed.
+
+use crate::error::{CryptoError, Result};
+
+/// Type byte of a serialized Kyber-1024 public key
+pub const KYBER_1024_TYPE: u8 = 0x08;
+/// Encoded encapsulation key: 4 x 256 12-bit coefficients and a 32-byte seed
+pub const KYBER_PUBLIC_KEY_LENGTH: usize = 1568;
+pub(crate) const ML_KEM_Q: u16 = 3329;
+pub(crate) const ML_KEM_1024_POLY_BYTES: usize = 1536;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KyberPublicKey(Vec<u8>);
+
+impl KyberPublicKey {
+    /// Parse a type-prefixed key as clients upload it
+    pub fn deserialize(serialized: &[u8]) -> Result<Self> {
+        match serialized.split_first() {
+            None => Err(CryptoError::NoKeyType),
+            Some((&KYBER_1024_TYPE, key)) => Self::from_public_key_bytes(key),
+            Some((&key_type, _)) => Err(CryptoError::BadKeyType(key_type)),
+        }
+    }
+
+    /// Wrap a bare encapsulation key, checking it the way FIPS 203 requires
+    /// before use: every coefficient must already be reduced mod q
+    pub fn from_public_key_bytes(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() != KYBER_PUBLIC_KEY_LENGTH {
+            return Err(CryptoError::BadKeyLength(
+                KYBER_PUBLIC_KEY_LENGTH,
+                bytes.len(),
+            ));
+        }
+
+        for chunk in bytes[..ML_KEM_1024_POLY_BYTES].chunks_exact(3) {
+            let (b0, b1, b2) = (chunk[0] as u16, chunk[1] as u16, chunk[2] as u16);
+            let first = b0 | ((b1 & 0x0f) << 8);
+            let second = (b1 >> 4) | (b2 << 4);
+            if first >= ML_KEM_Q || second >= ML_KEM_Q {
+                return Err(CryptoError::InvalidKyberKey);
+            }
+        }
+        Ok(Self(bytes.to_vec()))
+    }
+
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut serialized = Vec::with_capacity(1 + KYBER_PUBLIC_KEY_LENGTH);
+        serialized.push(KYBER_1024_TYPE);
+        serialized.extend_from_slice(&self.0);
+        serialized
+    }
+
+    pub fn public_key_bytes(&self) -> &[u8] {
+        &self.0
+    }
+}
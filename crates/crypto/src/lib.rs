@@ -0,0 +1,34 @@
+//! Signal protocol key handling: Curve25519 identity and prekeys with XEdDSA
+//! signatures, ML-KEM-1024 prekeys for PQXDH, and the type-prefixed wire
+//! format libsignal clients upload. Also the Merkle tree behind the key
+//! transparency log.
+
+mod error;
+pub mod generate;
+pub mod keys;
+pub mod kyber;
+pub mod merkle;
+pub mod xeddsa;
+
+pub use error::{CryptoError, Result};
+pub use keys::{KeyPair, PrivateKey, PublicKey, DJB_TYPE};
+pub use kyber::{KyberPublicKey, KYBER_1024_TYPE};
+
+/// Check a signed prekey against the identity key. Clients sign the
+/// serialized (type-prefixed) prekey, not the bare key.
+pub fn verify_signed_prekey(
+    identity_key: &PublicKey,
+    signed_prekey: &PublicKey,
+    signature: &[u8],
+) -> Result<()> {
+    identity_key.verify_signature(&signed_prekey.serialize(), signature)
+}
+
+/// Check a Kyber prekey against the identity key, as for [`verify_signed_prekey`]
+pub fn verify_kyber_prekey(
+    identity_key: &PublicKey,
+    kyber_prekey: &KyberPublicKey,
+    signature: &[u8],
+) -> Result<()> {
+    identity_key.verify_signature(&kyber_prekey.serialize(), signature)
+}
@@ -0,0 +1,219 @@
+//! Merkle tree hashing and proofs as defined for Certificate Transparency
+//! (RFC 9162 section 2.1), used by the key transparency log.
+//!
+//! Every subtree a proof refers to starts at a multiple of a power of two at
+//! least as large as itself, so it splits into a few perfect subtrees. Callers
+//! that store perfect subtree hashes can build proofs without the full tree:
+//! [`inclusion_path`] and [`consistency_path`] name the ranges a proof needs,
+//! [`perfect_subtrees`] says which stored hashes cover each range, and
+//! [`combine`] folds them back into the range's hash.
+
+use sha2::{Digest, Sha256};
+use std::ops::Range;
+
+pub type Hash = [u8; 32];
+
+/// A perfect subtree of `2^level` leaves, the `index`-th of that size
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Subtree {
+    pub level: u32,
+    pub index: u64,
+}
+
+impl Subtree {
+    pub fn leaves(&self) -> Range<u64> {
+        let start = self.index << self.level;
+        start..start + (1 << self.level)
+    }
+}
+
+/// Root of the empty tree
+pub fn empty_root() -> Hash {
+    Sha256::digest([]).into()
+}
+
+pub fn leaf_hash(data: &[u8]) -> Hash {
+    let mut hasher = Sha256::new();
+    hasher.update([0x00]);
+    hasher.update(data);
+    hasher.finalize().into()
+}
+
+pub fn node_hash(left: &Hash, right: &Hash) -> Hash {
+    let mut hasher = Sha256::new();
+    hasher.update([0x01]);
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+/// Root of a tree held in memory, from its leaf hashes
+pub fn root(leaves: &[Hash]) -> Hash {
+    match leaves.len() {
+        0 => empty_root(),
+        1 => leaves[0],
+        n => {
+            let k = split_point(n as u64) as usize;
+            node_hash(&root(&leaves[..k]), &root(&leaves[k..]))
+        }
+    }
+}
+
+/// Perfect subtrees covering `range`, largest (leftmost) first
+pub fn perfect_subtrees(range: Range<u64>) -> Vec<Subtree> {
+    let mut subtrees = Vec::new();
+    let mut start = range.start;
+    while start < range.end {
+        let aligned = if start == 0 { 63 } else { start.trailing_zeros() };
+        let fits = 63 - (range.end - start).leading_zeros();
+        let level = aligned.min(fits);
+        subtrees.push(Subtree {
+            level,
+            index: start >> level,
+        });
+        start += 1 << level;
+    }
+    subtrees
+}
+
+/// Hash of a range from the hashes of its [`perfect_subtrees`], in order
+pub fn combine(hashes: &[Hash]) -> Hash {
+    match hashes.split_last() {
+        None => empty_root(),
+        Some((last, rest)) => rest
+            .iter()
+            .rev()
+            .fold(*last, |acc, hash| node_hash(hash, &acc)),
+    }
+}
+
+/// Ranges whose hashes make up the audit path of leaf `index` in a tree of
+/// `size` leaves, leaf end first
+pub fn inclusion_path(index: u64, size: u64) -> Vec<Range<u64>> {
+    let mut path = Vec::new();
+    if index < size {
+        subtree_path(index, 0..size, &mut path);
+    }
+    path
+}
+
+fn subtree_path(index: u64, tree: Range<u64>, path: &mut Vec<Range<u64>>) {
+    let n = tree.end - tree.start;
+    if n <= 1 {
+        return;
+    }
+    let mid = tree.start + split_point(n);
+    if index < mid {
+        subtree_path(index, tree.start..mid, path);
+        path.push(mid..tree.end);
+    } else {
+        subtree_path(index, mid..tree.end, path);
+        path.push(tree.start..mid);
+    }
+}
+
+/// Ranges whose hashes prove the tree of `new_size` leaves extends the tree
+/// of `old_size` leaves. Empty when the sizes are equal or out of order.
+pub fn consistency_path(old_size: u64, new_size: u64) -> Vec<Range<u64>> {
+    let mut path = Vec::new();
+    if 0 < old_size && old_size < new_size {
+        subproof(old_size, 0..new_size, true, &mut path);
+    }
+    path
+}
+
+fn subproof(m: u64, tree: Range<u64>, complete: bool, path: &mut Vec<Range<u64>>) {
+    let n = tree.end - tree.start;
+    if m == n {
+        if !complete {
+            path.push(tree);
+        }
+        return;
+    }
+    let k = split_point(n);
+    let mid = tree.start + k;
+    if m <= k {
+        subproof(m, tree.start..mid, complete, path);
+        path.push(mid..tree.end);
+    } else {
+        subproof(m - k, mid..tree.end, false, path);
+        path.push(tree.start..mid);
+    }
+}
+
+/// Check an audit path, following RFC 9162 section 2.1.3.2
+pub fn verify_inclusion(leaf: &Hash, index: u64, size: u64, path: &[Hash], root: &Hash) -> bool {
+    if index >= size {
+        return false;
+    }
+    let (mut fn_, mut sn) = (index, size - 1);
+    let mut r = *leaf;
+    for p in path {
+        if sn == 0 {
+            return false;
+        }
+        if fn_ & 1 == 1 || fn_ == sn {
+            r = node_hash(p, &r);
+            while fn_ & 1 == 0 && fn_ != 0 {
+                fn_ >>= 1;
+                sn >>= 1;
+            }
+        } else {
+            r = node_hash(&r, p);
+        }
+        fn_ >>= 1;
+        sn >>= 1;
+    }
+    sn == 0 && r == *root
+}
+
+/// Check a consistency proof, following RFC 9162 section 2.1.4.2
+pub fn verify_consistency(
+    old_size: u64,
+    new_size: u64,
+    old_root: &Hash,
+    new_root: &Hash,
+    proof: &[Hash],
+) -> bool {
+    if old_size == new_size {
+        return proof.is_empty() && old_root == new_root;
+    }
+    if old_size == 0 || old_size > new_size || proof.is_empty() {
+        return false;
+    }
+
+    let mut path = proof.to_vec();
+    if old_size.is_power_of_two() {
+        path.insert(0, *old_root);
+    }
+
+    let (mut fn_, mut sn) = (old_size - 1, new_size - 1);
+    while fn_ & 1 == 1 {
+        fn_ >>= 1;
+        sn >>= 1;
+    }
+    let (mut fr, mut sr) = (path[0], path[0]);
+    for c in &path[1..] {
+        if sn == 0 {
+            return false;
+        }
+        if fn_ & 1 == 1 || fn_ == sn {
+            fr = node_hash(c, &fr);
+            sr = node_hash(c, &sr);
+            while fn_ & 1 == 0 && fn_ != 0 {
+                fn_ >>= 1;
+                sn >>= 1;
+            }
+        } else {
+            sr = node_hash(&sr, c);
+        }
+        fn_ >>= 1;
+        sn >>= 1;
+    }
+    sn == 0 && fr == *old_root && sr == *new_root
+}
+
+/// Largest power of two smaller than `n` (n > 1)
+fn split_point(n: u64) -> u64 {
+    1 << (63 - (n - 1).leading_zeros())
+}
@@ -0,0 +1,75 @@
+//! XEdDSA signatures over Curve25519 keys.
+//!
+//! Identity keys are X25519 key pairs, so one key serves both for
+//! Diffie-Hellman in X3DH/PQXDH and for signing prekeys. The Montgomery public
+//! key is converted to its Edwards form to verify; the sign bit of that point
+//! travels in the top bit of `s`, the same layout libsignal uses.
+
+use curve25519_dalek::edwards::{CompressedEdwardsY, EdwardsPoint};
+use curve25519_dalek::montgomery::MontgomeryPoint;
+use curve25519_dalek::scalar::Scalar;
+use sha2::{Digest, Sha512};
+
+pub const SIGNATURE_LENGTH: usize = 64;
+
+/// Domain separation for the nonce hash: 2^256 - 2 little-endian ("hash1")
+const NONCE_HASH_PREFIX: [u8; 32] = {
+    let mut prefix = [0xFF; 32];
+    prefix[0] = 0xFE;
+    prefix
+};
+
+/// Sign `message` with a clamped Curve25519 private key. `random` is 64 bytes
+/// of fresh randomness; it only hedges the nonce, which is also derived from
+/// the key and message.
+pub fn sign(private_key: &[u8; 32], message: &[u8], random: &[u8; 64]) -> [u8; SIGNATURE_LENGTH] {
+    let a = Scalar::from_bytes_mod_order(*private_key);
+    let public_key = EdwardsPoint::mul_base(&a).compress();
+    let sign_bit = public_key.as_bytes()[31] & 0x80;
+
+    let r = hash_to_scalar(&[&NONCE_HASH_PREFIX, private_key, message, random]);
+    let cap_r = EdwardsPoint::mul_base(&r).compress();
+    let h = hash_to_scalar(&[cap_r.as_bytes(), public_key.as_bytes(), message]);
+    let s = h * a + r;
+
+    let mut signature = [0u8; SIGNATURE_LENGTH];
+    signature[..32].copy_from_slice(cap_r.as_bytes());
+    signature[32..].copy_from_slice(s.as_bytes());
+    signature[SIGNATURE_LENGTH - 1] |= sign_bit;
+    signature
+}
+
+/// Check an XEdDSA signature against a Curve25519 public key
+pub fn verify(public_key: &[u8; 32], message: &[u8], signature: &[u8; SIGNATURE_LENGTH]) -> bool {
+    let sign_bit = (signature[SIGNATURE_LENGTH - 1] & 0x80) >> 7;
+    let Some(a) = MontgomeryPoint(*public_key).to_edwards(sign_bit) else {
+        return false;
+    };
+
+    let mut cap_r = [0u8; 32];
+    cap_r.copy_from_slice(&signature[..32]);
+    let mut s = [0u8; 32];
+    s.copy_from_slice(&signature[32..]);
+    s[31] &= 0x7F;
+    // s must be below 2^253; anything larger is a malleated signature
+    if s[31] & 0xE0 != 0 {
+        return false;
+    }
+
+    let h = hash_to_scalar(&[&cap_r, a.compress().as_bytes(), message]);
+    let check = EdwardsPoint::vartime_double_scalar_mul_basepoint(
+        &h,
+        &-a,
+        &Scalar::from_bytes_mod_order(s),
+    );
+
+    check.compress() == CompressedEdwardsY(cap_r)
+}
+
+fn hash_to_scalar(parts: &[&[u8]]) -> Scalar {
+    let mut hasher = Sha512::new();
+    for part in parts {
+        hasher.update(part);
+    }
+    Scalar::from_bytes_mod_order_wide(&hasher.finalize().into())
+}
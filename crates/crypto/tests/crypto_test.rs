@@ -0,0 +1,157 @@
+use crypto::generate::{
+    generate_identity_keypair, generate_kyber_public_key, generate_prekeys, generate_signed_prekey,
+    sign_kyber_prekey,
+};
+use crypto::{CryptoError, KyberPublicKey, PrivateKey, PublicKey, DJB_TYPE, KYBER_1024_TYPE};
+use x25519_dalek::StaticSecret;
+
+#[test]
+fn test_x3dh_handshake_simulation() {
+    // 1. Alice's device generates keys and uploads the public halves
+    let alice_identity = generate_identity_keypair();
+    let alice_signed_prekey = generate_signed_prekey(&alice_identity, 1);
+    let alice_one_time_prekey = generate_prekeys(1, 1).remove(0);
+
+    // 2. Bob fetches Alice's bundle and verifies the signed prekey
+    assert!(crypto::verify_signed_prekey(
+        &alice_identity.public_key,
+        &alice_signed_prekey.key_pair.public_key,
+        &alice_signed_prekey.signature
+    )
+    .is_ok());
+
+    // 3. Bob performs X3DH. The identity key is a Curve25519 key, so it takes
+    // part in DH directly.
+    let bob_identity = generate_identity_keypair();
+    let bob_ephemeral = generate_identity_keypair();
+    let bob_dh = [
+        bob_identity
+            .private_key
+            .calculate_agreement(&alice_signed_prekey.key_pair.public_key),
+        bob_ephemeral
+            .private_key
+            .calculate_agreement(&alice_identity.public_key),
+        bob_ephemeral
+            .private_key
+            .calculate_agreement(&alice_signed_prekey.key_pair.public_key),
+        bob_ephemeral
+            .private_key
+            .calculate_agreement(&alice_one_time_prekey.key_pair.public_key),
+    ];
+
+    // 4. Alice derives the same secrets from Bob's public keys
+    let alice_dh = [
+        alice_signed_prekey
+            .key_pair
+            .private_key
+            .calculate_agreement(&bob_identity.public_key),
+        alice_identity
+            .private_key
+            .calculate_agreement(&bob_ephemeral.public_key),
+        alice_signed_prekey
+            .key_pair
+            .private_key
+            .calculate_agreement(&bob_ephemeral.public_key),
+        alice_one_time_prekey
+            .key_pair
+            .private_key
+            .calculate_agreement(&bob_ephemeral.public_key),
+    ];
+
+    // 5. Assert Shared Secrets Match
+    for (i, (bob, alice)) in bob_dh.iter().zip(alice_dh.iter()).enumerate() {
+        assert_eq!(bob, alice, "DH{} mismatch", i + 1);
+    }
+
+    // Key agreement interoperates with x25519-dalek
+    let secret =
+        StaticSecret::from(<[u8; 32]>::try_from(bob_ephemeral.private_key.serialize()).unwrap());
+    let peer = x25519_dalek::PublicKey::from(*alice_identity.public_key.public_key_bytes());
+    assert_eq!(secret.diffie_hellman(&peer).as_bytes(), &bob_dh[1]);
+}
+
+#[test]
+fn test_verify_signed_prekey() {
+    let identity = generate_identity_keypair();
+    let signed_prekey = generate_signed_prekey(&identity, 1);
+    let prekey = signed_prekey.key_pair.public_key;
+    assert!(
+        crypto::verify_signed_prekey(&identity.public_key, &prekey, &signed_prekey.signature)
+            .is_ok()
+    );
+
+    // Signed by a different identity
+    let other_identity = generate_identity_keypair();
+    assert_eq!(
+        crypto::verify_signed_prekey(
+            &other_identity.public_key,
+            &prekey,
+            &signed_prekey.signature
+        ),
+        Err(CryptoError::InvalidSignature)
+    );
+
+    // The signature covers the serialized key, type byte included
+    let bare_signature = identity
+        .private_key
+        .calculate_signature(prekey.public_key_bytes(), &mut rand::rngs::OsRng);
+    assert!(crypto::verify_signed_prekey(&identity.public_key, &prekey, &bare_signature).is_err());
+
+    // Malformed signatures are rejected rather than panicking
+    assert!(crypto::verify_signed_prekey(&identity.public_key, &prekey, &[0u8; 5]).is_err());
+}
+
+#[test]
+fn test_public_key_serialization() {
+    let key_pair = generate_identity_keypair();
+    let serialized = key_pair.public_key.serialize();
+    assert_eq!(serialized.len(), 33);
+    assert_eq!(serialized[0], DJB_TYPE);
+    assert_eq!(PublicKey::deserialize(&serialized), Ok(key_pair.public_key));
+
+    assert_eq!(PublicKey::deserialize(&[]), Err(CryptoError::NoKeyType));
+    assert_eq!(
+        PublicKey::deserialize(&serialized[1..]),
+        Err(CryptoError::BadKeyType(serialized[1]))
+    );
+    assert_eq!(
+        PublicKey::deserialize(&serialized[..20]),
+        Err(CryptoError::BadKeyLength(32, 19))
+    );
+
+    // Private keys are clamped on import, so they round-trip to the same public key
+    let private = PrivateKey::deserialize(&[0xFFu8; 32]).unwrap();
+    let reimported = PrivateKey::deserialize(&private.serialize()).unwrap();
+    assert_eq!(private.public_key(), reimported.public_key());
+}
+
+#[test]
+fn test_verify_kyber_prekey() {
+    let identity = generate_identity_keypair();
+    let public_key = generate_kyber_public_key();
+    let prekey = sign_kyber_prekey(&identity, 1, public_key.clone());
+    assert!(crypto::verify_kyber_prekey(
+        &identity.public_key,
+        &prekey.public_key,
+        &prekey.signature
+    )
+    .is_ok());
+
+    let serialized = public_key.serialize();
+    assert_eq!(serialized[0], KYBER_1024_TYPE);
+    assert_eq!(KyberPublicKey::deserialize(&serialized), Ok(public_key.clone()));
+
+    // Coefficient 4095 is not reduced mod q
+    let mut unreduced = public_key.public_key_bytes().to_vec();
+    unreduced[0] = 0xff;
+    unreduced[1] |= 0x0f;
+    assert_eq!(
+        KyberPublicKey::from_public_key_bytes(&unreduced),
+        Err(CryptoError::InvalidKyberKey)
+    );
+
+    assert!(KyberPublicKey::from_public_key_bytes(&public_key.public_key_bytes()[..1000]).is_err());
+
+    // A Curve25519 key is not accepted where a Kyber key is expected
+    assert!(KyberPublicKey::deserialize(&identity.public_key.serialize()).is_err());
+}
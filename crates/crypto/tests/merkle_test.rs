@@ -0,0 +1,124 @@
+use crypto::merkle::{
+    combine, consistency_path, empty_root, inclusion_path, leaf_hash, perfect_subtrees, root,
+    verify_consistency, verify_inclusion, Hash,
+};
+use std::ops::Range;
+
+/// Leaves and roots from the Certificate Transparency reference test vectors
+const LEAVES: [&str; 8] = [
+    "",
+    "00",
+    "10",
+    "2021",
+    "3031",
+    "40414243",
+    "5051525354555657",
+    "606162636465666768696a6b6c6d6e6f",
+];
+const ROOTS: [&str; 8] = [
+    "6e340b9cffb37a989ca544e6bb780a2c78901d3fb33738768511a30617afa01d",
+    "fac54203e7cc696cf0dfcb42c92a1d9dbaf70ad9e621f4bd8d98662f00e3c125",
+    "aeb6bcfe274b70a14fb067a5e5578264db0fa9b51af5e0ba159158f329e06e77",
+    "d37ee418976dd95753c1c73862b9398fa2a2cf9b4ff0fdfe8b30cd95209614b7",
+    "4e3bbb1f7b478dcfe71fb631631519a3bca12c9aefca1612bfce4c13a86264d4",
+    "76e67dadbcdf1e10e1b74ddc608abd2f98dfb16fbce75277b5232a127f2087ef",
+    "ddb89be403809e325750d3d263cd78929c2942b7942a34b77e122c9594a74c8c",
+    "5dc9da79a70659a9ad559cb701ded9a2ab9d823aad2f4960cfe370eff4604328",
+];
+
+fn leaves(n: u64) -> Vec<Hash> {
+    (0..n).map(|i| leaf_hash(&i.to_be_bytes())).collect()
+}
+
+/// Hash of a range the way the log builds it, from perfect subtree hashes
+fn range_hash(leaves: &[Hash], range: Range<u64>) -> Hash {
+    let hashes: Vec<Hash> = perfect_subtrees(range)
+        .iter()
+        .map(|subtree| {
+            let leaves_range = subtree.leaves();
+            root(&leaves[leaves_range.start as usize..leaves_range.end as usize])
+        })
+        .collect();
+    combine(&hashes)
+}
+
+fn proof(leaves: &[Hash], ranges: Vec<Range<u64>>) -> Vec<Hash> {
+    ranges.into_iter().map(|range| range_hash(leaves, range)).collect()
+}
+
+#[test]
+fn test_reference_roots() {
+    assert_eq!(
+        hex::encode(empty_root()),
+        "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+    );
+
+    let hashes: Vec<Hash> = LEAVES
+        .iter()
+        .map(|leaf| leaf_hash(&hex::decode(leaf).unwrap()))
+        .collect();
+    for (size, expected) in ROOTS.iter().enumerate() {
+        assert_eq!(hex::encode(root(&hashes[..size + 1])), *expected);
+        assert_eq!(hex::encode(range_hash(&hashes, 0..size as u64 + 1)), *expected);
+    }
+}
+
+#[test]
+fn test_perfect_subtrees_cover_range() {
+    let subtrees = perfect_subtrees(0..13);
+    let sizes: Vec<u64> = subtrees.iter().map(|s| 1 << s.level).collect();
+    assert_eq!(sizes, vec![8, 4, 1]);
+
+    for start in 0..32u64 {
+        for end in start..40 {
+            let mut next = start;
+            for subtree in perfect_subtrees(start..end) {
+                assert_eq!(subtree.leaves().start, next);
+                next = subtree.leaves().end;
+            }
+            assert_eq!(next, end);
+        }
+    }
+}
+
+#[test]
+fn test_inclusion_proofs() {
+    let all = leaves(33);
+    for size in 1..=33u64 {
+        let tree_root = root(&all[..size as usize]);
+        for index in 0..size {
+            let path = proof(&all, inclusion_path(index, size));
+            assert!(verify_inclusion(&all[index as usize], index, size, &path, &tree_root));
+
+            // Wrong leaf, wrong position and a truncated path all fail
+            let other = all[((index + 1) % size) as usize];
+            if size > 1 {
+                assert!(!verify_inclusion(&other, index, size, &path, &tree_root));
+                assert!(!verify_inclusion(&all[index as usize], index, size, &path[1..], &tree_root));
+            }
+            assert!(!verify_inclusion(&all[index as usize], size, size, &path, &tree_root));
+        }
+    }
+}
+
+#[test]
+fn test_consistency_proofs() {
+    let all = leaves(33);
+    for new_size in 1..=33u64 {
+        let new_root = root(&all[..new_size as usize]);
+        for old_size in 1..=new_size {
+            let old_root = root(&all[..old_size as usize]);
+            let path = proof(&all, consistency_path(old_size, new_size));
+            assert!(verify_consistency(old_size, new_size, &old_root, &new_root, &path));
+
+            // A rewritten history does not verify
+            if old_size < new_size {
+                let forged = leaf_hash(b"forged");
+                assert!(!verify_consistency(old_size, new_size, &forged, &new_root, &path));
+                assert!(!verify_consistency(old_size, new_size, &old_root, &forged, &path));
+            }
+        }
+    }
+    assert!(!verify_consistency(0, 4, &empty_root(), &root(&all[..4]), &[]));
+    assert!(!verify_consistency(5, 4, &root(&all[..5]), &root(&all[..4]), &[]));
+}
@@ -0,0 +1,129 @@
+use crypto::{xeddsa, PrivateKey, PublicKey};
+
+fn decode(value: &str) -> Vec<u8> {
+    hex::decode(value).unwrap()
+}
+
+fn private_key(value: &str) -> PrivateKey {
+    PrivateKey::deserialize(&decode(value)).unwrap()
+}
+
+fn scalar_bytes(key: &PrivateKey) -> [u8; 32] {
+    key.serialize().try_into().unwrap()
+}
+
+// libsignal curve25519 test vectors: an identity key and its XEdDSA signature
+// over a serialized ephemeral key
+const ALICE_IDENTITY_PRIVATE: &str =
+    "c097248412e58bf05df487968205132794178e367637f5818f81e0e6ce73e865";
+const ALICE_IDENTITY_PUBLIC: &str =
+    "ab7e717d4a163b7d9a1d8071dfe9dcf8cdcd1cea3339b6356be84d887e322c64";
+const ALICE_EPHEMERAL_PUBLIC: &str =
+    "05edce9d9c415ca78cb7252e72c2c4a554d3eb29485a0e1d503118d1a82d99fb4a";
+const ALICE_SIGNATURE: &str = "5de88ca9a89b4a115da79109c67c9c7464a3e4180274f1cb8c63c2984e286dfbede82deb9dcd9fae0bfbb821569b3d9001bd8130cd11d486cef047bd60b86e88";
+
+// RFC 7748 section 6.1 X25519 test vectors
+const RFC7748_ALICE_PRIVATE: &str =
+    "77076d0a7318a57d3c16c17251b26645df4c2f87ebc0992ab177fba51db92c2a";
+const RFC7748_ALICE_PUBLIC: &str =
+    "8520f0098930a754748b7ddcb43ef75a0dbf3a0d26381af4eba4a98eaa9b4e6a";
+const RFC7748_BOB_PRIVATE: &str =
+    "5dab087e624a8a4b79e17f8b83800ee66f3bb1292618b6fd1c2f8b27ff88e0eb";
+const RFC7748_BOB_PUBLIC: &str = "de9edb7d7b7dc1b4d35b61c2ece435373f8343c85b78674dadfc7e146f882b4f";
+const RFC7748_SHARED_SECRET: &str =
+    "4a5d9d5ba4ce2de1728e3bf480350f25e07e21c947d19e3376f09b3c1e161742";
+
+// Signatures produced by this implementation with fixed randomness, pinned so
+// nonce derivation and encoding cannot drift. Each one also verifies above.
+const PINNED_SIGNATURES: [(&str, &[u8], u8, &str); 3] = [
+    (
+        ALICE_IDENTITY_PRIVATE,
+        b"",
+        0x00,
+        "dabf4aee463a4192a697908eca8e8e70b3c50e9e04b8ac466ace71a1b2d29e0d1fcd97f3bc3c77742bd60dd07af47639b83c3b13a091f462dd9d77559c6ac581",
+    ),
+    (
+        RFC7748_ALICE_PRIVATE,
+        b"vyry xeddsa",
+        0xAA,
+        "4adce55aa80872b3951aea0979705f480a07f3bde8e633e1b6b5eb3c9a8f9b513bb7a460cd560d60819d422a3f810aa28edd1739ebd7bf5013f7ee7a0b82da8f",
+    ),
+    (
+        RFC7748_BOB_PRIVATE,
+        b"vyry xeddsa",
+        0xAA,
+        "07283a2ac7e2ef2ab6d2d26ec2206dafa97572da0c8756349ef2a80a44dd4ecd753254cc794eb96e94dd0c35274abfc218e81a8cbb828cabb2ec6bb484d5f48a",
+    ),
+];
+
+#[test]
+fn test_libsignal_signature_verifies() {
+    let private = private_key(ALICE_IDENTITY_PRIVATE);
+    assert_eq!(
+        private.public_key().public_key_bytes().as_slice(),
+        decode(ALICE_IDENTITY_PUBLIC)
+    );
+
+    let public = PublicKey::from_djb_public_key_bytes(&decode(ALICE_IDENTITY_PUBLIC)).unwrap();
+    let message = decode(ALICE_EPHEMERAL_PUBLIC);
+    let signature = decode(ALICE_SIGNATURE);
+    assert!(public.verify_signature(&message, &signature).is_ok());
+
+    // Any change to the message or signature is caught
+    let mut tampered = message.clone();
+    tampered[1] ^= 0x01;
+    assert!(public.verify_signature(&tampered, &signature).is_err());
+
+    for index in [0, 31, 32, 63] {
+        let mut forged = signature.clone();
+        forged[index] ^= 0x01;
+        assert!(public.verify_signature(&message, &forged).is_err());
+    }
+
+    // The top bit of s carries the sign of the Edwards key
+    let mut flipped_sign = signature.clone();
+    flipped_sign[63] ^= 0x80;
+    assert!(public.verify_signature(&message, &flipped_sign).is_err());
+
+    // s >= 2^253 is rejected instead of being reduced
+    let mut unreduced = signature;
+    unreduced[63] |= 0x60;
+    assert!(public.verify_signature(&message, &unreduced).is_err());
+}
+
+#[test]
+fn test_rfc7748_key_agreement() {
+    let alice = private_key(RFC7748_ALICE_PRIVATE);
+    let bob = private_key(RFC7748_BOB_PRIVATE);
+    assert_eq!(
+        alice.public_key().public_key_bytes().as_slice(),
+        decode(RFC7748_ALICE_PUBLIC)
+    );
+    assert_eq!(
+        bob.public_key().public_key_bytes().as_slice(),
+        decode(RFC7748_BOB_PUBLIC)
+    );
+
+    let shared = decode(RFC7748_SHARED_SECRET);
+    assert_eq!(
+        alice.calculate_agreement(&bob.public_key()).as_slice(),
+        shared
+    );
+    assert_eq!(
+        bob.calculate_agreement(&alice.public_key()).as_slice(),
+        shared
+    );
+}
+
+#[test]
+fn test_pinned_signatures() {
+    for (private, message, random, expected) in PINNED_SIGNATURES {
+        let private = private_key(private);
+        let signature = xeddsa::sign(&scalar_bytes(&private), message, &[random; 64]);
+        assert_eq!(hex::encode(signature), expected);
+        assert!(private
+            .public_key()
+            .verify_signature(message, &signature)
+            .is_ok());
+    }
+}
@@ -1,6 +1,6 @@
-pub mod crypto;
 pub mod database;
 pub mod redis;
+pub mod storage;
 // TODO: Fix async_trait macro conflict with crate::core
 // The async_trait macro tries to use std::core but we have a crate named "core"
 // Solution: Either rename the core crate or use a different approach for async traits
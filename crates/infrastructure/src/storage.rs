@@ -0,0 +1,79 @@
+use anyhow::Result;
+use bytes::Bytes;
+use object_store::aws::AmazonS3Builder;
+use object_store::local::LocalFileSystem;
+use object_store::path::Path;
+use object_store::{ObjectStore, PutPayload};
+use std::sync::Arc;
+
+/// Where uploaded blobs are kept
+#[derive(Debug, Clone)]
+pub enum BlobStoreConfig {
+    /// Directory on the local filesystem, created if missing
+    Local { root: String },
+    /// S3 or an S3-compatible service. Credentials come from the standard AWS_* variables.
+    S3 {
+        bucket: String,
+        region: Option<String>,
+        endpoint: Option<String>,
+    },
+}
+
+/// Blobs addressed by caller-chosen keys such as "avatars/<user_id>/<id>.jpg"
+#[derive(Clone)]
+pub struct BlobStore {
+    inner: Arc<dyn ObjectStore>,
+}
+
+impl BlobStore {
+    pub fn new(config: &BlobStoreConfig) -> Result<Self> {
+        let inner: Arc<dyn ObjectStore> = match config {
+            BlobStoreConfig::Local { root } => {
+                std::fs::create_dir_all(root)?;
+                Arc::new(LocalFileSystem::new_with_prefix(root)?)
+            }
+            BlobStoreConfig::S3 {
+                bucket,
+                region,
+                endpoint,
+            } => {
+                let mut builder = AmazonS3Builder::from_env().with_bucket_name(bucket);
+                if let Some(region) = region {
+                    builder = builder.with_region(region);
+                }
+                if let Some(endpoint) = endpoint {
+                    builder = builder.with_endpoint(endpoint).with_allow_http(true);
+                }
+                Arc::new(builder.build()?)
+            }
+        };
+
+        tracing::info!("Blob store initialised: {:?}", config);
+        Ok(Self { inner })
+    }
+
+    /// Content type is not stored; readers infer it from the key's extension
+    pub async fn put(&self, key: &str, data: Vec<u8>) -> Result<()> {
+        self.inner
+            .put(&Path::from(key), PutPayload::from(data))
+            .await?;
+        Ok(())
+    }
+
+    /// `None` if no blob is stored under `key`
+    pub async fn get(&self, key: &str) -> Result<Option<Bytes>> {
+        match self.inner.get(&Path::from(key)).await {
+            Ok(result) => Ok(Some(result.bytes().await?)),
+            Err(object_store::Error::NotFound { .. }) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Deleting a missing blob is not an error
+    pub async fn delete(&self, key: &str) -> Result<()> {
+        match self.inner.delete(&Path::from(key)).await {
+            Ok(()) | Err(object_store::Error::NotFound { .. }) => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
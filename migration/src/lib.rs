@@ -17,6 +17,28 @@ mod m20251207000001_add_pin_to_users;
 mod m20251207000002_add_device_type_to_devices;
 mod m20251207000003_create_device_linking_sessions;
 mod m20251207000004_add_background_image_to_users;
+mod m20251208000001_create_audit_logs;
+mod m20251208000002_canonicalize_phone_numbers;
+mod m20251208000003_add_phone_hash_prefix_index;
+mod m20251209000001_add_username_discoverability;
+mod m20251209000002_add_profile_field_visibility;
+mod m20251209000003_create_encrypted_profiles;
+mod m20251210000001_add_account_purge;
+mod m20251210000002_create_data_exports;
+mod m20251210000003_create_blocks;
+mod m20251210000004_add_message_requests;
+mod m20251211000001_create_admins;
+mod m20251211000002_add_account_moderation;
+mod m20251211000003_create_reports;
+mod m20251212000001_make_audit_logs_immutable;
+mod m20251213000001_add_linking_session_keys;
+mod m20251213000002_add_signed_prekey_rotation;
+mod m20251214000001_create_kyber_prekeys;
+mod m20251215000001_create_identity_key_history;
+mod m20251216000001_add_signal_session_versions;
+mod m20251217000001_create_key_transparency_log;
+mod m20251218000001_add_pin_reset_at;
+mod m20251218000002_keep_moderation_history;
 
 pub struct Migrator;
 
@@ -41,6 +63,28 @@ impl MigratorTrait for Migrator {
             Box::new(m20251207000002_add_device_type_to_devices::Migration),
             Box::new(m20251207000003_create_device_linking_sessions::Migration),
             Box::new(m20251207000004_add_background_image_to_users::Migration),
+            Box::new(m20251208000001_create_audit_logs::Migration),
+            Box::new(m20251208000002_canonicalize_phone_numbers::Migration),
+            Box::new(m20251208000003_add_phone_hash_prefix_index::Migration),
+            Box::new(m20251209000001_add_username_discoverability::Migration),
+            Box::new(m20251209000002_add_profile_field_visibility::Migration),
+            Box::new(m20251209000003_create_encrypted_profiles::Migration),
+            Box::new(m20251210000001_add_account_purge::Migration),
+            Box::new(m20251210000002_create_data_exports::Migration),
+            Box::new(m20251210000003_create_blocks::Migration),
+            Box::new(m20251210000004_add_message_requests::Migration),
+            Box::new(m20251211000001_create_admins::Migration),
+            Box::new(m20251211000002_add_account_moderation::Migration),
+            Box::new(m20251211000003_create_reports::Migration),
+            Box::new(m20251212000001_make_audit_logs_immutable::Migration),
+            Box::new(m20251213000001_add_linking_session_keys::Migration),
+            Box::new(m20251213000002_add_signed_prekey_rotation::Migration),
+            Box::new(m20251214000001_create_kyber_prekeys::Migration),
+            Box::new(m20251215000001_create_identity_key_history::Migration),
+            Box::new(m20251216000001_add_signal_session_versions::Migration),
+            Box::new(m20251217000001_create_key_transparency_log::Migration),
+            Box::new(m20251218000001_add_pin_reset_at::Migration),
+            Box::new(m20251218000002_keep_moderation_history::Migration),
         ]
     }
 }
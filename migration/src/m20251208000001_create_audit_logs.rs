@@ -0,0 +1,74 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // No foreign keys on purpose: audit rows must outlive the users and devices they describe
+        manager
+            .create_table(
+                Table::create()
+                    .table(AuditLogs::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(AuditLogs::AuditId)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(AuditLogs::UserId).uuid())
+                    .col(ColumnDef::new(AuditLogs::DeviceId).big_integer())
+                    .col(ColumnDef::new(AuditLogs::Actor).text().not_null())
+                    .col(ColumnDef::new(AuditLogs::Action).text().not_null())
+                    .col(
+                        ColumnDef::new(AuditLogs::Details)
+                            .json_binary()
+                            .not_null()
+                            .default(Expr::cust("'{}'::jsonb")),
+                    )
+                    .col(
+                        ColumnDef::new(AuditLogs::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Create index for per-user audit history
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_audit_logs_user_created")
+                    .table(AuditLogs::Table)
+                    .col(AuditLogs::UserId)
+                    .col(AuditLogs::CreatedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(AuditLogs::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum AuditLogs {
+    Table,
+    AuditId,
+    UserId,
+    DeviceId,
+    Actor,
+    Action,
+    Details,
+    CreatedAt,
+}
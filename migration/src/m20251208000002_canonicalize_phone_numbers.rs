@@ -0,0 +1,114 @@
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::sea_orm::{ConnectionTrait, Statement};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+
+/// Rewrite `users.phone_number` / `phone_number_hash` to canonical E.164.
+///
+/// Rows that cannot be parsed are left untouched. When two accounts collapse to
+/// the same canonical number, the account already stored in canonical form (or
+/// else the oldest one) gets it; the other is left as-is, since merging
+/// accounts needs a human decision. Every skipped account gets an `audit_logs`
+/// row (`phone_number.canonicalization_skipped`) so it can be found later.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        let backend = manager.get_database_backend();
+
+        let rows = db
+            .query_all(Statement::from_string(
+                backend,
+                "SELECT user_id::text AS user_id, phone_number FROM users ORDER BY created_at ASC",
+            ))
+            .await?;
+
+        let mut users = Vec::with_capacity(rows.len());
+        for row in rows {
+            let user_id: String = row.try_get("", "user_id")?;
+            let phone_number: String = row.try_get("", "phone_number")?;
+            users.push((user_id, phone_number));
+        }
+
+        // Numbers already in canonical form are claimed up front
+        let mut taken: HashMap<String, String> = users
+            .iter()
+            .map(|(user_id, p)| (p.clone(), user_id.clone()))
+            .collect();
+
+        for (user_id, phone_number) in &users {
+            let canonical = match canonicalize(phone_number) {
+                Some(c) => c,
+                None => {
+                    record_skipped(db, user_id, "unparseable", None).await?;
+                    continue;
+                }
+            };
+
+            if &canonical == phone_number {
+                continue;
+            }
+
+            if let Some(holder) = taken.get(&canonical) {
+                record_skipped(db, user_id, "collision", Some((&canonical, holder))).await?;
+                continue;
+            }
+
+            let hash = Sha256::digest(canonical.as_bytes()).to_vec();
+            db.execute(Statement::from_sql_and_values(
+                backend,
+                "UPDATE users SET phone_number = $1, phone_number_hash = $2, updated_at = now() WHERE user_id = $3::uuid",
+                [canonical.clone().into(), hash.into(), user_id.clone().into()],
+            ))
+            .await?;
+
+            taken.remove(phone_number);
+            taken.insert(canonical, user_id.clone());
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, _manager: &SchemaManager) -> Result<(), DbErr> {
+        // Original spellings are not recoverable; canonical numbers remain valid.
+        // The audit rows of skipped accounts stay: audit_logs is append-only.
+        Ok(())
+    }
+}
+
+/// Audit a skipped account; `collision` is the canonical number and the
+/// account already holding it
+async fn record_skipped(
+    db: &SchemaManagerConnection<'_>,
+    user_id: &str,
+    reason: &str,
+    collision: Option<(&String, &String)>,
+) -> Result<(), DbErr> {
+    let (canonical, held_by) = collision.unzip();
+    db.execute(Statement::from_sql_and_values(
+        db.get_database_backend(),
+        "INSERT INTO audit_logs (user_id, actor, action, details) \
+         SELECT $1::uuid, 'system', 'phone_number.canonicalization_skipped', \
+                jsonb_strip_nulls(jsonb_build_object( \
+                    'reason', $2::text, 'canonical', $3::text, 'held_by', $4::text))",
+        [
+            user_id.into(),
+            reason.into(),
+            canonical.cloned().into(),
+            held_by.cloned().into(),
+        ],
+    ))
+    .await?;
+    Ok(())
+}
+
+fn canonicalize(raw: &str) -> Option<String> {
+    let number = phonenumber::parse(None, raw.trim()).ok()?;
+    if !phonenumber::is_valid(&number) {
+        return None;
+    }
+    Some(number.format().mode(phonenumber::Mode::E164).to_string())
+}
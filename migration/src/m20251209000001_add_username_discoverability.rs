@@ -0,0 +1,85 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Add discoverability column (0 = everyone, 1 = contacts, 2 = nobody)
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Users::Table)
+                    .add_column(
+                        ColumnDef::new(Users::Discoverability)
+                            .small_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        let db = manager.get_connection();
+
+        // Usernames become case-insensitive. Where two accounts differ only by case,
+        // the oldest keeps the username and the others get a deterministic suffix
+        // from their user id, recorded in the audit log so support can explain it.
+        db.execute_unprepared(
+            "WITH ranked AS ( \
+                 SELECT user_id, username, row_number() OVER ( \
+                     PARTITION BY lower(username) ORDER BY created_at, user_id \
+                 ) AS rn \
+                 FROM users WHERE username IS NOT NULL \
+             ), renamed AS ( \
+                 UPDATE users u \
+                 SET username = left(r.username, 37) || '_' \
+                         || right(replace(u.user_id::text, '-', ''), 12), \
+                     updated_at = now() \
+                 FROM ranked r \
+                 WHERE u.user_id = r.user_id AND r.rn > 1 \
+                 RETURNING u.user_id, r.username AS previous, u.username AS renamed_to \
+             ) \
+             INSERT INTO audit_logs (user_id, actor, action, details) \
+             SELECT user_id, 'system', 'username.renamed', \
+                    jsonb_build_object('from', previous, 'to', renamed_to, \
+                                       'reason', 'case_insensitive_collision') \
+             FROM renamed",
+        )
+        .await?;
+
+        // Serves both case-insensitive equality and `LIKE 'prefix%'` search
+        db.execute_unprepared(
+            "CREATE UNIQUE INDEX IF NOT EXISTS idx_users_username_lower \
+             ON users (lower(username) text_pattern_ops)",
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared("DROP INDEX IF EXISTS idx_users_username_lower")
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Users::Table)
+                    .drop_column(Users::Discoverability)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Discoverability,
+}
@@ -0,0 +1,62 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Per-field visibility (0 = everyone, 1 = contacts, 2 = nobody)
+        for column in [
+            Users::DisplayNameVisibility,
+            Users::BioVisibility,
+            Users::ProfilePictureVisibility,
+            Users::BackgroundImageVisibility,
+        ] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Users::Table)
+                        .add_column(
+                            ColumnDef::new(column)
+                                .small_integer()
+                                .not_null()
+                                .default(0),
+                        )
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for column in [
+            Users::DisplayNameVisibility,
+            Users::BioVisibility,
+            Users::ProfilePictureVisibility,
+            Users::BackgroundImageVisibility,
+        ] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Users::Table)
+                        .drop_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    DisplayNameVisibility,
+    BioVisibility,
+    ProfilePictureVisibility,
+    BackgroundImageVisibility,
+}
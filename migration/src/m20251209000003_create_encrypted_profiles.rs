@@ -0,0 +1,87 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // One row per user: only the ciphertexts for the current profile key are kept
+        manager
+            .create_table(
+                Table::create()
+                    .table(EncryptedProfiles::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(EncryptedProfiles::UserId)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(EncryptedProfiles::Version).text().not_null())
+                    .col(
+                        ColumnDef::new(EncryptedProfiles::Commitment)
+                            .binary()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(EncryptedProfiles::Name).binary().not_null())
+                    .col(ColumnDef::new(EncryptedProfiles::About).binary())
+                    .col(ColumnDef::new(EncryptedProfiles::AboutEmoji).binary())
+                    .col(ColumnDef::new(EncryptedProfiles::Avatar).binary())
+                    .col(
+                        ColumnDef::new(EncryptedProfiles::RotationRequired)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .col(
+                        ColumnDef::new(EncryptedProfiles::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(EncryptedProfiles::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_encrypted_profiles_user_id")
+                            .from(EncryptedProfiles::Table, EncryptedProfiles::UserId)
+                            .to(Users::Table, Users::UserId)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(EncryptedProfiles::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum EncryptedProfiles {
+    Table,
+    UserId,
+    Version,
+    Commitment,
+    Name,
+    About,
+    AboutEmoji,
+    Avatar,
+    RotationRequired,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    UserId,
+}
@@ -0,0 +1,59 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Set once a deleted account's data has been purged; the row stays as a
+        // tombstone so anonymised messages keep a valid sender
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Users::Table)
+                    .add_column(ColumnDef::new(Users::PurgedAt).timestamp_with_time_zone().null())
+                    // Purged accounts release their number by storing a tombstone that
+                    // is longer than any E.164 number
+                    .modify_column(ColumnDef::new(Users::PhoneNumber).string_len(64).not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        // The purge job scans for accounts whose grace period has ended
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "CREATE INDEX IF NOT EXISTS idx_users_pending_purge \
+                 ON users (deleted_at) WHERE is_deleted AND purged_at IS NULL",
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared("DROP INDEX IF EXISTS idx_users_pending_purge")
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Users::Table)
+                    .drop_column(Users::PurgedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    PhoneNumber,
+    PurgedAt,
+}
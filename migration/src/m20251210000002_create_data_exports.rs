@@ -0,0 +1,88 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Personal data exports, built in the background and kept until they expire
+        manager
+            .create_table(
+                Table::create()
+                    .table(DataExports::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(DataExports::ExportId)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(DataExports::UserId).uuid().not_null())
+                    // 1 = pending, 2 = ready, 3 = failed, 4 = expired
+                    .col(
+                        ColumnDef::new(DataExports::Status)
+                            .small_integer()
+                            .not_null()
+                            .default(1),
+                    )
+                    .col(ColumnDef::new(DataExports::BlobKey).text())
+                    .col(ColumnDef::new(DataExports::SizeBytes).big_integer())
+                    .col(
+                        ColumnDef::new(DataExports::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(ColumnDef::new(DataExports::CompletedAt).timestamp_with_time_zone())
+                    .col(ColumnDef::new(DataExports::ExpiresAt).timestamp_with_time_zone())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_data_exports_user_id")
+                            .from(DataExports::Table, DataExports::UserId)
+                            .to(Users::Table, Users::UserId)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_data_exports_user_id")
+                    .table(DataExports::Table)
+                    .col(DataExports::UserId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(DataExports::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum DataExports {
+    Table,
+    ExportId,
+    UserId,
+    Status,
+    BlobKey,
+    SizeBytes,
+    CreatedAt,
+    CompletedAt,
+    ExpiresAt,
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    UserId,
+}
@@ -0,0 +1,79 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Blocks::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(Blocks::BlockerId).uuid().not_null())
+                    .col(ColumnDef::new(Blocks::BlockedId).uuid().not_null())
+                    .col(
+                        ColumnDef::new(Blocks::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(Blocks::BlockerId)
+                            .col(Blocks::BlockedId),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_blocks_blocker_id")
+                            .from(Blocks::Table, Blocks::BlockerId)
+                            .to(Users::Table, Users::UserId)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_blocks_blocked_id")
+                            .from(Blocks::Table, Blocks::BlockedId)
+                            .to(Users::Table, Users::UserId)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Message delivery asks "who has blocked this sender"
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_blocks_blocked_id")
+                    .table(Blocks::Table)
+                    .col(Blocks::BlockedId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Blocks::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Blocks {
+    Table,
+    BlockerId,
+    BlockedId,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    UserId,
+}
@@ -0,0 +1,60 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 0 = accepted, 1 = pending message request, 2 = declined.
+        // Existing memberships predate requests and count as accepted.
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ConvMembers::Table)
+                    .add_column(
+                        ColumnDef::new(ConvMembers::RequestStatus)
+                            .small_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // The request inbox lists a user's pending memberships
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "CREATE INDEX IF NOT EXISTS idx_conv_members_pending_requests \
+                 ON conv_members (user_id) WHERE request_status = 1",
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared("DROP INDEX IF EXISTS idx_conv_members_pending_requests")
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ConvMembers::Table)
+                    .drop_column(ConvMembers::RequestStatus)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum ConvMembers {
+    Table,
+    RequestStatus,
+}
@@ -0,0 +1,53 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Operator accounts for the admin API, kept apart from app users
+        manager
+            .create_table(
+                Table::create()
+                    .table(Admins::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Admins::AdminId)
+                            .uuid()
+                            .not_null()
+                            .primary_key()
+                            .extra("DEFAULT gen_random_uuid()"),
+                    )
+                    .col(ColumnDef::new(Admins::Username).string_len(64).not_null().unique_key())
+                    .col(ColumnDef::new(Admins::PasswordHash).text().not_null())
+                    .col(ColumnDef::new(Admins::IsActive).boolean().not_null().default(true))
+                    .col(
+                        ColumnDef::new(Admins::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(ColumnDef::new(Admins::LastLoginAt).timestamp_with_time_zone().null())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Admins::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Admins {
+    Table,
+    AdminId,
+    Username,
+    PasswordHash,
+    IsActive,
+    CreatedAt,
+    LastLoginAt,
+}
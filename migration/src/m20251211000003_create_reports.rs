@@ -0,0 +1,222 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // reason: 1 = spam, 2 = harassment, 3 = impersonation, 4 = illegal content, 5 = other
+        // status: 1 = open, 2 = actioned, 3 = dismissed
+        manager
+            .create_table(
+                Table::create()
+                    .table(Reports::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Reports::ReportId)
+                            .uuid()
+                            .not_null()
+                            .primary_key()
+                            .extra("DEFAULT gen_random_uuid()"),
+                    )
+                    .col(ColumnDef::new(Reports::ReporterId).uuid().not_null())
+                    .col(ColumnDef::new(Reports::ReportedUserId).uuid().null())
+                    .col(ColumnDef::new(Reports::ConversationId).uuid().null())
+                    .col(ColumnDef::new(Reports::Reason).small_integer().not_null())
+                    .col(ColumnDef::new(Reports::Comment).text().null())
+                    // Decrypted messages the reporter chose to forward
+                    .col(
+                        ColumnDef::new(Reports::Evidence)
+                            .json_binary()
+                            .not_null()
+                            .default(Expr::cust("'[]'::jsonb")),
+                    )
+                    .col(ColumnDef::new(Reports::Status).small_integer().not_null().default(1))
+                    .col(ColumnDef::new(Reports::ResolvedBy).uuid().null())
+                    .col(ColumnDef::new(Reports::ResolvedAt).timestamp_with_time_zone().null())
+                    .col(ColumnDef::new(Reports::ResolutionNote).text().null())
+                    .col(
+                        ColumnDef::new(Reports::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_reports_reporter_id")
+                            .from(Reports::Table, Reports::ReporterId)
+                            .to(Users::Table, Users::UserId)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_reports_reported_user_id")
+                            .from(Reports::Table, Reports::ReportedUserId)
+                            .to(Users::Table, Users::UserId)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_reports_conversation_id")
+                            .from(Reports::Table, Reports::ConversationId)
+                            .to(Conversations::Table, Conversations::ConvId)
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_reports_resolved_by")
+                            .from(Reports::Table, Reports::ResolvedBy)
+                            .to(Admins::Table, Admins::AdminId)
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // The moderation queue lists open reports oldest first
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_reports_status_created_at")
+                    .table(Reports::Table)
+                    .col(Reports::Status)
+                    .col(Reports::CreatedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_reports_reported_user_id")
+                    .table(Reports::Table)
+                    .col(Reports::ReportedUserId)
+                    .to_owned(),
+            )
+            .await?;
+
+        // action: 1 = warn, 2 = suspend, 3 = ban
+        manager
+            .create_table(
+                Table::create()
+                    .table(ModerationActions::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ModerationActions::ActionId)
+                            .uuid()
+                            .not_null()
+                            .primary_key()
+                            .extra("DEFAULT gen_random_uuid()"),
+                    )
+                    .col(ColumnDef::new(ModerationActions::UserId).uuid().not_null())
+                    .col(ColumnDef::new(ModerationActions::AdminId).uuid().null())
+                    .col(ColumnDef::new(ModerationActions::ReportId).uuid().null())
+                    .col(ColumnDef::new(ModerationActions::Action).small_integer().not_null())
+                    .col(ColumnDef::new(ModerationActions::Reason).text().null())
+                    .col(ColumnDef::new(ModerationActions::ExpiresAt).timestamp_with_time_zone().null())
+                    .col(
+                        ColumnDef::new(ModerationActions::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_moderation_actions_user_id")
+                            .from(ModerationActions::Table, ModerationActions::UserId)
+                            .to(Users::Table, Users::UserId)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_moderation_actions_admin_id")
+                            .from(ModerationActions::Table, ModerationActions::AdminId)
+                            .to(Admins::Table, Admins::AdminId)
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_moderation_actions_report_id")
+                            .from(ModerationActions::Table, ModerationActions::ReportId)
+                            .to(Reports::Table, Reports::ReportId)
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_moderation_actions_user_id")
+                    .table(ModerationActions::Table)
+                    .col(ModerationActions::UserId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ModerationActions::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(Reports::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Reports {
+    Table,
+    ReportId,
+    ReporterId,
+    ReportedUserId,
+    ConversationId,
+    Reason,
+    Comment,
+    Evidence,
+    Status,
+    ResolvedBy,
+    ResolvedAt,
+    ResolutionNote,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum ModerationActions {
+    Table,
+    ActionId,
+    UserId,
+    AdminId,
+    ReportId,
+    Action,
+    Reason,
+    ExpiresAt,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    UserId,
+}
+
+#[derive(DeriveIden)]
+enum Conversations {
+    Table,
+    ConvId,
+}
+
+#[derive(DeriveIden)]
+enum Admins {
+    Table,
+    AdminId,
+}
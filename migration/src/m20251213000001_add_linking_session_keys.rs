@@ -0,0 +1,36 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Public keys uploaded by the new device, held until the primary approves
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(DeviceLinkingSessions::Table)
+                    .add_column(ColumnDef::new(DeviceLinkingSessions::NewDeviceKeys).json_binary().null())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(DeviceLinkingSessions::Table)
+                    .drop_column(DeviceLinkingSessions::NewDeviceKeys)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum DeviceLinkingSessions {
+    Table,
+    NewDeviceKeys,
+}
@@ -0,0 +1,95 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // When the current signed prekey was uploaded, and the key it replaced,
+        // kept until the grace period ends
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Devices::Table)
+                    .add_column(ColumnDef::new(Devices::SignedPrekeyCreatedAt).timestamp_with_time_zone().null())
+                    .add_column(ColumnDef::new(Devices::PreviousSignedPrekeyId).integer().null())
+                    .add_column(ColumnDef::new(Devices::PreviousSignedPrekeyPublic).binary().null())
+                    .add_column(ColumnDef::new(Devices::PreviousSignedPrekeySignature).binary().null())
+                    .add_column(
+                        ColumnDef::new(Devices::PreviousSignedPrekeyExpiresAt)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Existing keys date from device registration
+        manager
+            .get_connection()
+            .execute_unprepared("UPDATE devices SET signed_prekey_created_at = created_at")
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Devices::Table)
+                    .modify_column(
+                        ColumnDef::new(Devices::SignedPrekeyCreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Admin metrics look for devices that have not rotated in a while
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_devices_active_signed_prekey_created")
+                    .table(Devices::Table)
+                    .col(Devices::SignedPrekeyCreatedAt)
+                    .and_where(Expr::col(Devices::IsActive).eq(true))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_devices_active_signed_prekey_created")
+                    .table(Devices::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Devices::Table)
+                    .drop_column(Devices::SignedPrekeyCreatedAt)
+                    .drop_column(Devices::PreviousSignedPrekeyId)
+                    .drop_column(Devices::PreviousSignedPrekeyPublic)
+                    .drop_column(Devices::PreviousSignedPrekeySignature)
+                    .drop_column(Devices::PreviousSignedPrekeyExpiresAt)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Devices {
+    Table,
+    IsActive,
+    SignedPrekeyCreatedAt,
+    PreviousSignedPrekeyId,
+    PreviousSignedPrekeyPublic,
+    PreviousSignedPrekeySignature,
+    PreviousSignedPrekeyExpiresAt,
+}
@@ -0,0 +1,87 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Signed ML-KEM-1024 prekeys for PQXDH. One-time keys are deleted when
+        // claimed; the last-resort key is handed out once those run out.
+        manager
+            .create_table(
+                Table::create()
+                    .table(KyberPrekeys::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(KyberPrekeys::DeviceId).big_integer().not_null())
+                    .col(ColumnDef::new(KyberPrekeys::PrekeyId).integer().not_null())
+                    .col(ColumnDef::new(KyberPrekeys::PublicKey).binary().not_null())
+                    .col(ColumnDef::new(KyberPrekeys::Signature).binary().not_null())
+                    .col(
+                        ColumnDef::new(KyberPrekeys::IsLastResort)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .col(
+                        ColumnDef::new(KyberPrekeys::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(KyberPrekeys::DeviceId)
+                            .col(KyberPrekeys::PrekeyId),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_kyber_prekeys_device_id")
+                            .from(KyberPrekeys::Table, KyberPrekeys::DeviceId)
+                            .to(Devices::Table, Devices::DeviceId)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // At most one last-resort key per device
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_kyber_prekeys_last_resort")
+                    .table(KyberPrekeys::Table)
+                    .col(KyberPrekeys::DeviceId)
+                    .unique()
+                    .and_where(Expr::col(KyberPrekeys::IsLastResort).eq(true))
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(KyberPrekeys::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum KyberPrekeys {
+    Table,
+    DeviceId,
+    PrekeyId,
+    PublicKey,
+    Signature,
+    IsLastResort,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Devices {
+    Table,
+    DeviceId,
+}
@@ -0,0 +1,90 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Every identity key a user's devices have registered. Device rows are
+        // replaced on re-registration, so there is no foreign key to devices.
+        manager
+            .create_table(
+                Table::create()
+                    .table(IdentityKeyHistory::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(IdentityKeyHistory::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(IdentityKeyHistory::UserId).uuid().not_null())
+                    .col(ColumnDef::new(IdentityKeyHistory::DeviceId).big_integer().not_null())
+                    .col(ColumnDef::new(IdentityKeyHistory::IdentityKey).binary().not_null())
+                    .col(ColumnDef::new(IdentityKeyHistory::PreviousIdentityKey).binary())
+                    .col(
+                        ColumnDef::new(IdentityKeyHistory::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_identity_key_history_user_id")
+                            .from(IdentityKeyHistory::Table, IdentityKeyHistory::UserId)
+                            .to(Users::Table, Users::UserId)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_identity_key_history_user_id")
+                    .table(IdentityKeyHistory::Table)
+                    .col(IdentityKeyHistory::UserId)
+                    .col(IdentityKeyHistory::CreatedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        // Start the history with the keys devices already have
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "INSERT INTO identity_key_history (user_id, device_id, identity_key, created_at)
+                 SELECT user_id, device_id, identity_key_public, created_at FROM devices",
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(IdentityKeyHistory::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum IdentityKeyHistory {
+    Table,
+    Id,
+    UserId,
+    DeviceId,
+    IdentityKey,
+    PreviousIdentityKey,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    UserId,
+}
@@ -0,0 +1,68 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Writers pass the version they read so concurrent updates from the
+        // same device are detected instead of silently overwritten
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(SignalSessions::Table)
+                    .add_column(
+                        ColumnDef::new(SignalSessions::Version)
+                            .big_integer()
+                            .not_null()
+                            .default(1),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .get_connection()
+            .execute_unprepared("UPDATE signal_sessions SET updated_at = now() WHERE updated_at IS NULL")
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(SignalSessions::Table)
+                    .modify_column(
+                        ColumnDef::new(SignalSessions::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(SignalSessions::Table)
+                    .drop_column(SignalSessions::Version)
+                    .modify_column(
+                        ColumnDef::new(SignalSessions::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum SignalSessions {
+    Table,
+    UpdatedAt,
+    Version,
+}
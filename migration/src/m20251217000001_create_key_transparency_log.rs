@@ -0,0 +1,202 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Every (user, device, identity key) binding, in log order. Bindings
+        // outlive their devices, so there are no foreign keys.
+        manager
+            .create_table(
+                Table::create()
+                    .table(KeyTransparencyLeaves::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(KeyTransparencyLeaves::LeafIndex)
+                            .big_integer()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(KeyTransparencyLeaves::UserId).uuid().not_null())
+                    .col(ColumnDef::new(KeyTransparencyLeaves::DeviceId).big_integer().not_null())
+                    .col(ColumnDef::new(KeyTransparencyLeaves::IdentityKey).binary().not_null())
+                    .col(
+                        ColumnDef::new(KeyTransparencyLeaves::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_key_transparency_leaves_device_id")
+                    .table(KeyTransparencyLeaves::Table)
+                    .col(KeyTransparencyLeaves::DeviceId)
+                    .to_owned(),
+            )
+            .await?;
+
+        // Hashes of the perfect subtrees, level 0 being the leaves. Any root
+        // or proof is assembled from O(log n) of these.
+        manager
+            .create_table(
+                Table::create()
+                    .table(KeyTransparencyNodes::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(KeyTransparencyNodes::Level).small_integer().not_null())
+                    .col(ColumnDef::new(KeyTransparencyNodes::NodeIndex).big_integer().not_null())
+                    .col(ColumnDef::new(KeyTransparencyNodes::Hash).binary().not_null())
+                    .primary_key(
+                        Index::create()
+                            .col(KeyTransparencyNodes::Level)
+                            .col(KeyTransparencyNodes::NodeIndex),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(KeyTransparencyTreeHeads::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(KeyTransparencyTreeHeads::TreeSize)
+                            .big_integer()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(KeyTransparencyTreeHeads::RootHash).binary().not_null())
+                    .col(ColumnDef::new(KeyTransparencyTreeHeads::TimestampMs).big_integer().not_null())
+                    .col(ColumnDef::new(KeyTransparencyTreeHeads::Signature).binary().not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        // Start the log with the keys active devices already have, then hash
+        // the leaves (user_id || device_id || 0x05 || key) and every perfect
+        // subtree above them
+        manager
+            .get_connection()
+            .execute_unprepared(
+                r#"
+INSERT INTO key_transparency_leaves (leaf_index, user_id, device_id, identity_key, created_at)
+SELECT row_number() OVER (ORDER BY created_at, device_id) - 1,
+       user_id, device_id, identity_key_public, created_at
+FROM devices
+WHERE is_active;
+
+INSERT INTO key_transparency_nodes (level, node_index, hash)
+SELECT 0, leaf_index,
+       sha256('\x00'::bytea || uuid_send(user_id) || int8send(device_id) || '\x05'::bytea || identity_key)
+FROM key_transparency_leaves;
+
+DO $$
+DECLARE
+    lvl smallint := 1;
+BEGIN
+    LOOP
+        INSERT INTO key_transparency_nodes (level, node_index, hash)
+        SELECT lvl, l.node_index / 2, sha256('\x01'::bytea || l.hash || r.hash)
+        FROM key_transparency_nodes l
+        JOIN key_transparency_nodes r
+          ON r.level = l.level AND r.node_index = l.node_index + 1
+        WHERE l.level = lvl - 1 AND l.node_index % 2 = 0;
+        EXIT WHEN NOT FOUND;
+        lvl := lvl + 1;
+    END LOOP;
+END $$;
+"#,
+            )
+            .await?;
+
+        // The log is append-only, like audit_logs
+        manager
+            .get_connection()
+            .execute_unprepared(
+                r#"
+CREATE OR REPLACE FUNCTION key_transparency_reject_change() RETURNS trigger AS $$
+BEGIN
+    RAISE EXCEPTION '% is append-only (% rejected)', TG_TABLE_NAME, TG_OP;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER key_transparency_leaves_no_update_delete
+    BEFORE UPDATE OR DELETE ON key_transparency_leaves
+    FOR EACH ROW EXECUTE FUNCTION key_transparency_reject_change();
+CREATE TRIGGER key_transparency_leaves_no_truncate
+    BEFORE TRUNCATE ON key_transparency_leaves
+    FOR EACH STATEMENT EXECUTE FUNCTION key_transparency_reject_change();
+
+CREATE TRIGGER key_transparency_nodes_no_update_delete
+    BEFORE UPDATE OR DELETE ON key_transparency_nodes
+    FOR EACH ROW EXECUTE FUNCTION key_transparency_reject_change();
+CREATE TRIGGER key_transparency_nodes_no_truncate
+    BEFORE TRUNCATE ON key_transparency_nodes
+    FOR EACH STATEMENT EXECUTE FUNCTION key_transparency_reject_change();
+
+CREATE TRIGGER key_transparency_tree_heads_no_update_delete
+    BEFORE UPDATE OR DELETE ON key_transparency_tree_heads
+    FOR EACH ROW EXECUTE FUNCTION key_transparency_reject_change();
+CREATE TRIGGER key_transparency_tree_heads_no_truncate
+    BEFORE TRUNCATE ON key_transparency_tree_heads
+    FOR EACH STATEMENT EXECUTE FUNCTION key_transparency_reject_change();
+"#,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(KeyTransparencyTreeHeads::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(KeyTransparencyNodes::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(KeyTransparencyLeaves::Table).to_owned())
+            .await?;
+        manager
+            .get_connection()
+            .execute_unprepared("DROP FUNCTION IF EXISTS key_transparency_reject_change()")
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum KeyTransparencyLeaves {
+    Table,
+    LeafIndex,
+    UserId,
+    DeviceId,
+    IdentityKey,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum KeyTransparencyNodes {
+    Table,
+    Level,
+    NodeIndex,
+    Hash,
+}
+
+#[derive(DeriveIden)]
+enum KeyTransparencyTreeHeads {
+    Table,
+    TreeSize,
+    RootHash,
+    TimestampMs,
+    Signature,
+}
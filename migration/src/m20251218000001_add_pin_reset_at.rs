@@ -0,0 +1,59 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // When a pending forgot-PIN reset may complete. Kept apart from
+        // registration_lock_expires_at so the two cannot cancel each other.
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Users::Table)
+                    .add_column(ColumnDef::new(Users::PinResetAt).timestamp_with_time_zone().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        // Pending resets were stored in registration_lock_expires_at until now
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "UPDATE users
+                 SET pin_reset_at = registration_lock_expires_at,
+                     registration_lock_expires_at = NULL
+                 WHERE registration_lock_expires_at IS NOT NULL",
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "UPDATE users
+                 SET registration_lock_expires_at = pin_reset_at
+                 WHERE pin_reset_at IS NOT NULL",
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Users::Table)
+                    .drop_column(Users::PinResetAt)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    PinResetAt,
+}
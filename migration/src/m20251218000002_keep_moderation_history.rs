@@ -0,0 +1,73 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Reports outlive their reporter; purging an account anonymises them
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "ALTER TABLE reports
+                 ALTER COLUMN reporter_id DROP NOT NULL,
+                 DROP CONSTRAINT fk_reports_reporter_id,
+                 ADD CONSTRAINT fk_reports_reporter_id FOREIGN KEY (reporter_id)
+                     REFERENCES users (user_id) ON DELETE SET NULL",
+            )
+            .await?;
+
+        // Numbers of purged banned accounts, by SHA-256 of the E.164 form.
+        // The tombstone frees the number, so this is what stops re-registration.
+        manager
+            .create_table(
+                Table::create()
+                    .table(BannedPhoneNumbers::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(BannedPhoneNumbers::PhoneHash)
+                            .binary()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(BannedPhoneNumbers::UserId).uuid().not_null())
+                    .col(
+                        ColumnDef::new(BannedPhoneNumbers::BannedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(BannedPhoneNumbers::Table).to_owned())
+            .await?;
+
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "DELETE FROM reports WHERE reporter_id IS NULL;
+                 ALTER TABLE reports
+                 ALTER COLUMN reporter_id SET NOT NULL,
+                 DROP CONSTRAINT fk_reports_reporter_id,
+                 ADD CONSTRAINT fk_reports_reporter_id FOREIGN KEY (reporter_id)
+                     REFERENCES users (user_id) ON DELETE CASCADE",
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum BannedPhoneNumbers {
+    Table,
+    PhoneHash,
+    UserId,
+    BannedAt,
+}